@@ -1,6 +1,59 @@
 import test from "node:test";
 import assert from "node:assert";
-import { extractTextAndImages, extractText } from "../index.js";
+import {
+  extractTextAndImages,
+  extractTextAndImagesWithOptions,
+  extractText,
+  extractTextWithOptions,
+  renderPages,
+  renderDocument,
+  renderTiles,
+  comparePages,
+  imagesToPdf,
+  createPdf,
+  makeSearchable,
+  optimizePdf,
+  renderRegion,
+  extractWords,
+  extractChars,
+  extractTables,
+  extractLinks,
+  extractAnnotations,
+  extractFormFields,
+  fillForm,
+  extractBookmarks,
+  getDocumentMetadata,
+  getXmpMetadata,
+  getPageCount,
+  getPageDimensions,
+  extractAttachments,
+  extractSignatures,
+  isLinearized,
+  getSecurityInfo,
+  splitPdf,
+  mergePdfs,
+  rotatePages,
+  removePages,
+  reorderPages,
+  stampPages,
+  redactText,
+  searchText,
+  exportHocr,
+  exportAlto,
+  convertToMarkdown,
+  exportHtml,
+  extractLayout,
+  initPdfium,
+  setLogger,
+  extractPage,
+  openDocument,
+  extractFonts,
+  listFonts,
+  getTextStats,
+  extractNamedDestinations,
+  extractStructureTree,
+  checkAccessibility,
+} from "../index.js";
 import path from "path";
 import fs from "node:fs/promises";
 import { tmpdir } from "os";
@@ -13,6 +66,64 @@ const pdfPath2 = path.join(
   import.meta.dirname,
   "./pdf-test-without-images.pdf"
 );
+const headersPdfPath = path.join(import.meta.dirname, "./pdf-test-headers.pdf");
+const annotationsPdfPath = path.join(
+  import.meta.dirname,
+  "./pdf-test-annotations.pdf"
+);
+const documentPdfPath = path.join(
+  import.meta.dirname,
+  "./pdf-test-document.pdf"
+);
+const signedPdfPath = path.join(import.meta.dirname, "./pdf-test-signed.pdf");
+const formPdfPath = path.join(import.meta.dirname, "./pdf-test-form.pdf");
+const twoColumnsPdfPath = path.join(
+  import.meta.dirname,
+  "./pdf-test-two-columns.pdf"
+);
+const rtlPdfPath = path.join(import.meta.dirname, "./pdf-test-rtl.pdf");
+const verticalPdfPath = path.join(
+  import.meta.dirname,
+  "./pdf-test-vertical.pdf"
+);
+const ligaturesPdfPath = path.join(
+  import.meta.dirname,
+  "./pdf-test-ligatures.pdf"
+);
+const hyphensPdfPath = path.join(import.meta.dirname, "./pdf-test-hyphens.pdf");
+const shadowPdfPath = path.join(import.meta.dirname, "./pdf-test-shadow.pdf");
+const glyphsPdfPath = path.join(import.meta.dirname, "./pdf-test-glyphs.pdf");
+const densePdfPath = path.join(import.meta.dirname, "./pdf-test-dense.pdf");
+const captionPdfPath = path.join(import.meta.dirname, "./pdf-test-caption.pdf");
+const cmykPdfPath = path.join(import.meta.dirname, "./pdf-test-cmyk.pdf");
+const formTextPdfPath = path.join(
+  import.meta.dirname,
+  "./pdf-test-form-text.pdf"
+);
+const smaskPdfPath = path.join(import.meta.dirname, "./pdf-test-smask.pdf");
+const grayDecodePdfPath = path.join(
+  import.meta.dirname,
+  "./pdf-test-gray-decode.pdf"
+);
+const inlineImagesPdfPath = path.join(
+  import.meta.dirname,
+  "./pdf-test-inline-images.pdf"
+);
+const transformPdfPath = path.join(import.meta.dirname, "./pdf-test-transform.pdf");
+const recurringPdfPath = path.join(import.meta.dirname, "./pdf-test-recurring.pdf");
+const badPagePdfPath = path.join(import.meta.dirname, "./pdf-test-bad-page.pdf");
+const protectedPdfPath = path.join(import.meta.dirname, "./pdf-test-protected.pdf");
+const labelsPdfPath = path.join(import.meta.dirname, "./pdf-test-labels.pdf");
+const destinationsPdfPath = path.join(
+  import.meta.dirname,
+  "./pdf-test-destinations.pdf"
+);
+const taggedPdfPath = path.join(import.meta.dirname, "./pdf-test-tagged.pdf");
+const scannedPdfPath = path.join(import.meta.dirname, "./pdf-test-scanned.pdf");
+const objectStreamsPdfPath = path.join(
+  import.meta.dirname,
+  "./pdf-test-object-streams.pdf",
+);
 
 const imagesFolderPath = path.join(tmpdir(), randomUUID());
 if (!existsSync(imagesFolderPath)) {
@@ -37,9 +148,18 @@ test("should extract images and text from pdf", async () => {
           filename: "image-1.png",
           relatedText: ["1. How to program", "AA-FFF222 - AY"],
           fileSizeBytes: 43119,
+          pageIndex: 0,
+          left: 242.98599243164062,
+          top: 770.031005859375,
+          right: 344.21099853515625,
+          bottom: 653.8109741210938,
+          width: 135,
+          height: 155,
+          dpi: 96.02371100538953,
         },
       ],
       pageTextLines: ["1. How to program", "AA-FFF222 - AY", "TSCode V1.2"],
+      isScanned: false,
     },
     {
       pageImages: [],
@@ -55,6 +175,7 @@ test("should extract images and text from pdf", async () => {
         "7. G Keyboard",
         "8. H Browser",
       ],
+      isScanned: false,
     },
     {
       pageImages: [
@@ -65,6 +186,14 @@ test("should extract images and text from pdf", async () => {
             "is a treatise on the theory of ethics, very popular during the Renaissance.",
           ],
           fileSizeBytes: 260899,
+          pageIndex: 2,
+          left: 62.986000061035156,
+          top: 313.9930114746094,
+          right: 500.2019958496094,
+          bottom: 86.03099822998047,
+          width: 583,
+          height: 304,
+          dpi: 96.00746466700552,
         },
       ],
       pageTextLines: [
@@ -85,6 +214,7 @@ test("should extract images and text from pdf", async () => {
         "is a treatise on the theory of ethics, very popular during the Renaissance.",
         "See animal below.",
       ],
+      isScanned: false,
     },
     {
       pageImages: [
@@ -92,11 +222,27 @@ test("should extract images and text from pdf", async () => {
           filename: "image-3.png",
           relatedText: ["Probably this is electricity:", "What is it?"],
           fileSizeBytes: 92681,
+          pageIndex: 3,
+          left: 57.03300094604492,
+          top: 635.0460205078125,
+          right: 170.9860076904297,
+          bottom: 541.3040161132812,
+          width: 220,
+          height: 181,
+          dpi: 139.0046737932283,
         },
         {
           filename: "image-4.png",
           relatedText: ["Here is a key", "Some text here and there"],
           fileSizeBytes: 368679,
+          pageIndex: 3,
+          left: 59.41400146484375,
+          top: 403.7960205078125,
+          right: 486,
+          bottom: 239.04600524902344,
+          width: 800,
+          height: 309,
+          dpi: 135.0255287275984,
         },
       ],
       pageTextLines: [
@@ -105,6 +251,7 @@ test("should extract images and text from pdf", async () => {
         "Some text here and there",
         "Here is a key",
       ],
+      isScanned: false,
     },
   ]);
 
@@ -158,6 +305,2103 @@ test("should extract text from pdf with no images", async () => {
   ]);
 });
 
+test("should extract text and images with options", async () => {
+  const optionsImagesFolderPath = path.join(tmpdir(), randomUUID());
+  const res = await extractTextAndImagesWithOptions(pdfium_dirname, pdfPath, {
+    imagesFolderPath: optionsImagesFolderPath,
+  });
+
+  assert.deepEqual(
+    res,
+    await extractTextAndImages(pdfium_dirname, pdfPath, imagesFolderPath)
+  );
+});
+
+test("should return images as buffers", async () => {
+  const res = await extractTextAndImagesWithOptions(pdfium_dirname, pdfPath, {
+    imagesAsBuffers: true,
+  });
+  const image = res[0].pageImages[0];
+
+  assert.equal(image.filename, "image-1.png");
+  assert.equal(image.data.length, image.fileSizeBytes);
+  assert.deepEqual(
+    image.data,
+    readFileSync(`${imagesFolderPath}/image-1.png`)
+  );
+});
+
+test("should encode images in requested format", async () => {
+  const res = await extractTextAndImagesWithOptions(pdfium_dirname, pdfPath, {
+    imagesAsBuffers: true,
+    imageFormat: "jpeg",
+    imageQuality: 60,
+  });
+  const image = res[0].pageImages[0];
+
+  assert.equal(image.filename, "image-1.jpg");
+  assert.deepEqual([...image.data.subarray(0, 3)], [0xff, 0xd8, 0xff]);
+});
+
+test("should render images in requested DPI", async () => {
+  const res = await extractTextAndImagesWithOptions(pdfium_dirname, pdfPath, {
+    imagesAsBuffers: true,
+    imageDpi: 144,
+  });
+
+  // PNG width and height from IHDR chunk
+  assert.deepEqual(
+    res.map((page) =>
+      page.pageImages.map((image) => [
+        image.data.readUInt32BE(16),
+        image.data.readUInt32BE(20),
+      ])
+    ),
+    [[[201, 231]], [], [[873, 456]], [[228, 187], [853, 330]]]
+  );
+  assert.deepEqual(
+    res[2].pageImages[0].relatedText,
+    (await extractTextAndImages(pdfium_dirname, pdfPath, imagesFolderPath))[2]
+      .pageImages[0].relatedText
+  );
+});
+
+test("should skip images smaller than required", async () => {
+  const res = await extractTextAndImagesWithOptions(pdfium_dirname, pdfPath, {
+    imagesAsBuffers: true,
+    minFileSizeBytes: 100000,
+  });
+
+  assert.deepEqual(
+    res.map((page) => page.pageImages.map((image) => image.fileSizeBytes)),
+    [[], [], [260899], [368679]]
+  );
+});
+
+test("should export vector graphics as svg", async () => {
+  const res = await extractTextAndImagesWithOptions(pdfium_dirname, pdfPath, {
+    imagesAsBuffers: true,
+    exportVectorGraphics: true,
+  });
+
+  assert.deepEqual(
+    res.map((page) => page.pageVectorGraphics.map((svg) => svg.filename)),
+    [["vector-1.svg"], ["vector-2.svg"], ["vector-3.svg"], ["vector-4.svg"]]
+  );
+  const svg = res[1].pageVectorGraphics[0].data.toString();
+  assert.ok(svg.startsWith('<svg xmlns="http://www.w3.org/2000/svg"'));
+  assert.ok(svg.includes("<path d=\"M"));
+});
+
+test("should report extraction progress", async () => {
+  const progress = [];
+  await extractTextAndImagesWithOptions(pdfium_dirname, pdfPath, {
+    imagesFolderPath: path.join(tmpdir(), randomUUID()),
+    onProgress: (p) => progress.push(p),
+  });
+  await new Promise((resolve) => setImmediate(resolve));
+
+  assert.deepEqual(progress, [
+    { pageIndex: 0, totalPages: 4, imagesExtracted: 1 },
+    { pageIndex: 1, totalPages: 4, imagesExtracted: 1 },
+    { pageIndex: 2, totalPages: 4, imagesExtracted: 2 },
+    { pageIndex: 3, totalPages: 4, imagesExtracted: 4 },
+  ]);
+});
+
+test("should stop extraction when aborted", async () => {
+  const controller = new AbortController();
+  controller.abort();
+
+  await assert.rejects(
+    extractTextWithOptions(pdfium_dirname, pdfPath, {
+      signal: controller.signal,
+    }),
+    { message: "CANCELLED: Extraction aborted" }
+  );
+});
+
+test("should extract text with options", async () => {
+  const res = await extractTextWithOptions(pdfium_dirname, pdfPath);
+
+  assert.deepEqual(res, await extractText(pdfium_dirname, pdfPath));
+});
+
+test("should render selected pages", async () => {
+  const outputFolderPath = path.join(tmpdir(), randomUUID());
+  const res = await renderPages(pdfium_dirname, pdfPath, {
+    pages: [1, 3],
+    dpi: 72,
+    outputFolderPath,
+  });
+
+  assert.deepEqual(
+    res.map(({ pageIndex, filename, width, height }) => ({
+      pageIndex,
+      filename,
+      width,
+      height,
+    })),
+    [
+      { pageIndex: 1, filename: "page-2.png", width: 596, height: 842 },
+      { pageIndex: 3, filename: "page-4.png", width: 596, height: 842 },
+    ]
+  );
+  assert.ok(existsSync(path.join(outputFolderPath, "page-4.png")));
+});
+
+test("should render page region", async () => {
+  const res = await renderRegion(
+    pdfium_dirname,
+    pdfPath,
+    3,
+    { left: 100, bottom: 400, right: 300, top: 700 },
+    { dpi: 144, asBuffers: true }
+  );
+
+  assert.equal(res.filename, "page-4-region-100-400-300-700.png");
+  assert.equal(res.width, 400);
+  assert.equal(res.height, 600);
+  assert.equal(res.data.length, res.fileSizeBytes);
+});
+
+test("should extract words with bounding boxes", async () => {
+  const res = await extractWords(pdfium_dirname, pdfPath);
+
+  assert.deepEqual(
+    res.slice(0, 3).map(({ text, pageIndex }) => ({ text, pageIndex })),
+    [
+      { text: "1.How", pageIndex: 0 },
+      { text: "to", pageIndex: 0 },
+      { text: "program", pageIndex: 0 },
+    ]
+  );
+  for (const word of res) {
+    assert.ok(word.left < word.right && word.bottom < word.top);
+  }
+  // words of the same line share vertical position and follow from left to right
+  assert.equal(res[0].top, res[1].top);
+  assert.ok(res[0].right < res[1].left);
+});
+
+test("should extract page characters", async () => {
+  const res = await extractChars(pdfium_dirname, pdfPath, 0);
+
+  assert.equal(
+    res
+      .slice(0, 6)
+      .map(({ text }) => text)
+      .join(""),
+    "1.How "
+  );
+  assert.equal(Math.round(res[0].fontSize), 40);
+  assert.equal(res[0].rotation, 0);
+});
+
+test("should return text line bounds", async () => {
+  const res = await extractTextAndImagesWithOptions(pdfium_dirname, pdfPath, {
+    imagesAsBuffers: true,
+    includeTextLineBounds: true,
+  });
+
+  for (const page of res) {
+    assert.deepEqual(
+      page.pageTextLineBounds.map(({ text }) => text),
+      page.pageTextLines
+    );
+  }
+  const [first, second] = res[0].pageTextLineBounds;
+  assert.equal(first.pageIndex, 0);
+  assert.ok(first.left < first.right && first.bottom < first.top);
+  // lines follow from top to bottom
+  assert.ok(second.top < first.bottom);
+});
+
+test("should merge text lines into paragraphs", async () => {
+  const res = await extractTextAndImagesWithOptions(pdfium_dirname, pdfPath, {
+    imagesAsBuffers: true,
+    detectParagraphs: true,
+  });
+
+  const paragraphs = res[2].pageParagraphs;
+  assert.equal(paragraphs.length, 7);
+  assert.equal(paragraphs[0], "What is Lorem Ipsum?");
+  assert.ok(paragraphs[2].startsWith("Lorem Ipsum has been"));
+  assert.ok(paragraphs[2].endsWith("remaining essentially unchanged."));
+  assert.equal(res[0].pageParagraphs.length, 3);
+});
+
+test("should read text column by column", async () => {
+  const res = await extractTextAndImagesWithOptions(pdfium_dirname, twoColumnsPdfPath, {
+    imagesAsBuffers: true,
+    columnReadingOrder: true,
+  });
+
+  assert.deepEqual(res[0].pageTextLines, [
+    "Two column title",
+    "Left column first line",
+    "Left column second line",
+    "Left column third line",
+    "Right column first line",
+    "Right column second line",
+    "Right column third line",
+  ]);
+});
+
+test("should extract tables", async () => {
+  const res = await extractTables(pdfium_dirname, pdfPath);
+
+  assert.equal(res.length, 1);
+  assert.equal(res[0].pageIndex, 1);
+  const rows = res[0].rows.map((row) => row.map(({ text }) => text));
+  assert.equal(rows.length, 10);
+  // header cell spans all columns
+  assert.deepEqual(rows[0], ["List of required items"]);
+  assert.deepEqual(rows[1], ["N.", "Index", "Item"]);
+  assert.deepEqual(rows[9], ["8.", "H", "Browser"]);
+  const [number, index] = res[0].rows[1];
+  assert.equal(number.right, index.left);
+});
+
+test("should return text lines font style", async () => {
+  const res = await extractTextAndImagesWithOptions(pdfium_dirname, pdfPath, {
+    imagesAsBuffers: true,
+    includeTextLineBounds: true,
+  });
+
+  const [heading, body] = res[2].pageTextLineBounds;
+  assert.equal(heading.fontName, "LiberationSerif-Bold");
+  assert.equal(heading.fontWeight, 700);
+  assert.equal(Math.round(heading.fontSize), 16);
+  assert.equal(body.fontName, "LiberationSerif");
+  assert.equal(body.fontWeight, 400);
+  assert.equal(Math.round(body.fontSize), 10);
+  assert.equal(body.isItalic, false);
+});
+
+test("should return text lines fill color", async () => {
+  const res = await extractTextAndImagesWithOptions(pdfium_dirname, twoColumnsPdfPath, {
+    imagesAsBuffers: true,
+    includeTextLineBounds: true,
+  });
+
+  const [title, line] = res[0].pageTextLineBounds;
+  assert.equal(title.fillColor, "#FF0000");
+  assert.equal(line.fillColor, "#000000");
+});
+
+test("should classify headings", async () => {
+  const res = await extractTextAndImagesWithOptions(pdfium_dirname, pdfPath, {
+    imagesAsBuffers: true,
+    detectHeadings: true,
+  });
+
+  const kinds = res.map((page) =>
+    page.pageTextLineBounds
+      .filter(({ kind }) => kind !== "body")
+      .map(({ text, kind }) => [text, kind])
+  );
+  assert.deepEqual(kinds, [
+    [
+      ["1. How to program", "h1"],
+      ["AA-FFF222 - AY", "h1"],
+      ["TSCode V1.2", "h1"],
+    ],
+    [["List of required items", "h2"]],
+    [["What is Lorem Ipsum?", "h2"]],
+    [["What is it?", "h2"]],
+  ]);
+});
+
+test("should strip headers and footers", async () => {
+  const res = await extractTextAndImagesWithOptions(pdfium_dirname, headersPdfPath, {
+    imagesAsBuffers: true,
+    stripHeadersAndFooters: true,
+  });
+
+  assert.deepEqual(
+    res.map(({ pageTextLines }) => pageTextLines),
+    [
+      ["Chapter 1", "Body text of page one."],
+      ["Chapter 2", "Body text of page two."],
+      ["Chapter 3", "Body text of page three."],
+    ]
+  );
+});
+
+test("should extract links", async () => {
+  const res = await extractLinks(pdfium_dirname, annotationsPdfPath);
+
+  assert.deepEqual(
+    res.map(({ pageIndex, uri, destinationPageIndex, text }) => ({
+      pageIndex,
+      uri,
+      destinationPageIndex,
+      text,
+    })),
+    [
+      {
+        pageIndex: 0,
+        uri: "https://example.com/",
+        destinationPageIndex: undefined,
+        text: "Visit example website",
+      },
+      {
+        pageIndex: 0,
+        uri: undefined,
+        destinationPageIndex: 1,
+        text: "Go to second page",
+      },
+    ]
+  );
+  assert.deepEqual(
+    [res[0].left, res[0].top, res[0].right, res[0].bottom],
+    [48, 712, 180, 695]
+  );
+});
+
+test("should extract annotations", async () => {
+  const res = await extractAnnotations(pdfium_dirname, annotationsPdfPath);
+
+  assert.deepEqual(
+    res.map(({ annotationType }) => annotationType),
+    ["Link", "Link", "Text", "Highlight"]
+  );
+  const comment = res[2];
+  assert.equal(comment.pageIndex, 0);
+  assert.equal(comment.contents, "Please check the figures");
+  assert.equal(comment.author, "Reviewer");
+  assert.equal(comment.modificationDate, "D:20240102030405Z");
+  assert.equal(comment.color, "#FFFF00");
+  assert.deepEqual(
+    [comment.left, comment.top, comment.right, comment.bottom],
+    [300, 720, 320, 700]
+  );
+  assert.equal(res[3].contents, "Important");
+});
+
+test("should extract form fields", async () => {
+  const res = await extractFormFields(pdfium_dirname, formPdfPath);
+
+  assert.deepEqual(
+    res.map(({ name, fieldType, value, isChecked }) => [
+      name,
+      fieldType,
+      value,
+      isChecked,
+    ]),
+    [
+      ["inspector", "Text", "John Smith", undefined],
+      ["approved", "Checkbox", "Yes", true],
+      ["condition", "RadioButton", "Good", true],
+      ["condition", "RadioButton", "Good", false],
+      ["site", "ComboBox", "Berlin", undefined],
+    ]
+  );
+  assert.deepEqual(res[4].options, ["Berlin", "Hamburg", "Munich"]);
+  assert.deepEqual(
+    [res[0].left, res[0].top, res[0].right, res[0].bottom],
+    [50, 700, 250, 680]
+  );
+});
+
+test("should fill form fields", async () => {
+  const outputPath = path.join(imagesFolderPath, "filled-form.pdf");
+  await fillForm(
+    pdfium_dirname,
+    formPdfPath,
+    { inspector: "Jane Doe", approved: "false" },
+    outputPath
+  );
+
+  let res = await extractFormFields(pdfium_dirname, outputPath);
+  assert.equal(res[0].value, "Jane Doe");
+  assert.equal(res[1].isChecked, false);
+  assert.equal(res[1].value, "Off");
+  assert.equal(res[4].value, "Berlin");
+
+  // unchecked box is checked again
+  const checkedPath = path.join(imagesFolderPath, "checked-form.pdf");
+  await fillForm(pdfium_dirname, outputPath, { approved: "true" }, checkedPath);
+  res = await extractFormFields(pdfium_dirname, checkedPath);
+  assert.deepEqual(
+    [res[0].value, res[1].value, res[1].isChecked],
+    ["Jane Doe", "Yes", true]
+  );
+
+  await fillForm(
+    pdfium_dirname,
+    formPdfPath,
+    { condition: "Poor", site: "Hamburg" },
+    outputPath
+  );
+  res = await extractFormFields(pdfium_dirname, outputPath);
+  assert.deepEqual(
+    res.map(({ value, isChecked }) => [value, isChecked]),
+    [
+      ["John Smith", undefined],
+      ["Yes", true],
+      ["Poor", false],
+      ["Poor", true],
+      ["Hamburg", undefined],
+    ]
+  );
+
+  await assert.rejects(
+    fillForm(pdfium_dirname, formPdfPath, { site: "Paris" }, outputPath),
+    { message: "INVALID_ARGUMENT: Form field site has no option Paris" }
+  );
+
+  await assert.rejects(
+    fillForm(pdfium_dirname, formPdfPath, { approved: "yes" }, outputPath),
+    {
+      message:
+        "INVALID_ARGUMENT: Checkbox form field approved takes true or false, not yes",
+    }
+  );
+
+  await assert.rejects(
+    fillForm(pdfium_dirname, formPdfPath, { missing: "value" }, outputPath),
+    { message: "INVALID_ARGUMENT: Form field missing not found" }
+  );
+});
+
+test("should extract bookmarks", async () => {
+  const res = await extractBookmarks(pdfium_dirname, documentPdfPath);
+
+  assert.deepEqual(res, [
+    {
+      title: "Chapter One",
+      pageIndex: 0,
+      children: [{ title: "Section Details", pageIndex: 1, children: [] }],
+    },
+    { title: "Chapter Two", pageIndex: 2, children: [] },
+  ]);
+});
+
+test("should get document metadata", async () => {
+  const res = await getDocumentMetadata(pdfium_dirname, documentPdfPath);
+
+  assert.deepEqual(res, {
+    title: "Quarterly Report",
+    author: "Jane Doe",
+    subject: "Finance",
+    keywords: "report, finance",
+    creator: "Writer",
+    producer: "mkpdf",
+    creationDate: "D:20240102030405Z",
+    modificationDate: "D:20240203040506Z",
+    pageCount: 3,
+  });
+});
+
+test("should get xmp metadata", async () => {
+  const res = await getXmpMetadata(pdfium_dirname, documentPdfPath);
+
+  assert.ok(res.packet.startsWith("<?xpacket begin="));
+  assert.ok(res.packet.endsWith('<?xpacket end="w"?>'));
+  assert.equal(res.title, "Quarterly Report");
+  assert.deepEqual(res.creators, ["Jane Doe", "John Smith"]);
+  assert.equal(res.description, "Results & outlook");
+  assert.deepEqual(res.subjects, ["report", "finance"]);
+  assert.equal(res.rights, "Internal use only");
+
+  assert.equal(await getXmpMetadata(pdfium_dirname, pdfPath2), null);
+
+  // compressed metadata stream referenced from catalog in object stream
+  const compressed = await getXmpMetadata(pdfium_dirname, objectStreamsPdfPath);
+  assert.equal(compressed.title, "Compressed Report");
+  assert.deepEqual(compressed.creators, ["Jane Doe"]);
+  const encrypted = await getXmpMetadata(
+    pdfium_dirname,
+    path.join(import.meta.dirname, "./pdf-test-encrypted.pdf")
+  );
+  assert.equal(encrypted.title, "Confidential");
+});
+
+test("should get page count", async () => {
+  assert.equal(await getPageCount(pdfium_dirname, documentPdfPath), 3);
+  assert.equal(await getPageCount(pdfium_dirname, pdfPath2), 1);
+});
+
+test("should get page dimensions", async () => {
+  const res = await getPageDimensions(pdfium_dirname, documentPdfPath);
+
+  assert.deepEqual(res[0], {
+    pageIndex: 0,
+    width: 612,
+    height: 792,
+    rotation: 0,
+    orientation: "portrait",
+    isScanned: false,
+  });
+  assert.deepEqual(res[2], {
+    pageIndex: 2,
+    width: 792,
+    height: 612,
+    rotation: 90,
+    orientation: "landscape",
+    isScanned: false,
+  });
+});
+
+test("should return bounds in displayed space of rotated pages", async () => {
+  const words = async (normalizeRotation) =>
+    (
+      await extractWords(pdfium_dirname, documentPdfPath, {
+        normalizeRotation,
+      })
+    ).filter(({ pageIndex, text }) => pageIndex === 2 && text === "Final");
+
+  const [word] = await words(false);
+  const [rotated] = await words(true);
+  assert.deepEqual(
+    [rotated.left, rotated.top, rotated.right, rotated.bottom],
+    [word.bottom, 612 - word.left, word.top, 612 - word.right]
+  );
+
+  const [char] = await extractChars(pdfium_dirname, documentPdfPath, 2, {
+    normalizeRotation: true,
+  });
+  assert.equal(char.rotation, 270);
+});
+
+test("should extract attachments", async () => {
+  const listed = await extractAttachments(pdfium_dirname, documentPdfPath);
+  assert.deepEqual(listed, [
+    { name: "parts.csv", mimeType: "text/csv", sizeBytes: 22 },
+  ]);
+
+  const [buffered] = await extractAttachments(pdfium_dirname, documentPdfPath, {
+    asBuffers: true,
+  });
+  assert.equal(buffered.data.toString(), "part,qty\nbolt,4\nnut,8\n");
+
+  const outputFolderPath = path.join(imagesFolderPath, "attachments");
+  const [saved] = await extractAttachments(pdfium_dirname, documentPdfPath, {
+    outputFolderPath,
+  });
+  assert.equal(saved.filename, "parts.csv");
+  assert.equal(
+    readFileSync(path.join(outputFolderPath, "parts.csv"), "utf8"),
+    "part,qty\nbolt,4\nnut,8\n"
+  );
+
+  // same length document listing the attachment twice with its stream subtype changed
+  const replacements = [
+    [
+      "/PageMode /UseOutlines /Metadata 15 0 R /Names << /EmbeddedFiles << /Names [(parts.csv) 16 0 R] >> >>",
+      "/Metadata 15 0 R /Names << /EmbeddedFiles << /Names [(parts.csv) 16 0 R (parts.csv) 16 0 R] >> >>",
+    ],
+    [
+      "/Length 22 /Type /EmbeddedFile /Subtype /text#2Fcsv /Params",
+      "/Length 22 /Subtype /text#2Ftab-separated-values /Params",
+    ],
+  ];
+  const twicePath = path.join(tmpdir(), `${randomUUID()}.pdf`);
+  await fs.writeFile(
+    twicePath,
+    replacements.reduce(
+      (data, [from, to]) => data.replace(from, to.padEnd(from.length)),
+      readFileSync(documentPdfPath, "latin1")
+    ),
+    "latin1"
+  );
+  const twiceFolderPath = path.join(imagesFolderPath, "attachments-twice");
+  const twice = await extractAttachments(pdfium_dirname, twicePath, {
+    outputFolderPath: twiceFolderPath,
+  });
+  assert.deepEqual(
+    twice.map(({ mimeType, filename }) => [mimeType, filename]),
+    [
+      ["text/tab-separated-values", "parts.csv"],
+      ["text/tab-separated-values", "parts-2.csv"],
+    ]
+  );
+  assert.equal(
+    readFileSync(path.join(twiceFolderPath, "parts-2.csv"), "utf8"),
+    "part,qty\nbolt,4\nnut,8\n"
+  );
+});
+
+test("should extract signatures", async () => {
+  const res = await extractSignatures(pdfium_dirname, signedPdfPath);
+
+  assert.deepEqual(res, {
+    hasSignatureFields: true,
+    signatures: [
+      {
+        signerName: "Jane Inspector",
+        signingDate: "D:20240305060708Z",
+        reason: "Approved",
+        byteRange: [0, 797, 1055, 440],
+        coversWholeDocument: true,
+      },
+    ],
+  });
+  assert.deepEqual(await extractSignatures(pdfium_dirname, formPdfPath), {
+    hasSignatureFields: false,
+    signatures: [],
+  });
+});
+
+test("should check linearization", async () => {
+  assert.equal(
+    await isLinearized(
+      pdfium_dirname,
+      path.join(import.meta.dirname, "./pdf-test-linearized.pdf")
+    ),
+    true
+  );
+  assert.equal(await isLinearized(pdfium_dirname, documentPdfPath), false);
+});
+
+test("should get security info", async () => {
+  const res = await getSecurityInfo(
+    pdfium_dirname,
+    path.join(import.meta.dirname, "./pdf-test-encrypted.pdf")
+  );
+
+  assert.deepEqual(res, {
+    isEncrypted: true,
+    encryptionRevision: 3,
+    canPrint: true,
+    canPrintHighQuality: true,
+    canCopy: false,
+    canModify: false,
+    canAnnotate: true,
+    canFillForms: true,
+    canAssemble: false,
+  });
+  const unprotected = await getSecurityInfo(pdfium_dirname, documentPdfPath);
+  assert.equal(unprotected.isEncrypted, false);
+  assert.equal(unprotected.canCopy, true);
+});
+
+test("should split pdf", async () => {
+  const outputFolderPath = path.join(imagesFolderPath, "split");
+  const pages = await splitPdf(pdfium_dirname, documentPdfPath, outputFolderPath);
+  assert.deepEqual(
+    pages.map((page) => page.filename),
+    ["page-1.pdf", "page-2.pdf", "page-3.pdf"]
+  );
+
+  const chunks = await splitPdf(
+    pdfium_dirname,
+    documentPdfPath,
+    outputFolderPath,
+    { pagesPerFile: 2 }
+  );
+  assert.deepEqual(chunks, [
+    { filename: "pages-1-2.pdf", firstPageIndex: 0, pageCount: 2 },
+    { filename: "page-3.pdf", firstPageIndex: 2, pageCount: 1 },
+  ]);
+  assert.equal(
+    await getPageCount(
+      pdfium_dirname,
+      path.join(outputFolderPath, "pages-1-2.pdf")
+    ),
+    2
+  );
+
+  await assert.rejects(
+    splitPdf(pdfium_dirname, documentPdfPath, path.join(documentPdfPath, "split")),
+    { message: /^SAVE_FAILED: Failed to create output folder - / }
+  );
+});
+
+test("should merge pdfs", async () => {
+  const outputPath = path.join(imagesFolderPath, "merged.pdf");
+  await mergePdfs(pdfium_dirname, [documentPdfPath, pdfPath2], outputPath);
+
+  const dimensions = await getPageDimensions(pdfium_dirname, outputPath);
+  assert.equal(dimensions.length, 4);
+  assert.deepEqual(
+    dimensions.map((page) => page.rotation),
+    [0, 0, 90, 0]
+  );
+
+  await assert.rejects(mergePdfs(pdfium_dirname, [], outputPath), {
+    message: "INVALID_ARGUMENT: No pdf documents to merge",
+  });
+});
+
+test("should rotate pages", async () => {
+  const outputPath = path.join(imagesFolderPath, "rotated.pdf");
+  await rotatePages(pdfium_dirname, documentPdfPath, [0, 2], 90, outputPath);
+
+  const dimensions = await getPageDimensions(pdfium_dirname, outputPath);
+  assert.deepEqual(
+    dimensions.map((page) => [page.rotation, page.orientation]),
+    [
+      [90, "landscape"],
+      [0, "portrait"],
+      [180, "portrait"],
+    ]
+  );
+
+  await assert.rejects(
+    rotatePages(pdfium_dirname, documentPdfPath, [3], 90, outputPath),
+    { message: "INVALID_ARGUMENT: Page is out of range (page index 3)" }
+  );
+});
+
+test("should remove pages", async () => {
+  const outputPath = path.join(imagesFolderPath, "removed.pdf");
+  await removePages(pdfium_dirname, documentPdfPath, [0, 1], outputPath);
+
+  const dimensions = await getPageDimensions(pdfium_dirname, outputPath);
+  assert.deepEqual(
+    dimensions.map((page) => page.rotation),
+    [90]
+  );
+
+  await assert.rejects(
+    removePages(pdfium_dirname, documentPdfPath, [0, 1, 2], outputPath),
+    { message: "INVALID_ARGUMENT: Can not remove all document pages" }
+  );
+});
+
+test("should reorder pages", async () => {
+  const outputPath = path.join(imagesFolderPath, "reordered.pdf");
+  await reorderPages(pdfium_dirname, documentPdfPath, [2, 0, 1], outputPath);
+
+  const dimensions = await getPageDimensions(pdfium_dirname, outputPath);
+  assert.deepEqual(
+    dimensions.map((page) => page.rotation),
+    [90, 0, 0]
+  );
+  // font shared by the pages is kept once
+  const fonts = (filePath) =>
+    readFileSync(filePath, "latin1").match(/\/Type\s*\/Font\b/g).length;
+  assert.equal(fonts(outputPath), fonts(documentPdfPath));
+  // outline and info dictionary of the document are kept
+  const bookmarks = await extractBookmarks(pdfium_dirname, outputPath);
+  assert.deepEqual(
+    bookmarks.map((bookmark) => bookmark.title),
+    (await extractBookmarks(pdfium_dirname, documentPdfPath)).map(
+      (bookmark) => bookmark.title
+    )
+  );
+  assert.ok(bookmarks.length > 0);
+  const metadata = await getDocumentMetadata(pdfium_dirname, outputPath);
+  assert.equal(metadata.title, "Quarterly Report");
+
+  await assert.rejects(
+    reorderPages(pdfium_dirname, documentPdfPath, [2, 0, 0], outputPath),
+    { message: "INVALID_ARGUMENT: Page order must list every page once" }
+  );
+});
+
+test("should stamp pages with text watermark", async () => {
+  const outputPath = path.join(imagesFolderPath, "stamped.pdf");
+  await stampPages(
+    pdfium_dirname,
+    documentPdfPath,
+    { text: "UNCONTROLLED COPY", pages: [0], angle: 45, opacity: 0.3 },
+    outputPath
+  );
+
+  const words = await extractWords(pdfium_dirname, outputPath);
+  const stamped = words.filter((word) =>
+    ["UNCONTROLLED", "COPY"].includes(word.text)
+  );
+  assert.deepEqual(
+    stamped.map((word) => word.pageIndex),
+    [0, 0]
+  );
+  // watermark is centered on the page
+  const left = Math.min(...stamped.map((word) => word.left));
+  const right = Math.max(...stamped.map((word) => word.right));
+  assert.ok(Math.abs((left + right) / 2 - 306) < 1);
+
+  await assert.rejects(
+    stampPages(pdfium_dirname, documentPdfPath, {}, outputPath),
+    { message: "INVALID_ARGUMENT: Either stamp text or image path must be set" }
+  );
+});
+
+test("should redact text matching terms and patterns", async () => {
+  const outputPath = path.join(imagesFolderPath, "redacted.pdf");
+  const res = await redactText(
+    pdfium_dirname,
+    documentPdfPath,
+    { terms: ["Introduction"], patterns: ["Fin\\w+"] },
+    outputPath
+  );
+
+  assert.deepEqual(
+    res.map(({ pageIndex, text }) => ({ pageIndex, text })),
+    [
+      { pageIndex: 0, text: "Introduction" },
+      { pageIndex: 2, text: "Final" },
+    ]
+  );
+  // matched text is removed while the rest of the line stays in place
+  const words = await extractWords(pdfium_dirname, outputPath);
+  assert.ok(!words.some((word) => ["Introduction", "Final"].includes(word.text)));
+  assert.ok(
+    words.some(
+      (word) =>
+        word.pageIndex === 0 && word.text === "text." && Math.round(word.left) === 138
+    )
+  );
+
+  await assert.rejects(
+    redactText(pdfium_dirname, documentPdfPath, { patterns: ["("] }, outputPath),
+    { message: "INVALID_ARGUMENT: Invalid redaction pattern (" }
+  );
+
+  // text of form XObjects is not covered while it could still be extracted
+  await redactText(pdfium_dirname, formTextPdfPath, { terms: ["Public"] }, outputPath);
+  await assert.rejects(
+    redactText(pdfium_dirname, formTextPdfPath, { terms: ["Secret"] }, outputPath),
+    {
+      message:
+        "PAGE_FAILED: Matched text drawn inside form XObject can not be removed (page index 0)",
+    }
+  );
+});
+
+test("should search text with match coordinates", async () => {
+  const res = await searchText(pdfium_dirname, documentPdfPath, "TEXT");
+  assert.deepEqual(
+    res.map(({ pageIndex, text }) => ({ pageIndex, text })),
+    [
+      { pageIndex: 0, text: "text" },
+      { pageIndex: 1, text: "text" },
+      { pageIndex: 2, text: "text" },
+    ]
+  );
+  assert.equal(Math.round(res[0].left), 138);
+  assert.equal(res[0].rects.length, 1);
+
+  const matchCase = await searchText(pdfium_dirname, documentPdfPath, "TEXT", {
+    matchCase: true,
+  });
+  assert.equal(matchCase.length, 0);
+});
+
+test("should export hocr", async () => {
+  const res = await exportHocr(pdfium_dirname, documentPdfPath);
+
+  assert.equal(res.length, 3);
+  assert.ok(
+    res[0].includes(
+      '<div class="ocr_page" id="page_1" title="bbox 0 0 612 792; ppageno 0">'
+    )
+  );
+  assert.ok(
+    res[0].includes(
+      '<span class="ocrx_word" id="word_1_2_1" title="bbox 72 92 135 104">Introduction</span>'
+    )
+  );
+});
+
+test("should export alto xml", async () => {
+  const res = await exportAlto(pdfium_dirname, documentPdfPath);
+
+  assert.equal(res.match(/<Page /g).length, 3);
+  assert.equal(res.match(/<TextBlock /g).length, 6);
+  assert.ok(
+    res.includes(
+      '<String ID="string_1_3" HPOS="72" VPOS="92" WIDTH="63" HEIGHT="12" CONTENT="Introduction"/>'
+    )
+  );
+});
+
+test("should convert to markdown", async () => {
+  const res = await convertToMarkdown(pdfium_dirname, pdfPath, {
+    imagesAsBuffers: true,
+  });
+
+  assert.equal(res.length, 4);
+  assert.ok(res[0].startsWith("# 1. How to program\n\n![](image-1.png)"));
+  assert.ok(
+    res[1].startsWith(
+      "| List of required items |  |  |\n| --- | --- | --- |\n| N. | Index | Item |"
+    )
+  );
+  assert.ok(res[2].startsWith("## What is Lorem Ipsum?\n\nLorem Ipsum is"));
+  assert.ok(res[3].endsWith("Here is a key\n\n![](image-4.png)"));
+});
+
+test("should export html with positioned spans", async () => {
+  const res = await exportHtml(pdfium_dirname, documentPdfPath);
+
+  assert.equal(res.match(/<div class="page"/g).length, 3);
+  assert.ok(
+    res.includes(
+      '<span style="left:72.00pt;top:92.30pt;font-size:12.00pt">Introduction</span>'
+    )
+  );
+});
+
+test("should extract structured layout", async () => {
+  const res = await extractLayout(pdfium_dirname, pdfPath);
+
+  assert.deepEqual(
+    res[3].blocks.map(({ kind, headingLevel }) => [kind, headingLevel]),
+    [
+      ["heading", 2],
+      ["paragraph", undefined],
+      ["image", undefined],
+      ["paragraph", undefined],
+      ["paragraph", undefined],
+      ["image", undefined],
+    ]
+  );
+  assert.equal(res[1].blocks[0].kind, "table");
+  assert.equal(res[1].blocks[0].lines.length, 10);
+
+  const [line] = res[3].blocks[1].lines;
+  assert.equal(line.fontName, "LiberationSerif");
+  assert.deepEqual(
+    line.words.map((word) => word.text),
+    ["Probably", "this", "is", "electricity:"]
+  );
+});
+
+test("should list layout images of forms and inline images", async () => {
+  const res = await extractLayout(pdfium_dirname, inlineImagesPdfPath);
+
+  assert.deepEqual(
+    res[0].blocks
+      .filter((block) => block.kind === "image")
+      .map(({ left, top, right, bottom }) => [left, top, right, bottom]),
+    [
+      [72, 684, 136, 620],
+      [72, 584, 136, 520],
+    ]
+  );
+});
+
+test("should preserve text layout", async () => {
+  const res = await extractTextWithOptions(pdfium_dirname, pdfPath, {
+    preserveLayout: true,
+  });
+
+  const lines = res[1].split("\n");
+  assert.equal(lines[0], "          List of required items");
+  // table columns stay aligned
+  const first = lines.find((line) => line.startsWith("1."));
+  const second = lines.find((line) => line.startsWith("2."));
+  assert.equal(first.indexOf("TSCode"), second.indexOf("PC or laptop"));
+  assert.equal(first.indexOf("A"), second.indexOf("B"));
+});
+
+test("should keep text of scanned pages empty without ocr", async () => {
+  const res = await extractTextAndImagesWithOptions(pdfium_dirname, scannedPdfPath, {
+    imagesAsBuffers: true,
+  });
+
+  assert.deepEqual(res[0].pageTextLines, []);
+  assert.equal(res[0].pageImages.length, 1);
+  assert.deepEqual(res[1].pageTextLines, ["Digital page text."]);
+  assert.deepEqual(
+    res.map((page) => page.isScanned),
+    [true, false]
+  );
+  // text of pages not recognized by OCR has no confidence
+  assert.deepEqual(
+    res.map((page) => page.pageTextLineConfidences),
+    [undefined, undefined]
+  );
+  const dimensions = await getPageDimensions(pdfium_dirname, scannedPdfPath);
+  assert.equal(dimensions[0].isScanned, true);
+
+  // default build runs without tesseract
+  await assert.rejects(
+    extractTextAndImagesWithOptions(pdfium_dirname, scannedPdfPath, {
+      imagesAsBuffers: true,
+      ocr: true,
+    }),
+    { message: "UNSUPPORTED: OCR is not supported by this build, rebuild with `ocr` feature" }
+  );
+});
+
+test("should detect page language", async () => {
+  const res = await extractTextAndImagesWithOptions(pdfium_dirname, pdfPath, {
+    imagesAsBuffers: true,
+    detectLanguage: true,
+  });
+
+  // short page text is not detected reliably
+  assert.deepEqual(
+    res.map((page) => page.language),
+    [undefined, undefined, "eng", "eng"]
+  );
+});
+
+test("should extract right to left text lines in reading order", async () => {
+  const res = await extractTextAndImagesWithOptions(pdfium_dirname, rtlPdfPath, {
+    imagesAsBuffers: true,
+  });
+
+  assert.deepEqual(res[0].pageTextLines, [
+    "שלום עולם 2024",
+    "שלום עולם PDF lib",
+    "Hello שלום עולם world",
+  ]);
+
+  const visual = await extractTextAndImagesWithOptions(pdfium_dirname, rtlPdfPath, {
+    imagesAsBuffers: true,
+    bidiVisualOrder: true,
+  });
+  assert.deepEqual(visual[0].pageTextLines, [
+    "2024 םלוע םולש",
+    "PDF lib םלוע םולש",
+    "Hello םלוע םולש world",
+  ]);
+});
+
+test("should extract vertical text in columns from right to left", async () => {
+  const res = await extractTextAndImagesWithOptions(pdfium_dirname, verticalPdfPath, {
+    imagesAsBuffers: true,
+  });
+
+  // first column written with vertical font, second with single character objects
+  assert.deepEqual(res[0].pageTextLines, ["Title", "日本語", "縦書き", "1"]);
+});
+
+test("should normalize extracted text", async () => {
+  const res = await extractTextWithOptions(pdfium_dirname, ligaturesPdfPath);
+  // accent is written as combining character
+  assert.deepEqual(res, ["Cafe\u0301 field guide"]);
+
+  const nfc = await extractTextWithOptions(pdfium_dirname, ligaturesPdfPath, {
+    unicodeNormalization: "nfc",
+    expandLigatures: true,
+  });
+  assert.deepEqual(nfc, ["Caf\u00e9 field guide"]);
+
+  const words = await extractWords(pdfium_dirname, ligaturesPdfPath, {
+    unicodeNormalization: "nfkc",
+  });
+  assert.deepEqual(
+    words.map((word) => word.text),
+    ["Caf\u00e9", "field", "guide"]
+  );
+});
+
+test("should join words hyphenated at line end", async () => {
+  const res = await extractTextAndImagesWithOptions(pdfium_dirname, hyphensPdfPath, {
+    imagesAsBuffers: true,
+    dehyphenate: true,
+  });
+
+  // next line starting uppercase keeps the hyphen
+  assert.deepEqual(res[0].pageTextLines, [
+    "The package needs a quick installation",
+    "of the command line tools.",
+    "Search is done with Full-",
+    "Text indexes.",
+  ]);
+});
+
+test("should extract text drawn twice once", async () => {
+  const res = await extractTextAndImagesWithOptions(pdfium_dirname, shadowPdfPath, {
+    imagesAsBuffers: true,
+  });
+  assert.deepEqual(res[0].pageTextLines, [
+    "Shadow title",
+    "Body line",
+    "Another line",
+  ]);
+
+  const words = await extractWords(pdfium_dirname, shadowPdfPath);
+  assert.deepEqual(
+    words.map((word) => word.text),
+    ["Shadow", "title", "Body", "line", "Another", "line"]
+  );
+});
+
+test("should join text objects of single glyphs into words", async () => {
+  const res = await extractTextAndImagesWithOptions(pdfium_dirname, glyphsPdfPath, {
+    imagesAsBuffers: true,
+  });
+  assert.deepEqual(res[0].pageTextLines, ["Hello world", "Spacing"]);
+});
+
+test("should group lines with tolerance relative to font size", async () => {
+  const res = await extractTextAndImagesWithOptions(pdfium_dirname, densePdfPath, {
+    imagesAsBuffers: true,
+    sameLineTolerance: 0.5,
+  });
+  assert.deepEqual(res[0].pageTextLines, [
+    "Dense first line end",
+    "Dense second line",
+  ]);
+});
+
+test("should relate configured text lines to images", async () => {
+  const relatedText = async (options) => {
+    const res = await extractTextAndImagesWithOptions(pdfium_dirname, pdfPath, {
+      imagesAsBuffers: true,
+      ...options,
+    });
+    return res[3].pageImages.map((image) => image.relatedText);
+  };
+
+  assert.deepEqual(
+    await relatedText({ relatedTextPosition: "below", relatedTextLines: 1 }),
+    [["Some text here and there"], []]
+  );
+  assert.deepEqual(await relatedText({ relatedTextPosition: "both" }), [
+    ["Probably this is electricity:", "Some text here and there"],
+    ["Here is a key", "Some text here and there"],
+  ]);
+  assert.deepEqual(await relatedText({ relatedTextMaxDistance: 20 }), [
+    ["Probably this is electricity:"],
+    [],
+  ]);
+});
+
+test("should relate image caption first", async () => {
+  const res = await extractTextAndImagesWithOptions(pdfium_dirname, captionPdfPath, {
+    imagesAsBuffers: true,
+  });
+  assert.deepEqual(res[0].pageImages[0].relatedText, [
+    "Figure 3: Gradient sample",
+    "More body text above.",
+  ]);
+});
+
+test("should return image page and position", async () => {
+  const res = await extractTextAndImagesWithOptions(pdfium_dirname, captionPdfPath, {
+    imagesAsBuffers: true,
+  });
+  const image = res[0].pageImages[0];
+  assert.deepEqual(
+    [image.pageIndex, image.left, image.top, image.right, image.bottom],
+    [0, 72, 668, 264, 540]
+  );
+});
+
+test("should keep original jpeg images data", async () => {
+  const res = await extractTextAndImagesWithOptions(pdfium_dirname, pdfPath, {
+    imagesAsBuffers: true,
+    keepOriginalJpeg: true,
+  });
+  const images = res.flatMap((page) => page.pageImages);
+  assert.deepEqual(
+    images.map((image) => image.filename),
+    ["image-1.jpg", "image-2.jpg", "image-3.jpg", "image-4.jpg"]
+  );
+  // image streams are written as is
+  const pdf = readFileSync(pdfPath);
+  for (const image of images) {
+    assert.ok(pdf.includes(image.data));
+  }
+});
+
+test("should convert cmyk jpeg images into rgb", async () => {
+  const res = await extractTextAndImagesWithOptions(pdfium_dirname, cmykPdfPath, {
+    imagesAsBuffers: true,
+    keepOriginalJpeg: true,
+    deduplicateImages: true,
+  });
+  // inverted CMYK JPEG has the same colors as not compressed CMYK image
+  assert.deepEqual(
+    res[0].pageImages.map((image) => [image.filename, image.duplicateOf]),
+    [
+      ["image-1.png", undefined],
+      ["image-1.png", "image-1.png"],
+      ["image-2.png", undefined],
+    ]
+  );
+});
+
+test("should keep soft mask transparency of images", async () => {
+  const res = await extractTextAndImagesWithOptions(pdfium_dirname, smaskPdfPath, {
+    imagesAsBuffers: true,
+    deduplicateImages: true,
+  });
+  // masked image differs from the same image without mask
+  assert.deepEqual(
+    res[0].pageImages.map((image) => [image.filename, image.duplicateOf]),
+    [
+      ["image-1.png", undefined],
+      ["image-2.png", undefined],
+    ]
+  );
+});
+
+test("should extract inline images and images of forms", async () => {
+  const res = await extractTextAndImagesWithOptions(pdfium_dirname, inlineImagesPdfPath, {
+    imagesAsBuffers: true,
+  });
+  assert.deepEqual(
+    res[0].pageImages.map((image) => [
+      image.filename,
+      image.left,
+      image.top,
+      image.right,
+      image.bottom,
+    ]),
+    [
+      ["image-1.png", 72, 684, 136, 620],
+      ["image-2.png", 72, 584, 136, 520],
+    ]
+  );
+});
+
+test("should turn and mirror images as displayed", async () => {
+  const res = await extractTextAndImagesWithOptions(pdfium_dirname, transformPdfPath, {
+    imagesAsBuffers: true,
+    applyImageTransform: true,
+    deduplicateImages: true,
+  });
+  // turned images are ordered first by their top, mirrored image differs from upright one
+  assert.deepEqual(
+    res[0].pageImages.map((image) => [
+      image.filename,
+      image.duplicateOf,
+      image.data?.readUInt32BE(16),
+      image.data?.readUInt32BE(20),
+    ]),
+    [
+      ["image-1.png", undefined, 4, 8],
+      ["image-2.png", undefined, 8, 8],
+      ["image-3.png", undefined, 8, 4],
+      ["image-4.png", undefined, 8, 4],
+    ]
+  );
+});
+
+test("should downscale images larger than max dimension", async () => {
+  const res = await extractTextAndImagesWithOptions(pdfium_dirname, pdfPath, {
+    imagesAsBuffers: true,
+    keepOriginalJpeg: true,
+    maxImageDimension: 200,
+  });
+  const images = res.flatMap((page) => page.pageImages);
+  // downscaled images are encoded instead of kept as original JPEG
+  assert.deepEqual(
+    images.map((image) => image.filename),
+    ["image-1.jpg", "image-2.png", "image-3.png", "image-4.png"]
+  );
+  // and keep their aspect ratio
+  assert.deepEqual(
+    images
+      .slice(1)
+      .map((image) => [image.data.readUInt32BE(16), image.data.readUInt32BE(20)]),
+    [
+      [200, 104],
+      [200, 165],
+      [200, 77],
+    ]
+  );
+});
+
+test("should exclude images recurring on most pages", async () => {
+  const res = await extractTextAndImagesWithOptions(pdfium_dirname, recurringPdfPath, {
+    imagesAsBuffers: true,
+    excludeRecurringImages: true,
+  });
+  // logo drawn at the same position on every page is left out, also from form XObject
+  assert.deepEqual(
+    res.map((page) => page.pageImages.map((image) => [image.left, image.top])),
+    [[[72, 688]], [[200, 496]], []]
+  );
+  assert.deepEqual(
+    res.map((page) => page.pageTextLines),
+    [["First page figure"], ["Second page figure"], ["Third page without figure"]]
+  );
+});
+
+test("should name images by filename template", async () => {
+  const res = await extractTextAndImagesWithOptions(pdfium_dirname, recurringPdfPath, {
+    imagesAsBuffers: true,
+    imageFilenameTemplate: "{pdfStem}-p{page}-img{index}-{n}.{ext}",
+  });
+  assert.deepEqual(
+    res.map((page) => page.pageImages.map((image) => image.filename)),
+    [
+      ["pdf-test-recurring-p1-img1-1.png", "pdf-test-recurring-p1-img2-2.png"],
+      ["pdf-test-recurring-p2-img1-3.png", "pdf-test-recurring-p2-img2-4.png"],
+      ["pdf-test-recurring-p3-img1-5.png"],
+    ]
+  );
+});
+
+test("should return image pixel size and resolution", async () => {
+  const res = await extractTextAndImagesWithOptions(pdfium_dirname, recurringPdfPath, {
+    imagesAsBuffers: true,
+  });
+  // 8 pixels wide logo is 48 points wide and 24 points high, lower resolution is returned
+  assert.deepEqual(
+    res.map((page) => page.pageImages.map((image) => [image.width, image.height, image.dpi])),
+    [
+      [
+        [8, 8, 12],
+        [8, 8, 4.5],
+      ],
+      [
+        [8, 8, 12],
+        [8, 8, 6],
+      ],
+      [[8, 8, 12]],
+    ]
+  );
+});
+
+test("should initialize pdfium library again from another path", async () => {
+  await assert.rejects(
+    initPdfium(path.join(pdfium_dirname, "missing")),
+    /Failed to bind to external Pdfium library/
+  );
+  // library is bound from pdfium folder of the next function when binding fails
+  assert.equal(await getPageCount(pdfium_dirname, pdfPath), 4);
+  await initPdfium(pdfium_dirname + path.sep);
+  assert.equal(await getPageCount(pdfium_dirname, pdfPath), 4);
+});
+
+test("should extract concurrently called documents like one by one", async () => {
+  const paths = [pdfPath, pdfPath2, twoColumnsPdfPath, recurringPdfPath];
+  const extract = (pdfPath) =>
+    extractTextAndImagesWithOptions(pdfium_dirname, pdfPath, {
+      imagesAsBuffers: true,
+    });
+  const sequential = [];
+  for (const pdfPath of paths) {
+    sequential.push(await extract(pdfPath));
+  }
+  const concurrent = await Promise.all([...paths, ...paths].map(extract));
+  assert.deepEqual(concurrent, [...sequential, ...sequential]);
+});
+
+test("should fail with LIMIT_EXCEEDED error over document limits", async () => {
+  const extract = (limits) =>
+    extractTextAndImagesWithOptions(pdfium_dirname, pdfPath, {
+      imagesAsBuffers: true,
+      ...limits,
+    });
+  await assert.rejects(extract({ maxPages: 3 }), {
+    message: "LIMIT_EXCEEDED: document has 4 pages, more than 3",
+  });
+  await assert.rejects(extract({ maxImagePixels: 20000 }), {
+    message:
+      "LIMIT_EXCEEDED: image of 135x155 pixels is larger than 20000 pixels (page index 0)",
+  });
+  await assert.rejects(
+    extract({ maxTotalImageBytes: 100000 }),
+    /^Error: LIMIT_EXCEEDED: extracted images take \d+ bytes, more than 100000$/
+  );
+  const res = await extract({
+    maxPages: 4,
+    maxImagePixels: 250000,
+    maxTotalImageBytes: 1000000,
+  });
+  assert.equal(res.flatMap((page) => page.pageImages).length, 4);
+});
+
+test("should fail with stable error codes", async () => {
+  const extract = (pdfPath, options) =>
+    extractTextAndImagesWithOptions(pdfium_dirname, pdfPath, {
+      imagesAsBuffers: true,
+      ...options,
+    });
+  await assert.rejects(
+    extract(path.join(import.meta.dirname, "./missing.pdf")),
+    /^Error: LOAD_FAILED: Failed to open pdf document - /
+  );
+  await assert.rejects(extract(path.join(import.meta.dirname, "./index.spec.mjs")), {
+    message: "LOAD_FAILED: Failed to read pdf document - PdfiumLibraryInternalError(FormatError)",
+  });
+  await assert.rejects(extract(protectedPdfPath), {
+    message: "BAD_PASSWORD: Pdf document is protected by password",
+  });
+
+  // folder in place of the first image file can't be written
+  const savedImagesFolderPath = path.join(tmpdir(), randomUUID());
+  mkdirSync(path.join(savedImagesFolderPath, "image-1.png"), { recursive: true });
+  await assert.rejects(
+    extract(pdfPath, { imagesAsBuffers: false, imagesFolderPath: savedImagesFolderPath }),
+    /^Error: IMAGE_SAVE_FAILED: Failed to save image image-1.png \(page index 0\) - /
+  );
+});
+
+test("should skip bad pages and return warnings", async () => {
+  await assert.rejects(
+    extractTextAndImagesWithOptions(pdfium_dirname, badPagePdfPath, {
+      imagesAsBuffers: true,
+    }),
+    /^Error: PAGE_FAILED: Failed to read pdf document page \(page index 1\) - /
+  );
+  const res = await extractTextAndImagesWithOptions(pdfium_dirname, badPagePdfPath, {
+    imagesAsBuffers: true,
+    skipBadPages: true,
+  });
+  assert.deepEqual(
+    res.map((page) => page.pageTextLines),
+    [["First page"], [], ["Third page"]]
+  );
+  assert.deepEqual(res[0].warnings, []);
+  assert.equal(res[1].warnings.length, 1);
+  assert.match(
+    res[1].warnings[0],
+    /^PAGE_FAILED: Failed to read pdf document page \(page index 1\) - /
+  );
+});
+
+test("should pass diagnostics to logging callback", async () => {
+  const messages = [];
+  setLogger((message) => messages.push(message), "warn");
+  try {
+    await extractTextAndImagesWithOptions(pdfium_dirname, badPagePdfPath, {
+      imagesAsBuffers: true,
+      skipBadPages: true,
+    });
+    // callback is called from the event loop after extraction resolves
+    await new Promise((resolve) => setTimeout(resolve, 50));
+  } finally {
+    setLogger();
+  }
+  assert.equal(messages.length, 1);
+  assert.equal(messages[0].level, "warn");
+  assert.match(
+    messages[0].message,
+    /^PAGE_FAILED: Failed to read pdf document page \(page index 1\) - /
+  );
+});
+
+test("should include page extraction timings", async () => {
+  const res = await extractTextAndImagesWithOptions(pdfium_dirname, pdfPath, {
+    imagesAsBuffers: true,
+    includeTimings: true,
+  });
+  assert.equal(res.length, 4);
+  assert.ok(res[0].timings.documentLoadMs >= 0);
+  for (const [index, { timings }] of res.entries()) {
+    if (index > 0) {
+      assert.equal(timings.documentLoadMs, undefined);
+    }
+    assert.ok(timings.imageDecodeMs > 0 || res[index].pageImages.length === 0);
+    assert.equal(timings.ocrMs, 0);
+    const stages = timings.textMs + timings.imageDecodeMs + timings.imageEncodeMs;
+    assert.ok(Math.abs(timings.pageMs - stages) < 0.001);
+  }
+  const [page] = await extractTextAndImagesWithOptions(pdfium_dirname, pdfPath, {
+    imagesAsBuffers: true,
+  });
+  assert.equal(page.timings, undefined);
+});
+
+test("should extract single page by index", async () => {
+  const options = {
+    imagesAsBuffers: true,
+    imageFilenameTemplate: "page-{page}-{index}.{ext}",
+  };
+  const pages = await extractTextAndImagesWithOptions(pdfium_dirname, pdfPath, options);
+  const page = await extractPage(pdfium_dirname, pdfPath, 3, options);
+  assert.deepEqual(page.pageTextLines, pages[3].pageTextLines);
+  assert.equal(page.pageImages.length, 2);
+  assert.deepEqual(
+    page.pageImages.map(({ filename, pageIndex, data }) => [filename, pageIndex, data]),
+    pages[3].pageImages.map(({ filename, pageIndex, data }) => [filename, pageIndex, data])
+  );
+  await assert.rejects(extractPage(pdfium_dirname, pdfPath, 4, options), {
+    message: "INVALID_ARGUMENT: Page is out of range (page index 4)",
+  });
+});
+
+test("should query document opened once until closed", async () => {
+  const options = { imagesAsBuffers: true };
+  const document = await openDocument(pdfium_dirname, pdfPath, options);
+  try {
+    assert.equal(await document.pageCount(), 4);
+    assert.equal((await document.metadata()).pageCount, 4);
+    const texts = await extractTextWithOptions(pdfium_dirname, pdfPath, options);
+    assert.equal(await document.pageText(1), texts[1]);
+    const pages = await extractTextAndImagesWithOptions(pdfium_dirname, pdfPath, options);
+    const images = await document.pageImages(3);
+    assert.deepEqual(
+      images.map(({ data }) => data),
+      pages[3].pageImages.map(({ data }) => data)
+    );
+    // images of pages extracted one by one are numbered across the document
+    const repeated = await document.pageImages(3);
+    assert.ok(images.length > 0);
+    assert.ok(
+      repeated.every(({ filename }) => !images.some((image) => image.filename === filename))
+    );
+    await assert.rejects(document.pageText(4), {
+      message: "INVALID_ARGUMENT: Page is out of range (page index 4)",
+    });
+  } finally {
+    await document.close();
+  }
+  await assert.rejects(document.pageCount(), {
+    message: "DOCUMENT_CLOSED: Pdf document is closed",
+  });
+});
+
+test("should extract embedded fonts", async () => {
+  const fonts = await extractFonts(pdfium_dirname, pdfPath2, {
+    asBuffers: true,
+  });
+  assert.deepEqual(
+    fonts.map(({ name, subsetPrefix, format, pageIndex }) => [
+      name,
+      subsetPrefix,
+      format,
+      pageIndex,
+    ]),
+    [
+      ["HelveticaNeue-UltraLight", "NBUHVV", "ttf", 0],
+      ["HelveticaNeue-LightItalic", "PUALXA", "ttf", 0],
+      ["HelveticaNeue-Light", "NPPOAD", "ttf", 0],
+    ]
+  );
+  for (const font of fonts) {
+    assert.equal(font.data.length, font.sizeBytes);
+    assert.equal(font.filename, undefined);
+  }
+
+  const outputFolderPath = path.join(tmpdir(), randomUUID());
+  const saved = await extractFonts(pdfium_dirname, pdfPath2, {
+    outputFolderPath,
+  });
+  assert.deepEqual(
+    saved.map(({ filename }) => filename),
+    [
+      "NBUHVV+HelveticaNeue-UltraLight.ttf",
+      "PUALXA+HelveticaNeue-LightItalic.ttf",
+      "NPPOAD+HelveticaNeue-Light.ttf",
+    ]
+  );
+  assert.deepEqual(
+    readFileSync(path.join(outputFolderPath, saved[0].filename)),
+    fonts[0].data
+  );
+  assert.equal(saved[0].data, undefined);
+});
+
+test("should list fonts with encoding and unicode map", async () => {
+  const fonts = await listFonts(pdfium_dirname, pdfPath2);
+  assert.equal(fonts.length, 3);
+  assert.deepEqual(fonts[0], {
+    name: "HelveticaNeue-UltraLight",
+    subsetPrefix: "NBUHVV",
+    fontType: "TrueType",
+    embedded: true,
+    subset: true,
+    encoding: "MacRomanEncoding",
+    hasToUnicode: false,
+    pageIndex: 0,
+  });
+
+  const [standardFont] = await listFonts(pdfium_dirname, ligaturesPdfPath);
+  assert.deepEqual(standardFont, {
+    name: "Helvetica",
+    fontType: "Type1",
+    embedded: false,
+    subset: false,
+    encoding: "Builtin",
+    hasToUnicode: true,
+    pageIndex: 0,
+  });
+
+  // font dictionary in compressed object stream
+  const [compressedFont] = await listFonts(pdfium_dirname, objectStreamsPdfPath);
+  assert.deepEqual(compressedFont, {
+    name: "Helvetica",
+    fontType: "Type1",
+    embedded: false,
+    subset: false,
+    encoding: "WinAnsiEncoding",
+    hasToUnicode: true,
+    pageIndex: 0,
+  });
+});
+
+test("should count words and characters", async () => {
+  const stats = await getTextStats(pdfium_dirname, scannedPdfPath);
+  assert.deepEqual(stats, {
+    wordCount: 3,
+    charCount: 16,
+    pages: [
+      { pageIndex: 0, wordCount: 0, charCount: 0 },
+      { pageIndex: 1, wordCount: 3, charCount: 16 },
+    ],
+  });
+
+  const { wordCount, charCount } = await getTextStats(pdfium_dirname, pdfPath);
+  const words = await extractWords(pdfium_dirname, pdfPath);
+  assert.equal(wordCount, words.length);
+  assert.equal(
+    charCount,
+    words.reduce((count, word) => count + [...word.text].length, 0)
+  );
+});
+
+test("should read page labels", async () => {
+  const labels = ["i", "ii", "1", "2", "A-1"];
+  const dimensions = await getPageDimensions(pdfium_dirname, labelsPdfPath);
+  assert.deepEqual(
+    dimensions.map(({ pageLabel }) => pageLabel),
+    labels
+  );
+  const pages = await extractTextAndImagesWithOptions(pdfium_dirname, labelsPdfPath, {
+    imagesAsBuffers: true,
+  });
+  assert.deepEqual(
+    pages.map(({ pageLabel }) => pageLabel),
+    labels
+  );
+
+  const [page] = await extractTextAndImagesWithOptions(pdfium_dirname, pdfPath, {
+    imagesAsBuffers: true,
+  });
+  assert.equal(page.pageLabel, undefined);
+});
+
+test("should extract named destinations", async () => {
+  const destinations = await extractNamedDestinations(
+    pdfium_dirname,
+    destinationsPdfPath
+  );
+  assert.deepEqual(destinations, [
+    { name: "appendix", pageIndex: 2 },
+    { name: "chapter1", pageIndex: 1, left: 72, top: 700, zoom: 1.5 },
+    { name: "legacy", pageIndex: 2, top: 500 },
+  ]);
+
+  // link to named destination is resolved to its page
+  const [link] = await extractLinks(pdfium_dirname, destinationsPdfPath);
+  assert.equal(link.destinationPageIndex, 1);
+
+  assert.deepEqual(await extractNamedDestinations(pdfium_dirname, pdfPath), []);
+});
+
+test("should extract structure tree and order tagged text by it", async () => {
+  const [document] = await extractStructureTree(pdfium_dirname, taggedPdfPath);
+  assert.equal(document.role, "Document");
+  assert.deepEqual(
+    document.children.map(({ role, text, altText }) => [role, text, altText]),
+    [
+      ["H1", "Chapter heading", undefined],
+      ["P", "Chapter body text", undefined],
+      ["Figure", undefined, "Red square"],
+      ["P", "Sidebar note", undefined],
+    ]
+  );
+  assert.equal(document.children[0].title, "Chapter");
+  assert.equal(document.children[1].lang, "en-GB");
+  assert.equal(document.children[3].actualText, "Side note");
+  assert.deepEqual(await extractStructureTree(pdfium_dirname, pdfPath2), []);
+
+  const options = { imagesAsBuffers: true };
+  const [page] = await extractTextAndImagesWithOptions(pdfium_dirname, taggedPdfPath, options);
+  assert.deepEqual(page.pageTextLines, [
+    "Running header",
+    "Sidebar note",
+    "Chapter heading",
+    "Chapter body text",
+  ]);
+  const [taggedPage] = await extractTextAndImagesWithOptions(pdfium_dirname, taggedPdfPath, {
+    ...options,
+    structureReadingOrder: true,
+  });
+  assert.deepEqual(taggedPage.pageTextLines, [
+    "Running header",
+    "Chapter heading",
+    "Chapter body text",
+    "Sidebar note",
+  ]);
+});
+
+test("should check accessibility", async () => {
+  assert.deepEqual(await checkAccessibility(pdfium_dirname, taggedPdfPath), {
+    isTagged: true,
+    language: "en-US",
+    figureCount: 1,
+    figuresWithoutAltText: [],
+    issues: ["Document title is missing"],
+  });
+
+  const report = await checkAccessibility(pdfium_dirname, pdfPath);
+  assert.equal(report.isTagged, true);
+  assert.equal(report.figuresWithoutAltText.length, report.figureCount);
+  assert.ok(report.issues.includes("Document title is missing"));
+
+  const untagged = await checkAccessibility(pdfium_dirname, pdfPath2);
+  assert.equal(untagged.isTagged, false);
+  assert.deepEqual(untagged.issues, [
+    "Document is not tagged",
+    "Document title is missing",
+    "Document language is not declared",
+  ]);
+
+  // same length catalog declaring its language as hexadecimal string
+  const hexLangPath = path.join(tmpdir(), `${randomUUID()}.pdf`);
+  const tagged = readFileSync(taggedPdfPath, "latin1");
+  await fs.writeFile(
+    hexLangPath,
+    tagged.replace(
+      "/Type /Catalog /Pages 2 0 R /MarkInfo << /Marked true >> /StructTreeRoot 6 0 R /Lang (en-US)",
+      "/Type/Catalog /Pages 2 0 R /MarkInfo<</Marked true>> /StructTreeRoot 6 0 R /Lang<656E2D5553>"
+    ),
+    "latin1"
+  );
+  const hexLang = await checkAccessibility(pdfium_dirname, hexLangPath);
+  assert.equal(hexLang.language, "en-US");
+});
+
+test("should render all pages with parallel encoding", async () => {
+  const options = { asBuffers: true, dpi: 72 };
+  const pages = await renderPages(pdfium_dirname, pdfPath, options);
+  const rendered = await renderDocument(pdfium_dirname, pdfPath, {
+    ...options,
+    concurrency: 3,
+  });
+  assert.deepEqual(
+    rendered.map(({ pageIndex, filename, width, height, data }) => [
+      pageIndex,
+      filename,
+      width,
+      height,
+      data,
+    ]),
+    pages.map(({ pageIndex, filename, width, height, data }) => [
+      pageIndex,
+      filename,
+      width,
+      height,
+      data,
+    ])
+  );
+
+  const outputFolderPath = path.join(tmpdir(), randomUUID());
+  const saved = await renderDocument(pdfium_dirname, pdfPath, {
+    outputFolderPath,
+    format: "jpeg",
+    pages: [3, 1],
+  });
+  assert.deepEqual(
+    saved.map(({ filename }) => filename),
+    ["page-4.jpg", "page-2.jpg"]
+  );
+  for (const { filename, fileSizeBytes } of saved) {
+    assert.equal(
+      readFileSync(path.join(outputFolderPath, filename)).length,
+      fileSizeBytes
+    );
+  }
+
+  // page image which cannot be written stops rendering with its error
+  mkdirSync(path.join(outputFolderPath, "page-1.png"));
+  await assert.rejects(
+    renderDocument(pdfium_dirname, pdfPath, { outputFolderPath, concurrency: 1 })
+  );
+});
+
+test("should render page in tiles", async () => {
+  const [page] = await renderPages(pdfium_dirname, pdfPath, {
+    asBuffers: true,
+    dpi: 100,
+    pages: [0],
+  });
+  const tiles = await renderTiles(pdfium_dirname, pdfPath, 0, {
+    asBuffers: true,
+    dpi: 100,
+    tileSize: 300,
+  });
+  assert.equal(tiles.length, 12);
+  assert.deepEqual(
+    tiles.slice(-3).map(({ row, column, x, y, width, height, filename }) => [
+      row,
+      column,
+      x,
+      y,
+      width,
+      height,
+      filename,
+    ]),
+    [
+      [3, 0, 0, 900, 300, 269, "page-1-tile-3-0.png"],
+      [3, 1, 300, 900, 300, 269, "page-1-tile-3-1.png"],
+      [3, 2, 600, 900, 227, 269, "page-1-tile-3-2.png"],
+    ]
+  );
+  const last = tiles[tiles.length - 1];
+  assert.equal(last.x + last.width, page.width);
+  assert.equal(last.y + last.height, page.height);
+
+  const outputFolderPath = path.join(tmpdir(), randomUUID());
+  const saved = await renderTiles(pdfium_dirname, pdfPath, 0, {
+    outputFolderPath,
+    dpi: 72,
+  });
+  assert.equal(saved.length, 12);
+  for (const { filename, fileSizeBytes } of saved) {
+    assert.equal(
+      readFileSync(path.join(outputFolderPath, filename)).length,
+      fileSizeBytes
+    );
+  }
+
+  await assert.rejects(
+    renderTiles(pdfium_dirname, pdfPath, 0, { asBuffers: true, tileSize: 0 }),
+    { message: "INVALID_ARGUMENT: Tile size must be positive" }
+  );
+});
+
+test("should compare rendered pages of documents", async () => {
+  const stampedPath = path.join(imagesFolderPath, "compare-stamped.pdf");
+  const shortenedPath = path.join(imagesFolderPath, "compare-shortened.pdf");
+  await stampPages(
+    pdfium_dirname,
+    documentPdfPath,
+    { text: "DRAFT", pages: [1] },
+    stampedPath
+  );
+  await removePages(pdfium_dirname, stampedPath, [2], shortenedPath);
+
+  const same = await comparePages(
+    pdfium_dirname,
+    documentPdfPath,
+    documentPdfPath
+  );
+  assert.deepEqual(
+    same.map(({ differencePercent }) => differencePercent),
+    [0, 0, 0]
+  );
+
+  const res = await comparePages(
+    pdfium_dirname,
+    documentPdfPath,
+    shortenedPath,
+    { diffImages: true, asBuffers: true }
+  );
+  assert.deepEqual(
+    res.map(({ pageIndex }) => pageIndex),
+    [0, 1, 2]
+  );
+  assert.equal(res[0].differencePercent, 0);
+  assert.ok(res[1].differencePercent > 0 && res[1].differencePercent < 5);
+  assert.equal(res[2].differencePercent, 100);
+  assert.equal(res[1].diffImage.filename, "page-2-diff.png");
+  assert.ok(res[1].diffImage.data.length > 0);
+  assert.equal(res[2].diffImage, undefined);
+});
+
+test("should create pdf from images", async () => {
+  const imagesPath = path.join(imagesFolderPath, "scans");
+  const scans = await renderPages(pdfium_dirname, documentPdfPath, {
+    outputFolderPath: imagesPath,
+    format: "jpeg",
+    dpi: 144,
+    pages: [0, 1],
+  });
+  const imagePaths = scans.map(({ filename }) =>
+    path.join(imagesPath, filename)
+  );
+
+  const outputPath = path.join(imagesFolderPath, "from-images.pdf");
+  await imagesToPdf(pdfium_dirname, imagePaths, outputPath, { dpi: 144 });
+  const dimensions = await getPageDimensions(pdfium_dirname, outputPath);
+  assert.deepEqual(
+    dimensions.map(({ width, height, isScanned }) => [width, height, isScanned]),
+    [
+      [612, 792, true],
+      [612, 792, true],
+    ]
+  );
+  const differences = await comparePages(
+    pdfium_dirname,
+    documentPdfPath,
+    outputPath
+  );
+  assert.ok(differences[0].differencePercent < 1);
+  assert.ok(differences[1].differencePercent < 1);
+  const created = await fs.readFile(outputPath);
+  const jpeg = await fs.readFile(imagePaths[0]);
+  assert.ok(created.includes("/DCTDecode"));
+  assert.ok(created.includes(jpeg.subarray(0, 4096)));
+
+  const a4Path = path.join(imagesFolderPath, "from-images-a4.pdf");
+  await imagesToPdf(pdfium_dirname, imagePaths, a4Path, {
+    pageWidth: 595,
+    pageHeight: 842,
+    margin: 36,
+  });
+  const a4 = await getPageDimensions(pdfium_dirname, a4Path);
+  assert.deepEqual(
+    a4.map(({ width, height }) => [width, height]),
+    [
+      [595, 842],
+      [595, 842],
+    ]
+  );
+
+  await assert.rejects(imagesToPdf(pdfium_dirname, [], outputPath), {
+    message: "INVALID_ARGUMENT: No images to convert",
+  });
+});
+
+test("should create pdf with text blocks and images", async () => {
+  const imagesPath = path.join(imagesFolderPath, "cover");
+  const [logo] = await renderPages(pdfium_dirname, documentPdfPath, {
+    outputFolderPath: imagesPath,
+    dpi: 36,
+    pages: [0],
+  });
+  const outputPath = path.join(imagesFolderPath, "created.pdf");
+  await createPdf(
+    pdfium_dirname,
+    [
+      {
+        texts: [
+          {
+            text: "Cover sheet",
+            x: 72,
+            y: 720,
+            fontSize: 24,
+            bold: true,
+            color: "#CC0000",
+          },
+          {
+            text: "Order 1234\nSummary text long enough to be wrapped into several lines",
+            x: 72,
+            y: 680,
+            font: "times",
+            maxWidth: 200,
+          },
+        ],
+        images: [
+          {
+            imagePath: path.join(imagesPath, logo.filename),
+            x: 72,
+            y: 72,
+            width: 200,
+          },
+        ],
+      },
+      {
+        width: 300,
+        height: 200,
+        texts: [{ text: "Second page", x: 10, y: 190, font: "courier" }],
+      },
+    ],
+    outputPath
+  );
+
+  const dimensions = await getPageDimensions(pdfium_dirname, outputPath);
+  assert.deepEqual(
+    dimensions.map(({ width, height }) => [width, height]),
+    [
+      [612, 792],
+      [300, 200],
+    ]
+  );
+
+  const pages = await extractTextAndImagesWithOptions(pdfium_dirname, outputPath, {
+    imagesFolderPath: imagesPath,
+    includeTextLineBounds: true,
+  });
+  const lines = pages[0].pageTextLineBounds;
+  assert.deepEqual(
+    lines.map(({ text }) => text),
+    [
+      "Cover sheet",
+      "Order 1234",
+      "Summary text long enough to be",
+      "wrapped into several lines",
+    ]
+  );
+  assert.equal(lines[0].fontName, "Helvetica-Bold");
+  assert.equal(lines[0].fillColor, "#CC0000");
+  assert.equal(lines[1].fontName, "Times-Roman");
+  assert.ok(lines.every(({ left, right }) => left >= 72 && right <= 272));
+  assert.equal(pages[0].pageImages.length, 1);
+  assert.equal(pages[1].pageTextLineBounds[0].fontName, "Courier");
+
+  await assert.rejects(createPdf(pdfium_dirname, [], outputPath), {
+    message: "INVALID_ARGUMENT: No pages to create",
+  });
+});
+
+test("should add recognized text layer only with ocr", async () => {
+  const outputPath = path.join(imagesFolderPath, "searchable.pdf");
+  // pages without scans are copied as they are
+  assert.deepEqual(
+    await makeSearchable(pdfium_dirname, documentPdfPath, outputPath),
+    []
+  );
+  assert.deepEqual(
+    await getPageDimensions(pdfium_dirname, outputPath),
+    await getPageDimensions(pdfium_dirname, documentPdfPath)
+  );
+
+  // default build runs without tesseract
+  await assert.rejects(
+    makeSearchable(pdfium_dirname, scannedPdfPath, outputPath),
+    { message: "UNSUPPORTED: OCR is not supported by this build, rebuild with `ocr` feature" }
+  );
+  await assert.rejects(
+    makeSearchable(pdfium_dirname, scannedPdfPath, outputPath, {
+      pages: [5],
+    }),
+    { message: "INVALID_ARGUMENT: Page is out of range (page index 5)" }
+  );
+  await assert.rejects(
+    makeSearchable(pdfium_dirname, documentPdfPath, outputPath, {
+      fontPath: path.join(import.meta.dirname, "missing.ttf"),
+    }),
+    { message: /^LOAD_FAILED: Failed to load font/ }
+  );
+});
+
+test("should optimize pdf images", async () => {
+  const outputPath = path.join(imagesFolderPath, "optimized.pdf");
+  const res = await optimizePdf(pdfium_dirname, pdfPath, outputPath, {
+    maxImageDpi: 72,
+  });
+  assert.strictEqual(res.inputSizeBytes, (await fs.stat(pdfPath)).size);
+  assert.strictEqual(res.outputSizeBytes, (await fs.stat(outputPath)).size);
+  assert.ok(res.outputSizeBytes < res.inputSizeBytes);
+  assert.strictEqual(res.recompressedImages, 3);
+  assert.strictEqual(res.downsampledImages, 3);
+  const differences = await comparePages(pdfium_dirname, pdfPath, outputPath);
+  assert.ok(differences.every((page) => page.differencePercent < 5));
+
+  // JPEG keeps no soft mask of transparent image
+  const masked = await optimizePdf(pdfium_dirname, smaskPdfPath, outputPath);
+  assert.strictEqual(masked.recompressedImages, 0);
+
+  // gray image keeps its single channel and dictionary entries like inverting decode array
+  const gray = await optimizePdf(pdfium_dirname, grayDecodePdfPath, outputPath);
+  assert.strictEqual(gray.recompressedImages, 1);
+  const optimized = await fs.readFile(outputPath, "latin1");
+  const [image] = optimized.match(/<<[^>]*\/DCTDecode[^>]*>>/g);
+  assert.match(image, /\/ColorSpace\s*\/DeviceGray/);
+  assert.match(image, /\/Decode\s*\[\s*1 0\s*\]/);
+  assert.match(image, /\/Interpolate true/);
+  assert.match(image, /\/Intent\s*\/Perceptual/);
+  assert.match(image, /\/Metadata \d+ 0 R/);
+  const [difference] = await comparePages(
+    pdfium_dirname,
+    grayDecodePdfPath,
+    outputPath
+  );
+  assert.ok(difference.differencePercent < 1);
+
+  // cross reference subsection past the largest object number is not read
+  const overflowPath = path.join(tmpdir(), `${randomUUID()}.pdf`);
+  await fs.writeFile(
+    overflowPath,
+    readFileSync(grayDecodePdfPath, "latin1").replace(
+      "trailer",
+      "4294967295 2\n0000000009 00000 n \n0000000009 00000 n \ntrailer"
+    ),
+    "latin1"
+  );
+  const overflow = await optimizePdf(pdfium_dirname, overflowPath, outputPath);
+  assert.strictEqual(overflow.recompressedImages, 0);
+});
+
 async function calculateHashForBuffer(data) {
   const hash = createHash("sha256");
   hash.update(data);
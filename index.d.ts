@@ -3,21 +3,1083 @@
 
 /* auto-generated by NAPI-RS */
 
+/** Link annotation with its anchor rectangle in page space points */
+export interface ExtractedLink {
+  pageIndex: number
+  /** External link target */
+  uri?: string
+  /** Target page of link inside the document */
+  destinationPageIndex?: number
+  /** Page text under the link anchor */
+  text: string
+  left: number
+  top: number
+  right: number
+  bottom: number
+}
+/** Page annotation with its rectangle in page space points */
+export interface ExtractedAnnotation {
+  pageIndex: number
+  /** PDF annotation subtype like `Text`, `Highlight` or `Link` */
+  annotationType: string
+  contents?: string
+  author?: string
+  /** Last modification date in PDF date format like "D:20240102030405Z" */
+  modificationDate?: string
+  /** Annotation color in hex like "#FFFF00" */
+  color?: string
+  left: number
+  top: number
+  right: number
+  bottom: number
+}
+/** Extract page annotations like comments and highlights */
+export declare function extractAnnotations(pdfiumDir: string, pdfPath: string, options?: ExtractOptions | undefined | null): Promise<Array<ExtractedAnnotation>>
+/** Extract page links with their targets and anchor text */
+export declare function extractLinks(pdfiumDir: string, pdfPath: string, options?: ExtractOptions | undefined | null): Promise<Array<ExtractedLink>>
+/** Embedded file attachment */
+export interface ExtractedAttachment {
+  /** Attachment file name */
+  name: string
+  /** MIME type of the embedded file, guessed from file name extension when it has none */
+  mimeType: string
+  sizeBytes: number
+  /** Saved file name, set when attachments are saved into output folder */
+  filename?: string
+  /** Attachment content, set when attachments are returned as buffers */
+  data?: Buffer
+}
+/** List embedded file attachments, optionally saving them or returning their content */
+export declare function extractAttachments(pdfiumDir: string, pdfPath: string, options?: AttachmentOptions | undefined | null): Promise<Array<ExtractedAttachment>>
+/** Standard font available without embedding */
+export const enum StandardFont {
+  Helvetica = 'helvetica',
+  Times = 'times',
+  Courier = 'courier'
+}
+/** Text placed on created page, lines are split on new line characters */
+export interface TextBlock {
+  text: string
+  /** Left edge of the block in points from the left page edge */
+  x: number
+  /** Top edge of the block in points from the bottom page edge */
+  y: number
+  /** Font family, Helvetica by default */
+  font?: StandardFont
+  bold?: boolean
+  italic?: boolean
+  /** Font size in points, 12 by default */
+  fontSize?: number
+  /** Distance between lines baselines relative to font size, 1.2 by default */
+  lineHeight?: number
+  /** Width in points to wrap lines at word boundaries, lines are not wrapped by default */
+  maxWidth?: number
+  /** Text color in hex like "#FF0000", black by default */
+  color?: string
+}
+/** Image placed on created page */
+export interface ImageBlock {
+  imagePath: string
+  /** Left edge of the image in points from the left page edge */
+  x: number
+  /** Bottom edge of the image in points from the bottom page edge */
+  y: number
+  /** Image width in points, scaled by height or image size at 72 DPI by default */
+  width?: number
+  /** Image height in points, scaled by width or image size at 72 DPI by default */
+  height?: number
+}
+/** Content of created page */
+export interface PageContent {
+  /** Page width in points, 612 by default */
+  width?: number
+  /** Page height in points, 792 by default */
+  height?: number
+  texts?: Array<TextBlock>
+  /** Images drawn below texts */
+  images?: Array<ImageBlock>
+}
+/**
+* Create document from pages with positioned text blocks and images and save it into output
+* path
+*/
+export declare function createPdf(pdfiumDir: string, pages: Array<PageContent>, outputPath: string): Promise<void>
+/** Document outline entry */
+export interface ExtractedBookmark {
+  title: string
+  /** Target page of bookmark inside the document */
+  pageIndex?: number
+  children: Array<ExtractedBookmark>
+}
+/** Extract document outline as bookmarks tree */
+export declare function extractBookmarks(pdfiumDir: string, pdfPath: string): Promise<Array<ExtractedBookmark>>
+/**
+* Named destination with its target position in page space points, origin at the bottom left
+* page corner
+*/
+export interface NamedDestination {
+  name: string
+  /** Target page inside the document */
+  pageIndex?: number
+  /** Target position, set when the destination specifies it */
+  left?: number
+  top?: number
+  /** Zoom factor, set when the destination specifies it */
+  zoom?: number
+}
+/** Extract named destinations which links and bookmarks can target by name */
+export declare function extractNamedDestinations(pdfiumDir: string, pdfPath: string): Promise<Array<NamedDestination>>
+/** Document information dictionary */
+export interface DocumentMetadata {
+  title?: string
+  author?: string
+  subject?: string
+  keywords?: string
+  creator?: string
+  producer?: string
+  /** Creation date in PDF date format like "D:20240102030405Z" */
+  creationDate?: string
+  /** Last modification date in PDF date format */
+  modificationDate?: string
+  pageCount: number
+}
+/** Read document metadata without loading its pages */
+export declare function getDocumentMetadata(pdfiumDir: string, pdfPath: string): Promise<DocumentMetadata>
+/** Count document pages without loading them */
+export declare function getPageCount(pdfiumDir: string, pdfPath: string): Promise<number>
+/** Page size and rotation */
+export interface PageDimensions {
+  pageIndex: number
+  /**
+  * Page number printed on the page like "iv" or "A-1", set when the document defines page
+  * labels
+  */
+  pageLabel?: string
+  /** Page width in points as displayed, with rotation applied */
+  width: number
+  /** Page height in points as displayed, with rotation applied */
+  height: number
+  /** Clockwise page rotation in degrees, 0, 90, 180 or 270 */
+  rotation: number
+  orientation: PageOrientation
+  /** Page is a scanned image without extractable text */
+  isScanned: boolean
+}
+/** Displayed page orientation */
+export const enum PageOrientation {
+  Portrait = 'portrait',
+  Landscape = 'landscape'
+}
+/** Read pages size, rotation, orientation and whether they are scanned */
+export declare function getPageDimensions(pdfiumDir: string, pdfPath: string): Promise<Array<PageDimensions>>
+/** Check the document is linearized for fast web view and was not updated since */
+export declare function isLinearized(pdfiumDir: string, pdfPath: string): Promise<boolean>
+/** Document encryption and permissions granted to the user */
+export interface SecurityInfo {
+  isEncrypted: boolean
+  /** Standard security handler revision, not set for unprotected documents */
+  encryptionRevision?: number
+  canPrint: boolean
+  canPrintHighQuality: boolean
+  /** Copy or extract text and graphics */
+  canCopy: boolean
+  canModify: boolean
+  /** Add or modify annotations */
+  canAnnotate: boolean
+  canFillForms: boolean
+  /** Insert, rotate or delete pages */
+  canAssemble: boolean
+}
+/** Read document encryption and permissions */
+export declare function getSecurityInfo(pdfiumDir: string, pdfPath: string): Promise<SecurityInfo>
+/**
+* Export page words and lines as hOCR documents, one for each page, with bounding boxes in
+* points from the top left page corner
+*/
+export declare function exportHocr(pdfiumDir: string, pdfPath: string, options?: ExtractOptions | undefined | null): Promise<Array<string>>
+/**
+* Export document layout as ALTO XML with text blocks, lines and strings, positions are in
+* pixels at 72 DPI from the top left page corner
+*/
+export declare function exportAlto(pdfiumDir: string, pdfPath: string, options?: ExtractOptions | undefined | null): Promise<string>
+/**
+* Convert document into Markdown, one string for each page, with headings, lists, tables and
+* images saved into images folder or returned as buffers like in text and images extraction
+*/
+export declare function convertToMarkdown(pdfiumDir: string, pdfPath: string, options?: ExtractOptions | undefined | null): Promise<Array<string>>
+/** Export document as HTML with each page word in absolutely positioned span, sizes in points */
+export declare function exportHtml(pdfiumDir: string, pdfPath: string, options?: ExtractOptions | undefined | null): Promise<string>
+/**
+* Page layout with blocks in reading order, bounds in page space points, origin at the bottom
+* left page corner
+*/
+export interface LayoutPage {
+  pageIndex: number
+  width: number
+  height: number
+  blocks: Array<LayoutBlock>
+}
+/** Layout block type */
+export const enum LayoutBlockKind {
+  Heading = 'heading',
+  Paragraph = 'paragraph',
+  List = 'list',
+  Table = 'table',
+  Image = 'image'
+}
+/** Block of text lines, table or image */
+export interface LayoutBlock {
+  kind: LayoutBlockKind
+  /** Heading level 1 or 2, set for headings */
+  headingLevel?: number
+  left: number
+  top: number
+  right: number
+  bottom: number
+  /** Block text lines, empty for images */
+  lines: Array<LayoutLine>
+}
+/** Text line with its dominant font style and words */
+export interface LayoutLine {
+  text: string
+  left: number
+  top: number
+  right: number
+  bottom: number
+  fontName: string
+  fontSize: number
+  /** Font weight from 100 to 900, 400 is normal and 700 is bold */
+  fontWeight: number
+  isItalic: boolean
+  /** Text fill color in hex like "#FF0000" */
+  fillColor: string
+  words: Array<ExtractedWord>
+}
+/**
+* Extract document layout as pages of headings, paragraphs, lists, tables and images blocks
+* with their lines and words
+*/
+export declare function extractLayout(pdfiumDir: string, pdfPath: string, options?: ExtractOptions | undefined | null): Promise<Array<LayoutPage>>
+/** Embedded font program format, detected from its data */
+export const enum FontFormat {
+  /** TrueType */
+  Ttf = 'ttf',
+  /** OpenType with CFF outlines */
+  Otf = 'otf',
+  /** Bare CFF, usually of Type1C and CIDFontType0C fonts */
+  Cff = 'cff',
+  /** Type 1 PostScript font */
+  Type1 = 'type1',
+  Unknown = 'unknown'
+}
+/** Font program embedded in the document */
+export interface ExtractedFont {
+  /** Base font name without subset prefix, like "Arial-BoldMT" */
+  name: string
+  /** Prefix of subset fonts with only used glyphs embedded, like "ABCDEF" */
+  subsetPrefix?: string
+  format: FontFormat
+  sizeBytes: number
+  /** First page using the font */
+  pageIndex: number
+  /** Saved file name, set when fonts are saved into output folder */
+  filename?: string
+  /** Font program, set when fonts are returned as buffers */
+  data?: Buffer
+}
+/** Font used by document text */
+export interface FontInfo {
+  /** Base font name without subset prefix, like "Arial-BoldMT" */
+  name: string
+  /** Prefix of subset fonts with only used glyphs embedded, like "ABCDEF" */
+  subsetPrefix?: string
+  /** Font dictionary subtype, like "TrueType" or "Type0" */
+  fontType?: string
+  embedded: boolean
+  subset: boolean
+  /**
+  * Encoding name like "WinAnsiEncoding" or "Identity-H", "Custom" for encoding dictionaries and
+  * "Builtin" for font program encoding
+  */
+  encoding?: string
+  /**
+  * Font maps character codes to unicode text, text of fonts without it and without standard
+  * encoding is often extracted wrong
+  */
+  hasToUnicode?: boolean
+  /** First page using the font */
+  pageIndex: number
+}
+/**
+* List fonts used by document text, font type, encoding and unicode map are read from font
+* dictionaries and are not set when no dictionary matches the font
+*/
+export declare function listFonts(pdfiumDir: string, pdfPath: string): Promise<Array<FontInfo>>
+/**
+* List fonts embedded in the document with their programs, optionally saving them or returning
+* their data
+*/
+export declare function extractFonts(pdfiumDir: string, pdfPath: string, options?: FontOptions | undefined | null): Promise<Array<ExtractedFont>>
+/**
+* Form field widget with its rectangle in page space points, radio buttons and checkboxes
+* of one group share the same name
+*/
+export interface ExtractedFormField {
+  pageIndex: number
+  name?: string
+  /** Form field type like `Text`, `Checkbox`, `RadioButton`, `ComboBox` or `ListBox` */
+  fieldType: string
+  /** Entered text, selected option or group value of checkboxes and radio buttons */
+  value?: string
+  /** Set for checkboxes and radio buttons */
+  isChecked?: boolean
+  /** Choices of combo and list boxes */
+  options: Array<string>
+  left: number
+  top: number
+  right: number
+  bottom: number
+}
+/** Extract interactive form fields with their values */
+export declare function extractFormFields(pdfiumDir: string, pdfPath: string, options?: ExtractOptions | undefined | null): Promise<Array<ExtractedFormField>>
+/**
+* Set form fields values by field name and save filled document into output path, checkboxes
+* take "true" or "false", radio buttons the export value of the button to select and combo and
+* list boxes the label of the option to select
+*/
+export declare function fillForm(pdfiumDir: string, pdfPath: string, values: Record<string, string>, outputPath: string): Promise<void>
+/** Open pdf document for repeated queries, configured with options used by all of them */
+export declare function openDocument(pdfiumDir: string, pdfPath: string, options?: ExtractOptions | undefined | null): Promise<PdfDocumentHandle>
+/** Diagnostic message level, from most to least severe */
+export const enum LogLevel {
+  Error = 'error',
+  Warn = 'warn',
+  Info = 'info',
+  Debug = 'debug'
+}
+/** Diagnostic message passed to logging callback */
+export interface LogMessage {
+  level: LogLevel
+  message: string
+}
+/**
+* Pass diagnostics up to level, `warn` by default, to callback instead of printing errors and
+* warnings to stderr, which is done again when callback is not set
+*/
+export declare function setLogger(callback?: (arg: LogMessage) => any | undefined | null, level?: LogLevel | undefined | null): void
+/** Page given recognized text layer */
+export interface SearchablePage {
+  pageIndex: number
+  /** Number of recognized text lines added to the page */
+  lineCount: number
+  /** Mean recognition confidence of page lines from 0 to 100 */
+  confidence: number
+}
+/**
+* Recognize text of scanned pages and save document with it added as invisible text over page
+* images, so the text can be searched and selected, requires build with `ocr` feature and
+* tesseract installed
+*/
+export declare function makeSearchable(pdfiumDir: string, pdfPath: string, outputPath: string, options?: SearchableOptions | undefined | null): Promise<Array<SearchablePage>>
+/** Sizes and changed images of optimized document */
+export interface OptimizedDocument {
+  inputSizeBytes: number
+  outputSizeBytes: number
+  /** Number of images replaced by smaller JPEG */
+  recompressedImages: number
+  /** Number of recompressed images scaled down to the maximum resolution */
+  downsampledImages: number
+}
+/**
+* Shrink document by recompressing images into JPEG where it is smaller, optionally scaling
+* down images shown in higher resolution than needed, and save it into output path without
+* objects no longer used
+*/
+export declare function optimizePdf(pdfiumDir: string, pdfPath: string, outputPath: string, options?: OptimizeOptions | undefined | null): Promise<OptimizedDocument>
+/** Extraction options */
+export interface ExtractOptions {
+  /** Folder to save extracted images into, created if not exist */
+  imagesFolderPath?: string
+  /** Extracted images format, PNG by default */
+  imageFormat?: ImageOutputFormat
+  /** Lossy images encoding quality from 1 to 100, used for JPEG */
+  imageQuality?: number
+  /**
+  * Extracted image filename with `{pdfStem}` pdf filename without extension, `{page}` page
+  * number, `{index}` image number on the page, `{n}` image number in the document and `{ext}`
+  * extension placeholders, `image-{n}.{ext}` by default
+  */
+  imageFilenameTemplate?: string
+  /**
+  * Save JPEG images with their original data as `.jpg` files instead of decoding and encoding
+  * them in image format, ignored when images are rendered in DPI
+  */
+  keepOriginalJpeg?: boolean
+  /**
+  * Turn and mirror extracted images as they are displayed on the page by their transformation
+  * and page rotation, ignored when images are rendered in DPI
+  */
+  applyImageTransform?: boolean
+  /** Render images at the size they appear on the page in this DPI instead of extracting embedded resolution */
+  imageDpi?: number
+  /** Save page vector graphics drawn with paths as SVG files alongside images */
+  exportVectorGraphics?: boolean
+  /**
+  * Return page text lines with their bounding rectangles, font style and color in
+  * `pageTextLineBounds`
+  */
+  includeTextLineBounds?: boolean
+  /**
+  * Classify text lines in `pageTextLineBounds` as headings or body text by their font size
+  * and weight relative to the document body text
+  */
+  detectHeadings?: boolean
+  /**
+  * Exclude text repeating at the same position in page top or bottom margin on most pages,
+  * like running headers and page numbers
+  */
+  stripHeadersAndFooters?: boolean
+  /** Merge consecutive text lines into paragraphs returned in `pageParagraphs` */
+  detectParagraphs?: boolean
+  /**
+  * Join words hyphenated at line end with their rest on the next line, like "instal-" and
+  * "lation" into "installation", when the next line starts lowercase
+  */
+  dehyphenate?: boolean
+  /**
+  * Return right to left text like Arabic or Hebrew in visual order as displayed from left
+  * to right instead of logical reading order
+  */
+  bidiVisualOrder?: boolean
+  /** Unicode normalization form of extracted text, not normalized by default */
+  unicodeNormalization?: UnicodeNormalization
+  /** Replace typographic ligatures like "ﬁ" and "ﬂ" with their letters */
+  expandLigatures?: boolean
+  /** Detect page text language returned in `language` */
+  detectLanguage?: boolean
+  /**
+  * Allowed vertical position difference of objects on the same line relative to their font
+  * size, like 0.4, 5 points regardless of font size by default
+  */
+  sameLineTolerance?: number
+  /**
+  * Emit text and images column by column using XY-cut page segmentation instead of
+  * sorting the whole page from top to bottom
+  */
+  columnReadingOrder?: boolean
+  /**
+  * Emit text lines of tagged pages in the order their structure tree lists them instead of by
+  * their position, images and untagged text like page artifacts keep their place
+  */
+  structureReadingOrder?: boolean
+  /**
+  * Place text in `extractTextWithOptions` output by its position on the page, padding
+  * words with spaces and lines with empty lines like `pdftotext -layout`
+  */
+  preserveLayout?: boolean
+  /**
+  * Return bounds on rotated pages in page space as displayed, origin at the bottom left
+  * corner of the rotated page
+  */
+  normalizeRotation?: boolean
+  /**
+  * Recognize text of pages without extractable text with tesseract, requires build with
+  * `ocr` feature and tesseract installed
+  */
+  ocr?: boolean
+  /** Tesseract languages like `eng+deu`, `eng` by default */
+  ocrLanguage?: string
+  /** Number of closest text lines related to each image, 2 by default */
+  relatedTextLines?: number
+  /**
+  * Take image related text lines above, below or on both sides of it, above or below images
+  * at page top by default
+  */
+  relatedTextPosition?: RelatedTextPosition
+  /**
+  * Maximum vertical distance in points between image and its related text lines, unlimited
+  * by default
+  */
+  relatedTextMaxDistance?: number
+  /** Return encoded images in `data` of image metadata instead of saving them into images folder */
+  imagesAsBuffers?: boolean
+  /**
+  * Scale down extracted images which width or height is larger than this in pixels keeping
+  * their aspect ratio, unlimited by default
+  */
+  maxImageDimension?: number
+  /** Skip images narrower than this in pixels */
+  minImageWidth?: number
+  /** Skip images lower than this in pixels */
+  minImageHeight?: number
+  /** Skip images which encoded size is smaller than this */
+  minFileSizeBytes?: number
+  /** Save identical images once, repeated images refer to the first extracted file */
+  deduplicateImages?: boolean
+  /** Exclude images repeating at the same position on most pages, like logos and border art */
+  excludeRecurringImages?: boolean
+  /** Fail with `LIMIT_EXCEEDED` error for documents with more pages */
+  maxPages?: number
+  /** Fail with `LIMIT_EXCEEDED` error once extracted images take more bytes in total */
+  maxTotalImageBytes?: number
+  /** Fail with `LIMIT_EXCEEDED` error before decoding an image with more pixels */
+  maxImagePixels?: number
+  /**
+  * Keep extracting when a page fails to be read, the page is left empty with the `PAGE_FAILED`
+  * error in its warnings, text and images extraction only
+  */
+  skipBadPages?: boolean
+  /** Measure extraction stages of each page, text and images extraction only */
+  includeTimings?: boolean
+  /** Called after each processed page */
+  onProgress?: (arg: ExtractProgress) => any
+  /** Stops extraction before the next page once aborted */
+  signal?: AbortSignal
+}
+/** Extracted images format */
+export const enum ImageOutputFormat {
+  Png = 'png',
+  Jpeg = 'jpeg',
+  Webp = 'webp'
+}
+/** Side of image to take its related text lines from */
+export const enum RelatedTextPosition {
+  Above = 'above',
+  Below = 'below',
+  Both = 'both'
+}
+/**
+* Unicode normalization form, compatibility form also replaces ligatures, full width and
+* other compatibility characters
+*/
+export const enum UnicodeNormalization {
+  Nfc = 'nfc',
+  Nfkc = 'nfkc'
+}
+/** Pages rendering options */
+export interface RenderOptions {
+  /** Indices of pages to render, all pages by default */
+  pages?: Array<number>
+  /** Rendering resolution, 150 by default */
+  dpi?: number
+  /** Rendered images format, PNG by default */
+  format?: ImageOutputFormat
+  /** Lossy images encoding quality from 1 to 100, used for JPEG */
+  quality?: number
+  /** Folder to save rendered images into, created if not exist */
+  outputFolderPath?: string
+  /** Return encoded images in `data` instead of saving them into output folder */
+  asBuffers?: boolean
+  /**
+  * Number of page images encoded at the same time by `renderDocument`, available CPUs by
+  * default
+  */
+  concurrency?: number
+  /** Square tile side in pixels used by `renderTiles`, 256 by default */
+  tileSize?: number
+}
+/** Visual documents comparison options */
+export interface CompareOptions {
+  /** Rendering resolution, 72 by default */
+  dpi?: number
+  /** Largest color channel difference from 0 to 255 of pixels considered same, 16 by default */
+  threshold?: number
+  /** Create images highlighting differing pixels of pages */
+  diffImages?: boolean
+  /** Diff images format, PNG by default */
+  format?: ImageOutputFormat
+  /** Folder to save diff images into, created if not exist */
+  outputFolderPath?: string
+  /** Return diff images in `data` instead of saving them into output folder */
+  asBuffers?: boolean
+}
+/** Attachments extraction options */
+export interface AttachmentOptions {
+  /** Folder to save attachments into, created if not exist */
+  outputFolderPath?: string
+  /** Return attachments content in `data` instead of saving them into output folder */
+  asBuffers?: boolean
+}
+/** Embedded fonts extraction options */
+export interface FontOptions {
+  /** Folder to save font programs into, created if not exist */
+  outputFolderPath?: string
+  /** Return font programs in `data` instead of saving them into output folder */
+  asBuffers?: boolean
+}
+/** Document splitting options */
+export interface SplitOptions {
+  /** Number of pages in each output document, 1 by default */
+  pagesPerFile?: number
+}
+/** Images to pdf conversion options */
+export interface ImagesToPdfOptions {
+  /** Resolution of images to size pages by, 72 by default */
+  dpi?: number
+  /** Page width in points, image width with margins by default */
+  pageWidth?: number
+  /** Page height in points, image height with margins by default */
+  pageHeight?: number
+  /** Space around images in points, 0 by default */
+  margin?: number
+}
+/** Document optimization options */
+export interface OptimizeOptions {
+  /** JPEG quality of recompressed images from 1 to 100, 80 by default */
+  quality?: number
+  /**
+  * Scale down images shown in higher resolution than this, images keep their resolution by
+  * default
+  */
+  maxImageDpi?: number
+}
+/** Options of adding recognized text layer to scanned pages */
+export interface SearchableOptions {
+  /** Indices of pages to recognize, scanned pages without recognized text layer by default */
+  pages?: Array<number>
+  /** Tesseract languages like `eng+deu`, `eng` by default */
+  ocrLanguage?: string
+  /**
+  * Path to TrueType font embedded for recognized text, standard Helvetica by default which
+  * writes WinAnsi characters only
+  */
+  fontPath?: string
+}
+/** Text watermark or image stamp options, either text or image path is required */
+export interface StampOptions {
+  /** Watermark text */
+  text?: string
+  /** Path to stamp image */
+  imagePath?: string
+  /** Indices of pages to stamp, all pages by default */
+  pages?: Array<number>
+  /** Watermark font size in points, 48 by default */
+  fontSize?: number
+  /** Stamp width in points, image size at 72 DPI by default */
+  imageWidth?: number
+  /** Counterclockwise angle in degrees as page is displayed, 0 by default */
+  angle?: number
+  /** Opacity from 0 to 1, 0.5 by default */
+  opacity?: number
+  /** Horizontal position of stamp center in page space points, page center by default */
+  x?: number
+  /** Vertical position of stamp center in page space points, page center by default */
+  y?: number
+}
+/** Text search options */
+export interface SearchOptions {
+  /** Match letter case */
+  matchCase?: boolean
+  /** Match whole words only */
+  matchWholeWord?: boolean
+}
+/** Redaction options, matches of all terms and patterns are removed */
+export interface RedactOptions {
+  /** Exact text to redact, case sensitive */
+  terms?: Array<string>
+  /** Regular expressions to redact like `[\w.]+@\w+\.\w+` */
+  patterns?: Array<string>
+  /** Indices of pages to redact, all pages by default */
+  pages?: Array<number>
+}
+/** Document saved from a range of source pages */
+export interface SplitDocument {
+  /** Document filename in output folder */
+  filename: string
+  firstPageIndex: number
+  pageCount: number
+}
+/** Split document into files with one or configured number of pages each */
+export declare function splitPdf(pdfiumDir: string, pdfPath: string, outputFolderPath: string, options?: SplitOptions | undefined | null): Promise<Array<SplitDocument>>
+/** Concatenate documents pages in the given order and save them into output path */
+export declare function mergePdfs(pdfiumDir: string, pdfPaths: Array<string>, outputPath: string): Promise<void>
+/** Create document with a page for each image in the given order and save it into output path */
+export declare function imagesToPdf(pdfiumDir: string, imagePaths: Array<string>, outputPath: string, options?: ImagesToPdfOptions | undefined | null): Promise<void>
+/**
+* Rotate selected pages clockwise by 90, 180 or 270 degrees on top of their current
+* rotation and save document into output path
+*/
+export declare function rotatePages(pdfiumDir: string, pdfPath: string, pageIndices: Array<number>, degrees: number, outputPath: string): Promise<void>
+/** Remove selected pages and save document into output path */
+export declare function removePages(pdfiumDir: string, pdfPath: string, pageIndices: Array<number>, outputPath: string): Promise<void>
+/**
+* Save document with pages in given order into output path, the order lists every source
+* page index once
+*/
+export declare function reorderPages(pdfiumDir: string, pdfPath: string, pageOrder: Array<number>, outputPath: string): Promise<void>
+/** Redacted text with its bounding rectangle in page space points */
+export interface RedactedMatch {
+  pageIndex: number
+  text: string
+  left: number
+  top: number
+  right: number
+  bottom: number
+}
+/**
+* Remove text matching terms or patterns from page content, cover it with black rectangles
+* and save document into output path
+*/
+export declare function redactText(pdfiumDir: string, pdfPath: string, options: RedactOptions, outputPath: string): Promise<Array<RedactedMatch>>
+/** Rendered page image */
+export interface RenderedPage {
+  pageIndex: number
+  /** Image filename */
+  filename: string
+  /** Image width in pixels */
+  width: number
+  /** Image height in pixels */
+  height: number
+  fileSizeBytes: number
+  /** Encoded image, set when pages are returned as buffers instead of saved */
+  data?: Buffer
+}
+/** Rendered page tile */
+export interface RenderedTile {
+  pageIndex: number
+  /** Tile row counted from the page top */
+  row: number
+  /** Tile column counted from the page left */
+  column: number
+  /** Tile left offset in the whole page image pixels */
+  x: number
+  /** Tile top offset in the whole page image pixels */
+  y: number
+  /** Image filename */
+  filename: string
+  /** Tile width in pixels, smaller than tile size at the right page edge */
+  width: number
+  /** Tile height in pixels, smaller than tile size at the bottom page edge */
+  height: number
+  fileSizeBytes: number
+  /** Encoded image, set when tiles are returned as buffers instead of saved */
+  data?: Buffer
+}
+/** Visual difference of document pages */
+export interface PageDifference {
+  pageIndex: number
+  /** Percentage of differing pixels from 0 to 100, 100 for pages missing in one of documents */
+  differencePercent: number
+  /**
+  * Page image with differing pixels highlighted, set when diff images are requested for pages
+  * present in both documents
+  */
+  diffImage?: RenderedPage
+}
+/** Render pdf pages into images */
+export declare function renderPages(pdfiumDir: string, pdfPath: string, options?: RenderOptions | undefined | null): Promise<Array<RenderedPage>>
+/**
+* Render all pdf pages into images, pages are rendered one by one while their images are
+* encoded and saved in parallel
+*/
+export declare function renderDocument(pdfiumDir: string, pdfPath: string, options?: RenderOptions | undefined | null): Promise<Array<RenderedPage>>
+/** Render rectangular region of pdf page into image */
+export declare function renderRegion(pdfiumDir: string, pdfPath: string, pageIndex: number, region: PageRect, options?: RenderOptions | undefined | null): Promise<RenderedPage>
+/** Render pdf page into grid of square tiles, only one tile bitmap is kept in memory at a time */
+export declare function renderTiles(pdfiumDir: string, pdfPath: string, pageIndex: number, options?: RenderOptions | undefined | null): Promise<Array<RenderedTile>>
+/**
+* Compare rendered pages of two pdf documents pixel by pixel, catching layout and graphics
+* changes not visible in text
+*/
+export declare function comparePages(pdfiumDir: string, pdfPath: string, otherPdfPath: string, options?: CompareOptions | undefined | null): Promise<Array<PageDifference>>
+/** Document signature fields and applied signatures */
+export interface DocumentSignatures {
+  /** Document has signature form fields, signed or not */
+  hasSignatureFields: boolean
+  signatures: Array<ExtractedSignature>
+}
+/** Applied digital signature, not verified */
+export interface ExtractedSignature {
+  /** Signer name written into signature dictionary, certificate subject is not read */
+  signerName?: string
+  /** Signing time in PDF date format like "D:20240102030405Z" */
+  signingDate?: string
+  reason?: string
+  /** Signed byte ranges as offset and length pairs */
+  byteRange: Array<number>
+  /** Signed bytes reach the end of file, false when document was changed after signing */
+  coversWholeDocument: boolean
+}
+/** Detect signature fields and read applied signatures details */
+export declare function extractSignatures(pdfiumDir: string, pdfPath: string): Promise<DocumentSignatures>
+/** Draw text watermark or image stamp on selected pages and save document into output path */
+export declare function stampPages(pdfiumDir: string, pdfPath: string, options: StampOptions, outputPath: string): Promise<void>
+/** Element of tagged document structure tree */
+export interface StructureElement {
+  /** Structure type like "P", "H1", "Table" or "Figure" */
+  role: string
+  pageIndex: number
+  title?: string
+  /** Alternate description, usually of figures */
+  altText?: string
+  /** Replacement text of element content, like of hyphenated words */
+  actualText?: string
+  /** Language like "en-US" */
+  lang?: string
+  /** Text of element own content, without text of its children */
+  text?: string
+  children: Array<StructureElement>
+}
+/**
+* Extract structure tree of tagged document, listed page by page as pdfium reads it, empty for
+* untagged documents
+*/
+export declare function extractStructureTree(pdfiumDir: string, pdfPath: string): Promise<Array<StructureElement>>
+/** Accessibility screening of the document */
+export interface AccessibilityReport {
+  /** Document is tagged with structure tree */
+  isTagged: boolean
+  title?: string
+  /** Document language like "en-US", read from document catalog stored uncompressed */
+  language?: string
+  figureCount: number
+  /** Page index of each figure without alternate description */
+  figuresWithoutAltText: Array<number>
+  /** Failed checks, empty when all of them pass */
+  issues: Array<string>
+}
+/**
+* Check the document is tagged, its figures have alternate descriptions and it declares title
+* and language
+*/
+export declare function checkAccessibility(pdfiumDir: string, pdfPath: string): Promise<AccessibilityReport>
+/** Table detected by its ruling lines, bounds in page space points */
+export interface ExtractedTable {
+  pageIndex: number
+  left: number
+  top: number
+  right: number
+  bottom: number
+  /** Table rows from top to bottom with cells from left to right */
+  rows: Array<Array<ExtractedTableCell>>
+}
+/** Table cell, cells spanning several columns are merged */
+export interface ExtractedTableCell {
+  /** Cell text lines joined with new lines */
+  text: string
+  left: number
+  top: number
+  right: number
+  bottom: number
+}
+/** Extract tables drawn with ruling lines with their cells text */
+export declare function extractTables(pdfiumDir: string, pdfPath: string, options?: ExtractOptions | undefined | null): Promise<Array<ExtractedTable>>
+/** Word with its bounding rectangle in page space points, origin at the bottom left page corner */
+export interface ExtractedWord {
+  text: string
+  pageIndex: number
+  left: number
+  top: number
+  right: number
+  bottom: number
+}
+/** Page character with its bounding rectangle in page space points */
+export interface ExtractedChar {
+  /** Unicode character, may be whitespace generated by pdfium between words and lines */
+  text: string
+  left: number
+  top: number
+  right: number
+  bottom: number
+  /** Effective font size in points */
+  fontSize: number
+  /** Counter clockwise rotation in degrees */
+  rotation: number
+}
+/** Extract words with their positions on the page */
+export declare function extractWords(pdfiumDir: string, pdfPath: string, options?: ExtractOptions | undefined | null): Promise<Array<ExtractedWord>>
+/**
+* Text search match with its bounding rectangle in page space points, origin at the bottom
+* left page corner
+*/
+export interface TextSearchMatch {
+  /** Matched text as written on the page */
+  text: string
+  pageIndex: number
+  left: number
+  top: number
+  right: number
+  bottom: number
+  /** Rectangle of each matched line, matches can wrap onto the next line */
+  rects: Array<PageRect>
+}
+/** Find text occurrences with their positions using pdfium text search */
+export declare function searchText(pdfiumDir: string, pdfPath: string, query: string, searchOptions?: SearchOptions | undefined | null, options?: ExtractOptions | undefined | null): Promise<Array<TextSearchMatch>>
+/** Extract page characters with their positions, font size and rotation */
+export declare function extractChars(pdfiumDir: string, pdfPath: string, pageIndex: number, options?: ExtractOptions | undefined | null): Promise<Array<ExtractedChar>>
+/** Word and character counts of the document and each page */
+export interface TextStats {
+  wordCount: number
+  /** Characters other than whitespace */
+  charCount: number
+  pages: Array<PageTextStats>
+}
+export interface PageTextStats {
+  pageIndex: number
+  wordCount: number
+  /** Characters other than whitespace, pages without any usually need OCR */
+  charCount: number
+}
+/** Count words and characters of document text without extracting it */
+export declare function getTextStats(pdfiumDir: string, pdfPath: string): Promise<TextStats>
+/** XMP metadata packet with its Dublin Core fields */
+export interface XmpMetadata {
+  /** Raw XMP packet */
+  packet: string
+  title?: string
+  creators: Array<string>
+  description?: string
+  /** Keywords */
+  subjects: Array<string>
+  rights?: string
+}
+/** Read XMP metadata packet of the document catalog */
+export declare function getXmpMetadata(pdfiumDir: string, pdfPath: string): Promise<XmpMetadata | null>
 /** Extracted image metadata */
 export interface ExtractedImageMeta {
   /** Image filename */
   filename: string
   fileSizeBytes: number
-  /** Two closest to image text lines above or below */
+  /** Closest to image text lines above or below, two by default */
   relatedText: Array<string>
+  /** Encoded image, set when images are returned as buffers instead of saved */
+  data?: Buffer
+  /** Filename of the first identical image when images are deduplicated */
+  duplicateOf?: string
+  pageIndex: number
+  /**
+  * Image bounding rectangle on the page in page space points, origin at the bottom left page
+  * corner
+  */
+  left: number
+  top: number
+  right: number
+  bottom: number
+  /** Extracted image size in pixels, not set for vector graphics */
+  width?: number
+  height?: number
+  /** Embedded image resolution at its size on the page, lower of horizontal and vertical */
+  dpi?: number
 }
 export interface ExtractedPage {
+  /**
+  * Page number printed on the page like "iv" or "A-1", set when the document defines page
+  * labels
+  */
+  pageLabel?: string
   /** Page images */
   pageImages: Array<ExtractedImageMeta>
   /** Page text lines */
   pageTextLines: Array<string>
+  /** Page vector graphics as SVG, set when vector graphics are exported */
+  pageVectorGraphics?: Array<ExtractedImageMeta>
+  /** Page text lines with their bounds, set when text line bounds are requested */
+  pageTextLineBounds?: Array<ExtractedTextLine>
+  /** Page text lines merged into paragraphs, set when paragraphs detection is requested */
+  pageParagraphs?: Array<string>
+  /**
+  * OCR confidence of each page text line from 0 to 100, set when page text is recognized by
+  * OCR
+  */
+  pageTextLineConfidences?: Array<number>
+  /** Page is a scanned image without extractable text, regardless of OCR */
+  isScanned: boolean
+  /**
+  * ISO 639-3 code of page text language like "eng", set when language detection is
+  * requested and the language is reliably detected
+  */
+  language?: string
+  /** What was skipped on the page, set when bad pages are skipped */
+  warnings?: Array<string>
+  /** Extraction stage timings, set when timings are requested */
+  timings?: PageTimings
+}
+/**
+* Text line with its bounding rectangle in page space points, origin at the bottom left page
+* corner, and dominant font style and color
+*/
+export interface ExtractedTextLine {
+  text: string
+  pageIndex: number
+  left: number
+  top: number
+  right: number
+  bottom: number
+  /** Dominant font of the line */
+  fontName: string
+  fontSize: number
+  /** Font weight from 100 to 900, 400 is normal and 700 is bold */
+  fontWeight: number
+  isItalic: boolean
+  /** Text fill color in hex like "#FF0000" */
+  fillColor: string
+  /** Heading level or body text, set when headings detection is requested */
+  kind?: TextLineKind
+  /** OCR confidence from 0 to 100, set when the line is recognized by OCR */
+  confidence?: number
+}
+/** Text line classification */
+export const enum TextLineKind {
+  H1 = 'h1',
+  H2 = 'h2',
+  Body = 'body'
+}
+/** Rectangle in page space points, origin at the bottom left page corner */
+export interface PageRect {
+  left: number
+  top: number
+  right: number
+  bottom: number
+}
+/** Extraction progress reported after each processed page */
+export interface ExtractProgress {
+  pageIndex: number
+  totalPages: number
+  /** Images extracted so far over all processed pages */
+  imagesExtracted: number
+}
+/** Page extraction stage timings in milliseconds */
+export interface PageTimings {
+  /** Document load, set on the first page */
+  documentLoadMs?: number
+  /** Whole page extraction */
+  pageMs: number
+  /** Page extraction without images decoding, encoding and OCR, mostly text extraction */
+  textMs: number
+  imageDecodeMs: number
+  imageEncodeMs: number
+  /** Scanned page text recognition */
+  ocrMs: number
 }
 /** Extract text from pdf files in lines and images with related text */
 export declare function extractTextAndImages(pdfiumDir: string, pdfPath: string, imagesFolderPath: string): Promise<Array<ExtractedPage>>
+/** Extract text from pdf files in lines and images with related text, configured with options */
+export declare function extractTextAndImagesWithOptions(pdfiumDir: string, pdfPath: string, options?: ExtractOptions | undefined | null): Promise<Array<ExtractedPage>>
+/**
+* Extract text lines and images with related text of single pdf page, configured with options,
+* images of pages saved into one folder need `{page}` in image filename template
+*/
+export declare function extractPage(pdfiumDir: string, pdfPath: string, pageIndex: number, options?: ExtractOptions | undefined | null): Promise<ExtractedPage>
 /** Extract text from pdf files in lines */
 export declare function extractText(pdfiumDir: string, pdfPath: string): Promise<Array<string>>
+/** Extract text from pdf files in lines, configured with options */
+export declare function extractTextWithOptions(pdfiumDir: string, pdfPath: string, options?: ExtractOptions | undefined | null): Promise<Array<string>>
+/**
+* Bind pdfium library from folder or library file used by all functions, replacing library
+* bound from a different path before once running functions finish. Functions bind library from
+* their pdfium folder when it is not initialized
+*/
+export declare function initPdfium(pdfiumDir: string): Promise<void>
+/** Pdf document opened once for repeated queries until it is closed */
+export class PdfDocumentHandle {
+  /** Count document pages */
+  pageCount(): Promise<number>
+  /** Extract page text like `extractTextWithOptions` */
+  pageText(pageIndex: number): Promise<string>
+  /** Extract page images with related text like `extractTextAndImagesWithOptions` */
+  pageImages(pageIndex: number): Promise<Array<ExtractedImageMeta>>
+  /** Read document metadata */
+  metadata(): Promise<DocumentMetadata>
+  /** Close document, queries fail once it is closed */
+  close(): Promise<void>
+}
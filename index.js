@@ -310,7 +310,67 @@ if (!nativeBinding) {
   throw new Error(`Failed to load native binding`)
 }
 
-const { extractTextAndImages, extractText } = nativeBinding
+const { extractAnnotations, extractLinks, extractAttachments, StandardFont, createPdf, extractBookmarks, extractNamedDestinations, getDocumentMetadata, getPageCount, PageOrientation, getPageDimensions, isLinearized, getSecurityInfo, exportHocr, exportAlto, convertToMarkdown, exportHtml, LayoutBlockKind, extractLayout, FontFormat, listFonts, extractFonts, extractFormFields, fillForm, PdfDocumentHandle, openDocument, LogLevel, setLogger, makeSearchable, optimizePdf, ImageOutputFormat, RelatedTextPosition, UnicodeNormalization, splitPdf, mergePdfs, imagesToPdf, rotatePages, removePages, reorderPages, redactText, renderPages, renderDocument, renderRegion, renderTiles, comparePages, extractSignatures, stampPages, extractStructureTree, checkAccessibility, extractTables, extractWords, searchText, extractChars, getTextStats, getXmpMetadata, TextLineKind, extractTextAndImages, extractTextAndImagesWithOptions, extractPage, extractText, extractTextWithOptions, initPdfium } = nativeBinding
 
+module.exports.extractAnnotations = extractAnnotations
+module.exports.extractLinks = extractLinks
+module.exports.extractAttachments = extractAttachments
+module.exports.StandardFont = StandardFont
+module.exports.createPdf = createPdf
+module.exports.extractBookmarks = extractBookmarks
+module.exports.extractNamedDestinations = extractNamedDestinations
+module.exports.getDocumentMetadata = getDocumentMetadata
+module.exports.getPageCount = getPageCount
+module.exports.PageOrientation = PageOrientation
+module.exports.getPageDimensions = getPageDimensions
+module.exports.isLinearized = isLinearized
+module.exports.getSecurityInfo = getSecurityInfo
+module.exports.exportHocr = exportHocr
+module.exports.exportAlto = exportAlto
+module.exports.convertToMarkdown = convertToMarkdown
+module.exports.exportHtml = exportHtml
+module.exports.LayoutBlockKind = LayoutBlockKind
+module.exports.extractLayout = extractLayout
+module.exports.FontFormat = FontFormat
+module.exports.listFonts = listFonts
+module.exports.extractFonts = extractFonts
+module.exports.extractFormFields = extractFormFields
+module.exports.fillForm = fillForm
+module.exports.PdfDocumentHandle = PdfDocumentHandle
+module.exports.openDocument = openDocument
+module.exports.LogLevel = LogLevel
+module.exports.setLogger = setLogger
+module.exports.makeSearchable = makeSearchable
+module.exports.optimizePdf = optimizePdf
+module.exports.ImageOutputFormat = ImageOutputFormat
+module.exports.RelatedTextPosition = RelatedTextPosition
+module.exports.UnicodeNormalization = UnicodeNormalization
+module.exports.splitPdf = splitPdf
+module.exports.mergePdfs = mergePdfs
+module.exports.imagesToPdf = imagesToPdf
+module.exports.rotatePages = rotatePages
+module.exports.removePages = removePages
+module.exports.reorderPages = reorderPages
+module.exports.redactText = redactText
+module.exports.renderPages = renderPages
+module.exports.renderDocument = renderDocument
+module.exports.renderRegion = renderRegion
+module.exports.renderTiles = renderTiles
+module.exports.comparePages = comparePages
+module.exports.extractSignatures = extractSignatures
+module.exports.stampPages = stampPages
+module.exports.extractStructureTree = extractStructureTree
+module.exports.checkAccessibility = checkAccessibility
+module.exports.extractTables = extractTables
+module.exports.extractWords = extractWords
+module.exports.searchText = searchText
+module.exports.extractChars = extractChars
+module.exports.getTextStats = getTextStats
+module.exports.getXmpMetadata = getXmpMetadata
+module.exports.TextLineKind = TextLineKind
 module.exports.extractTextAndImages = extractTextAndImages
+module.exports.extractTextAndImagesWithOptions = extractTextAndImagesWithOptions
+module.exports.extractPage = extractPage
 module.exports.extractText = extractText
+module.exports.extractTextWithOptions = extractTextWithOptions
+module.exports.initPdfium = initPdfium
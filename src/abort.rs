@@ -0,0 +1,65 @@
+use napi::bindgen_prelude::{FromNapiValue, TypeName, ValidateNapiValue};
+use napi::{sys, Env, JsFunction, JsObject, JsUnknown, ValueType};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::errors::{ErrorCode, PdfError};
+
+/// Aborted state of a JS `AbortSignal`, readable off the main thread
+#[derive(Clone, Default)]
+pub struct AbortFlag(Arc<AtomicBool>);
+
+impl AbortFlag {
+  pub fn is_aborted(&self) -> bool {
+    self.0.load(Ordering::Relaxed)
+  }
+
+  /// Error returned when extraction is stopped by the signal
+  pub fn check(&self) -> napi::Result<()> {
+    if self.is_aborted() {
+      return Err(PdfError::new(ErrorCode::Cancelled, "Extraction aborted").into());
+    }
+    Ok(())
+  }
+}
+
+impl TypeName for AbortFlag {
+  fn type_name() -> &'static str {
+    "AbortSignal"
+  }
+
+  fn value_type() -> ValueType {
+    ValueType::Object
+  }
+}
+
+impl ValidateNapiValue for AbortFlag {}
+
+impl FromNapiValue for AbortFlag {
+  unsafe fn from_napi_value(env: sys::napi_env, napi_val: sys::napi_value) -> napi::Result<Self> {
+    let js_env = Env::from_raw(env);
+    let signal = JsObject::from_napi_value(env, napi_val)?;
+    let flag = AbortFlag::default();
+
+    if signal.get_named_property::<bool>("aborted")? {
+      flag.0.store(true, Ordering::Relaxed);
+    }
+
+    // Flip the flag from the "abort" event, the extraction loop polls it between pages
+    let listener_flag = flag.clone();
+    let on_abort = js_env.create_function_from_closure("onabort", move |_| {
+      listener_flag.0.store(true, Ordering::Relaxed);
+      Ok(())
+    })?;
+    let add_event_listener: JsFunction = signal.get_named_property("addEventListener")?;
+    add_event_listener.call(
+      Some(&signal),
+      &[
+        js_env.create_string("abort")?.into_unknown(),
+        on_abort.into_unknown(),
+      ] as &[JsUnknown],
+    )?;
+
+    Ok(flag)
+  }
+}
@@ -0,0 +1,170 @@
+use pdfium_render::prelude::*;
+
+use crate::errors::PdfError;
+use crate::layout::{rect_bounds, PageRotation};
+use crate::options::ExtractOptions;
+use crate::{
+  check_aborted, check_page_limit, get_pdfium, load_document, report_progress, run_blocking,
+};
+
+#[napi(object)]
+/// Link annotation with its anchor rectangle in page space points
+pub struct ExtractedLink {
+  pub page_index: u32,
+  /// External link target
+  pub uri: Option<String>,
+  /// Target page of link inside the document
+  pub destination_page_index: Option<u32>,
+  /// Page text under the link anchor
+  pub text: String,
+  pub left: f64,
+  pub top: f64,
+  pub right: f64,
+  pub bottom: f64,
+}
+
+#[napi(object)]
+/// Page annotation with its rectangle in page space points
+pub struct ExtractedAnnotation {
+  pub page_index: u32,
+  /// PDF annotation subtype like `Text`, `Highlight` or `Link`
+  pub annotation_type: String,
+  pub contents: Option<String>,
+  pub author: Option<String>,
+  /// Last modification date in PDF date format like "D:20240102030405Z"
+  pub modification_date: Option<String>,
+  /// Annotation color in hex like "#FFFF00"
+  pub color: Option<String>,
+  pub left: f64,
+  pub top: f64,
+  pub right: f64,
+  pub bottom: f64,
+}
+
+#[napi(catch_unwind)]
+/// Extract page annotations like comments and highlights
+pub async fn extract_annotations(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+  options: Option<ExtractOptions>,
+) -> napi::Result<Vec<ExtractedAnnotation>> {
+  run_blocking(move || {
+    let options = options.unwrap_or_default();
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let document = load_document(pdfium, &pdf_path)?;
+    check_page_limit(&document, &options)?;
+
+    let mut result: Vec<ExtractedAnnotation> = vec![];
+    let total_pages = document.pages().len() as u32;
+
+    for (page_index, page) in document.pages().iter().enumerate() {
+      check_aborted(&options)?;
+      let rotation = PageRotation::of(&page, &options);
+
+      for annotation in page.annotations().iter() {
+        let bounds = annotation.bounds().unwrap_or(PdfRect::ZERO);
+        let (left, top, right, bottom) = rect_bounds(&bounds, rotation);
+
+        result.push(ExtractedAnnotation {
+          page_index: page_index as u32,
+          annotation_type: format!("{:?}", annotation.annotation_type()),
+          contents: annotation
+            .contents()
+            .filter(|contents| !contents.is_empty()),
+          author: annotation.creator().filter(|author| !author.is_empty()),
+          modification_date: annotation
+            .modification_date()
+            .filter(|date| !date.is_empty()),
+          color: annotation
+            .stroke_color()
+            .ok()
+            .map(|color| format!("#{}", color.to_hex())),
+          left,
+          top,
+          right,
+          bottom,
+        });
+      }
+
+      report_progress(&options, page_index as u32, total_pages, 0);
+    }
+
+    Ok(result)
+  })
+  .await
+}
+
+#[napi(catch_unwind)]
+/// Extract page links with their targets and anchor text
+pub async fn extract_links(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+  options: Option<ExtractOptions>,
+) -> napi::Result<Vec<ExtractedLink>> {
+  run_blocking(move || {
+    let options = options.unwrap_or_default();
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let document = load_document(pdfium, &pdf_path)?;
+    check_page_limit(&document, &options)?;
+
+    let mut result: Vec<ExtractedLink> = vec![];
+    let total_pages = document.pages().len() as u32;
+
+    for (page_index, page) in document.pages().iter().enumerate() {
+      check_aborted(&options)?;
+      let rotation = PageRotation::of(&page, &options);
+
+      let text_page: PdfPageText<'_> = page.text().map_err(|err| {
+        PdfError::page(page_index, "Failed to read pdf document page").caused_by(err)
+      })?;
+
+      for annotation in page.annotations().iter() {
+        let link = match annotation.as_link_annotation().map(|a| a.link()) {
+          Some(Ok(link)) => link,
+          _ => continue,
+        };
+        let bounds = match annotation.bounds() {
+          Ok(bounds) => bounds,
+          Err(_) => continue,
+        };
+
+        let (left, top, right, bottom) = rect_bounds(&bounds, rotation);
+
+        let action = link.action();
+        let uri = action
+          .as_ref()
+          .and_then(|action| action.as_uri_action())
+          .and_then(|action| action.uri().ok());
+        // destination is set on the link itself or with go to action
+        let destination_page_index = link
+          .destination()
+          .or_else(|| {
+            action
+              .as_ref()
+              .and_then(|action| action.as_local_destination_action())
+              .and_then(|action| action.destination().ok())
+          })
+          .and_then(|destination| destination.page_index().ok())
+          .map(|page_index| page_index as u32);
+
+        result.push(ExtractedLink {
+          page_index: page_index as u32,
+          uri,
+          destination_page_index,
+          text: text_page.inside_rect(bounds).trim().to_owned(),
+          left,
+          top,
+          right,
+          bottom,
+        });
+      }
+
+      report_progress(&options, page_index as u32, total_pages, 0);
+    }
+
+    Ok(result)
+  })
+  .await
+}
@@ -0,0 +1,233 @@
+use napi::bindgen_prelude::Buffer;
+use std::collections::HashSet;
+use std::fs::create_dir_all;
+use std::path::Path;
+
+use crate::errors::{ErrorCode, PdfError};
+use crate::objects::{array_items, decrypted_data, name_value, reference_value, PdfObjects};
+use crate::options::AttachmentOptions;
+use crate::{get_pdfium, load_document, run_blocking, RawDocument};
+
+// depth limit of embedded files name tree, malformed trees may list their own nodes as kids
+static MAX_NAME_TREE_DEPTH: usize = 32;
+
+#[napi(object)]
+/// Embedded file attachment
+pub struct ExtractedAttachment {
+  /// Attachment file name
+  pub name: String,
+  /// MIME type of the embedded file, guessed from file name extension when it has none
+  pub mime_type: String,
+  pub size_bytes: u32,
+  /// Saved file name, set when attachments are saved into output folder
+  pub filename: Option<String>,
+  /// Attachment content, set when attachments are returned as buffers
+  pub data: Option<Buffer>,
+}
+
+#[napi(catch_unwind)]
+/// List embedded file attachments, optionally saving them or returning their content
+pub async fn extract_attachments(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+  options: Option<AttachmentOptions>,
+) -> napi::Result<Vec<ExtractedAttachment>> {
+  run_blocking(move || {
+    let options = options.unwrap_or_default();
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let document = load_document(pdfium, &pdf_path)?;
+
+    let as_buffers = options.as_buffers.unwrap_or(false);
+    let output_folder_path = options
+      .output_folder_path
+      .as_deref()
+      .filter(|path| !as_buffers && !path.is_empty())
+      .map(Path::new);
+    if let Some(output_folder_path) = output_folder_path {
+      create_dir_all(output_folder_path).map_err(|err| {
+        PdfError::new(ErrorCode::SaveFailed, "Failed to create output folder").caused_by(err)
+      })?;
+    }
+
+    let subtypes = match document.attachments().is_empty() {
+      true => vec![],
+      false => embedded_file_subtypes(&RawDocument::new(pdfium.bindings(), &pdf_path)),
+    };
+
+    let mut filenames = HashSet::new();
+    let mut result: Vec<ExtractedAttachment> = vec![];
+
+    for (index, attachment) in document.attachments().iter().enumerate() {
+      let name = attachment.name();
+      let load = || {
+        attachment.save_to_bytes().map_err(|err| {
+          PdfError::new(
+            ErrorCode::LoadFailed,
+            format!("Failed to read pdf attachment {}", name),
+          )
+          .caused_by(err)
+        })
+      };
+
+      let data = as_buffers.then(load).transpose()?.map(Buffer::from);
+      let filename = match output_folder_path {
+        Some(output_folder_path) => {
+          // attachment names are not trusted paths, keep the file name only
+          let base_name = Path::new(&name)
+            .file_name()
+            .map(|filename| filename.to_string_lossy().into_owned())
+            .filter(|filename| !filename.is_empty())
+            .unwrap_or_else(|| format!("attachment-{}", index + 1));
+          let base_path = Path::new(&base_name);
+          let mut filename = base_name.clone();
+          let mut n = 2;
+          while !filenames.insert(filename.clone()) {
+            let stem = base_path.file_stem().unwrap_or_default().to_string_lossy();
+            filename = match base_path.extension() {
+              Some(extension) => format!("{}-{}.{}", stem, n, extension.to_string_lossy()),
+              None => format!("{}-{}", base_name, n),
+            };
+            n += 1;
+          }
+          std::fs::write(output_folder_path.join(&filename), load()?).map_err(|err| {
+            PdfError::new(
+              ErrorCode::SaveFailed,
+              format!("Failed to save attachment {}", filename),
+            )
+            .caused_by(err)
+          })?;
+          Some(filename)
+        }
+        None => None,
+      };
+
+      result.push(ExtractedAttachment {
+        mime_type: subtypes
+          .get(index)
+          .cloned()
+          .flatten()
+          .unwrap_or_else(|| mime_type(&name).to_owned()),
+        size_bytes: attachment.len() as u32,
+        name,
+        filename,
+        data,
+      });
+    }
+
+    Ok(result)
+  })
+  .await
+}
+
+/// Subtypes of embedded file streams in attachment order, read from the catalog embedded files
+/// name tree as pdfium-render does not expose them
+fn embedded_file_subtypes(raw_document: &RawDocument) -> Vec<Option<String>> {
+  let Some(data) = decrypted_data(raw_document) else {
+    return vec![];
+  };
+  let Some(objects) = PdfObjects::parse(&data) else {
+    return vec![];
+  };
+  let mut file_specs = vec![];
+  if let Some(root) = objects
+    .catalog()
+    .and_then(|catalog| objects.dictionary_value(&catalog, "/Names"))
+    .and_then(|names| objects.dictionary_value(&names, "/EmbeddedFiles"))
+  {
+    name_tree_values(&objects, &root, 0, &mut file_specs);
+  }
+
+  file_specs
+    .into_iter()
+    .map(|file_spec| {
+      let embedded_files = objects.dictionary_value(&file_spec?, "/EF")?;
+      let stream = reference_value(&embedded_files, "/UF")
+        .or_else(|| reference_value(&embedded_files, "/F"))?;
+      name_value(&objects.dictionary(stream)?, "/Subtype").map(|subtype| decoded_name(&subtype))
+    })
+    .collect()
+}
+
+/// Dictionary values of name tree node and its kids in key order, the order of pdfium attachments
+fn name_tree_values(
+  objects: &PdfObjects,
+  node: &str,
+  depth: usize,
+  values: &mut Vec<Option<String>>,
+) {
+  if depth > MAX_NAME_TREE_DEPTH {
+    return;
+  }
+  if let Some(kids) = array_items(node, "/Kids") {
+    for kid in kids.chunks_exact(3) {
+      if let Some(kid) = kid[0].parse().ok().and_then(|kid| objects.dictionary(kid)) {
+        name_tree_values(objects, &kid, depth + 1, values);
+      }
+    }
+    return;
+  }
+
+  // keys are followed by inline dictionaries or references to them
+  let mut items = array_items(node, "/Names").unwrap_or_default().into_iter();
+  while let (Some(_), Some(value)) = (items.next(), items.next()) {
+    values.push(match value.starts_with("<<") {
+      true => Some(value.to_owned()),
+      false => {
+        items.nth(1);
+        value
+          .parse()
+          .ok()
+          .and_then(|value| objects.dictionary(value))
+      }
+    });
+  }
+}
+
+/// Name with its `#xx` escaped characters decoded, like `text#2Fcsv`
+fn decoded_name(name: &str) -> String {
+  let mut bytes = vec![];
+  let mut rest = name.as_bytes();
+  while let Some((first, tail)) = rest.split_first() {
+    let escaped = tail
+      .get(..2)
+      .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+    match (first, escaped) {
+      (b'#', Some(byte)) => {
+        bytes.push(byte);
+        rest = &tail[2..];
+      }
+      _ => {
+        bytes.push(*first);
+        rest = tail;
+      }
+    }
+  }
+  String::from_utf8_lossy(&bytes).into_owned()
+}
+
+fn mime_type(name: &str) -> &'static str {
+  let extension = Path::new(name)
+    .extension()
+    .map(|extension| extension.to_string_lossy().to_lowercase())
+    .unwrap_or_default();
+  match extension.as_str() {
+    "pdf" => "application/pdf",
+    "txt" => "text/plain",
+    "csv" => "text/csv",
+    "xml" => "application/xml",
+    "json" => "application/json",
+    "htm" | "html" => "text/html",
+    "png" => "image/png",
+    "jpg" | "jpeg" => "image/jpeg",
+    "gif" => "image/gif",
+    "tif" | "tiff" => "image/tiff",
+    "zip" => "application/zip",
+    "doc" => "application/msword",
+    "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    "xls" => "application/vnd.ms-excel",
+    "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    "step" | "stp" => "model/step",
+    _ => "application/octet-stream",
+  }
+}
@@ -0,0 +1,278 @@
+use pdfium_render::prelude::*;
+
+use crate::errors::{ErrorCode, PdfError};
+use crate::pages::open_oriented_image;
+use crate::{get_pdfium, run_blocking, save_document};
+
+// default page size, US letter in points
+static DEFAULT_PAGE_WIDTH: f64 = 612.0;
+static DEFAULT_PAGE_HEIGHT: f64 = 792.0;
+// default text size in points
+static DEFAULT_FONT_SIZE: f64 = 12.0;
+// default distance between text lines baselines relative to font size
+static DEFAULT_LINE_HEIGHT: f64 = 1.2;
+
+#[napi(string_enum = "lowercase")]
+/// Standard font available without embedding
+pub enum StandardFont {
+  Helvetica,
+  Times,
+  Courier,
+}
+
+#[napi(object)]
+/// Text placed on created page, lines are split on new line characters
+pub struct TextBlock {
+  pub text: String,
+  /// Left edge of the block in points from the left page edge
+  pub x: f64,
+  /// Top edge of the block in points from the bottom page edge
+  pub y: f64,
+  /// Font family, Helvetica by default
+  pub font: Option<StandardFont>,
+  pub bold: Option<bool>,
+  pub italic: Option<bool>,
+  /// Font size in points, 12 by default
+  pub font_size: Option<f64>,
+  /// Distance between lines baselines relative to font size, 1.2 by default
+  pub line_height: Option<f64>,
+  /// Width in points to wrap lines at word boundaries, lines are not wrapped by default
+  pub max_width: Option<f64>,
+  /// Text color in hex like "#FF0000", black by default
+  pub color: Option<String>,
+}
+
+#[napi(object)]
+/// Image placed on created page
+pub struct ImageBlock {
+  pub image_path: String,
+  /// Left edge of the image in points from the left page edge
+  pub x: f64,
+  /// Bottom edge of the image in points from the bottom page edge
+  pub y: f64,
+  /// Image width in points, scaled by height or image size at 72 DPI by default
+  pub width: Option<f64>,
+  /// Image height in points, scaled by width or image size at 72 DPI by default
+  pub height: Option<f64>,
+}
+
+#[napi(object)]
+/// Content of created page
+pub struct PageContent {
+  /// Page width in points, 612 by default
+  pub width: Option<f64>,
+  /// Page height in points, 792 by default
+  pub height: Option<f64>,
+  pub texts: Option<Vec<TextBlock>>,
+  /// Images drawn below texts
+  pub images: Option<Vec<ImageBlock>>,
+}
+
+#[napi(catch_unwind)]
+/// Create document from pages with positioned text blocks and images and save it into output
+/// path
+pub async fn create_pdf(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pages: Vec<PageContent>,
+  output_path: String,
+) -> napi::Result<()> {
+  run_blocking(move || {
+    if pages.is_empty() {
+      return Err(PdfError::new(ErrorCode::InvalidArgument, "No pages to create").into());
+    }
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+
+    let mut document = pdfium
+      .create_new_pdf()
+      .map_err(|err| PdfError::edit("Failed to create pdf document").caused_by(err))?;
+    // standard fonts loaded once per document
+    let mut fonts: Vec<(PdfFontBuiltin, PdfFontToken)> = vec![];
+
+    for (page_index, content) in pages.iter().enumerate() {
+      let mut objects: Vec<PdfPageObject> = vec![];
+
+      for block in content.images.iter().flatten() {
+        let image = open_oriented_image(&block.image_path).map_err(|err| {
+          PdfError::new(
+            ErrorCode::LoadFailed,
+            format!("Failed to open image {}", block.image_path),
+          )
+          .caused_by(err)
+        })?;
+        let (image_width, image_height) = (image.width() as f64, image.height() as f64);
+        let (width, height) = match (block.width, block.height) {
+          (Some(width), Some(height)) => (width, height),
+          (Some(width), None) => (width, width * image_height / image_width),
+          (None, Some(height)) => (height * image_width / image_height, height),
+          (None, None) => (image_width, image_height),
+        };
+        let mut object = PdfPageImageObject::new_with_size(
+          &document,
+          &image,
+          PdfPoints::new(width as f32),
+          PdfPoints::new(height as f32),
+        )
+        .map_err(|err| {
+          PdfError::edit(format!("Failed to create image {}", block.image_path))
+            .on_page(page_index)
+            .caused_by(err)
+        })?;
+        object
+          .translate(
+            PdfPoints::new(block.x as f32),
+            PdfPoints::new(block.y as f32),
+          )
+          .map_err(|err| {
+            PdfError::edit("Failed to place image")
+              .on_page(page_index)
+              .caused_by(err)
+          })?;
+        objects.push(object.into());
+      }
+
+      for block in content.texts.iter().flatten() {
+        let builtin = builtin_font(
+          block.font.unwrap_or(StandardFont::Helvetica),
+          block.bold.unwrap_or(false),
+          block.italic.unwrap_or(false),
+        );
+        let font = match fonts.iter().find(|(loaded, _)| *loaded == builtin) {
+          Some((_, font)) => *font,
+          None => {
+            let font = document.fonts_mut().new_built_in(builtin);
+            fonts.push((builtin, font));
+            font
+          }
+        };
+        let font_size = PdfPoints::new(block.font_size.unwrap_or(DEFAULT_FONT_SIZE) as f32);
+        let color = match &block.color {
+          Some(color) => PdfColor::from_hex(color).map_err(|err| {
+            PdfError::new(
+              ErrorCode::InvalidArgument,
+              format!("Invalid text color {}", color),
+            )
+            .caused_by(err)
+          })?,
+          None => PdfColor::BLACK,
+        };
+        let line_height = font_size.value * block.line_height.unwrap_or(DEFAULT_LINE_HEIGHT) as f32;
+
+        let mut lines = vec![];
+        for paragraph in block.text.lines() {
+          match block.max_width {
+            Some(max_width) => lines.extend(wrap_line(
+              &document,
+              paragraph,
+              font,
+              font_size,
+              max_width as f32,
+            )?),
+            None => lines.push(paragraph.to_string()),
+          }
+        }
+
+        // first line baseline is one font size below the block top
+        let mut baseline = block.y as f32 - font_size.value;
+        for line in lines {
+          if !line.is_empty() {
+            let mut object =
+              PdfPageTextObject::new(&document, &line, font, font_size).map_err(|err| {
+                PdfError::edit("Failed to create text")
+                  .on_page(page_index)
+                  .caused_by(err)
+              })?;
+            object
+              .set_fill_color(color)
+              .and_then(|_| {
+                object.translate(PdfPoints::new(block.x as f32), PdfPoints::new(baseline))
+              })
+              .map_err(|err| {
+                PdfError::edit("Failed to place text")
+                  .on_page(page_index)
+                  .caused_by(err)
+              })?;
+            objects.push(object.into());
+          }
+          baseline -= line_height;
+        }
+      }
+
+      let mut page = document
+        .pages_mut()
+        .create_page_at_end(PdfPagePaperSize::new_custom(
+          PdfPoints::new(content.width.unwrap_or(DEFAULT_PAGE_WIDTH) as f32),
+          PdfPoints::new(content.height.unwrap_or(DEFAULT_PAGE_HEIGHT) as f32),
+        ))
+        .map_err(|err| {
+          PdfError::edit("Failed to create page")
+            .on_page(page_index)
+            .caused_by(err)
+        })?;
+      for object in objects {
+        page.objects_mut().add_object(object).map_err(|err| {
+          PdfError::edit("Failed to fill page")
+            .on_page(page_index)
+            .caused_by(err)
+        })?;
+      }
+    }
+
+    save_document(document, &output_path)
+  })
+  .await
+}
+
+fn builtin_font(font: StandardFont, bold: bool, italic: bool) -> PdfFontBuiltin {
+  match (font, bold, italic) {
+    (StandardFont::Helvetica, false, false) => PdfFontBuiltin::Helvetica,
+    (StandardFont::Helvetica, true, false) => PdfFontBuiltin::HelveticaBold,
+    (StandardFont::Helvetica, false, true) => PdfFontBuiltin::HelveticaOblique,
+    (StandardFont::Helvetica, true, true) => PdfFontBuiltin::HelveticaBoldOblique,
+    (StandardFont::Times, false, false) => PdfFontBuiltin::TimesRoman,
+    (StandardFont::Times, true, false) => PdfFontBuiltin::TimesBold,
+    (StandardFont::Times, false, true) => PdfFontBuiltin::TimesItalic,
+    (StandardFont::Times, true, true) => PdfFontBuiltin::TimesBoldItalic,
+    (StandardFont::Courier, false, false) => PdfFontBuiltin::Courier,
+    (StandardFont::Courier, true, false) => PdfFontBuiltin::CourierBold,
+    (StandardFont::Courier, false, true) => PdfFontBuiltin::CourierOblique,
+    (StandardFont::Courier, true, true) => PdfFontBuiltin::CourierBoldOblique,
+  }
+}
+
+/// Split line at spaces into lines fitting the width, words wider than it keep their own line
+fn wrap_line(
+  document: &PdfDocument,
+  line: &str,
+  font: PdfFontToken,
+  font_size: PdfPoints,
+  max_width: f32,
+) -> napi::Result<Vec<String>> {
+  let measure = |text: &str| -> napi::Result<f32> {
+    PdfPageTextObject::new(document, text, font, font_size)
+      .and_then(|object| object.width())
+      .map(|width| width.value)
+      .map_err(|err| {
+        PdfError::edit("Failed to measure text")
+          .caused_by(err)
+          .into()
+      })
+  };
+
+  let mut lines = vec![];
+  let mut current = String::new();
+  for word in line.split_whitespace() {
+    let candidate = if current.is_empty() {
+      word.to_string()
+    } else {
+      format!("{} {}", current, word)
+    };
+    if !current.is_empty() && measure(&candidate)? > max_width {
+      lines.push(std::mem::replace(&mut current, word.to_string()));
+    } else {
+      current = candidate;
+    }
+  }
+  lines.push(current);
+  Ok(lines)
+}
@@ -0,0 +1,467 @@
+use pdfium_render::prelude::*;
+use std::fs::{self, File};
+use std::io::Read;
+use std::os::raw::{c_int, c_long, c_ulong, c_void};
+
+use crate::errors::{ErrorCode, PdfError};
+use crate::objects::{find, find_last};
+use crate::ocr::is_scanned_page;
+use crate::structure::pdfium_string;
+use crate::{get_pdfium, load_document, run_blocking, RawDocument};
+
+// linearization dictionary must be within this many bytes from the file start
+static LINEARIZATION_HEADER_LENGTH: u64 = 1024;
+
+// pdfium destination view types with position parameters, constants to be matched on
+const DEST_VIEW_FIT_H: c_ulong = 3;
+const DEST_VIEW_FIT_V: c_ulong = 4;
+const DEST_VIEW_FIT_R: c_ulong = 5;
+const DEST_VIEW_FIT_BH: c_ulong = 7;
+const DEST_VIEW_FIT_BV: c_ulong = 8;
+
+#[napi(object)]
+/// Document outline entry
+pub struct ExtractedBookmark {
+  pub title: String,
+  /// Target page of bookmark inside the document
+  pub page_index: Option<u32>,
+  pub children: Vec<ExtractedBookmark>,
+}
+
+#[napi(catch_unwind)]
+/// Extract document outline as bookmarks tree
+pub async fn extract_bookmarks(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+) -> napi::Result<Vec<ExtractedBookmark>> {
+  run_blocking(move || {
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let document = load_document(pdfium, &pdf_path)?;
+
+    Ok(sibling_bookmarks(document.bookmarks().root()))
+  })
+  .await
+}
+
+/// Bookmark with its following siblings and their descendants
+fn sibling_bookmarks(first: Option<PdfBookmark>) -> Vec<ExtractedBookmark> {
+  let mut result: Vec<ExtractedBookmark> = vec![];
+
+  let mut bookmark = first;
+  while let Some(current) = bookmark {
+    // destination is set on the bookmark itself or with go to action
+    let action = current.action();
+    let page_index = current
+      .destination()
+      .or_else(|| {
+        action
+          .as_ref()
+          .and_then(|action| action.as_local_destination_action())
+          .and_then(|action| action.destination().ok())
+      })
+      .and_then(|destination| destination.page_index().ok())
+      .map(|page_index| page_index as u32);
+
+    result.push(ExtractedBookmark {
+      title: current.title().unwrap_or_default(),
+      page_index,
+      children: sibling_bookmarks(current.first_child()),
+    });
+    bookmark = current.next_sibling();
+  }
+
+  result
+}
+
+#[napi(object)]
+/// Named destination with its target position in page space points, origin at the bottom left
+/// page corner
+pub struct NamedDestination {
+  pub name: String,
+  /// Target page inside the document
+  pub page_index: Option<u32>,
+  /// Target position, set when the destination specifies it
+  pub left: Option<f64>,
+  pub top: Option<f64>,
+  /// Zoom factor, set when the destination specifies it
+  pub zoom: Option<f64>,
+}
+
+#[napi(catch_unwind)]
+/// Extract named destinations which links and bookmarks can target by name
+pub async fn extract_named_destinations(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+) -> napi::Result<Vec<NamedDestination>> {
+  run_blocking(move || {
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    // destinations are read through pdfium bindings as pdfium-render does not list them
+    let bindings = pdfium.bindings();
+    let raw_document = RawDocument::new(bindings, &pdf_path);
+    let document = raw_document.loaded_handle()?;
+
+    let mut result: Vec<NamedDestination> = vec![];
+    for index in 0..bindings.FPDF_CountNamedDests(document) as c_int {
+      // name is UTF-16LE with NUL terminator, its length in bytes is returned for empty buffer
+      let mut length: c_long = 0;
+      let destination =
+        bindings.FPDF_GetNamedDest(document, index, std::ptr::null_mut(), &mut length);
+      if destination.is_null() || length <= 0 {
+        continue;
+      }
+      let mut buffer = vec![0u8; length as usize];
+      let destination = bindings.FPDF_GetNamedDest(
+        document,
+        index,
+        buffer.as_mut_ptr() as *mut c_void,
+        &mut length,
+      );
+      if destination.is_null() {
+        continue;
+      }
+      let units: Vec<u16> = buffer
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .take_while(|unit| *unit != 0)
+        .collect();
+
+      let page_index = bindings.FPDFDest_GetDestPageIndex(document, destination);
+      let (mut has_left, mut has_top, mut has_zoom) = (0, 0, 0);
+      let (mut left, mut top, mut zoom) = (0.0, 0.0, 0.0);
+      let has_location = bindings.is_true(bindings.FPDFDest_GetLocationInPage(
+        destination,
+        &mut has_left,
+        &mut has_top,
+        &mut has_zoom,
+        &mut left,
+        &mut top,
+        &mut zoom,
+      ));
+      let value = |has_value, value: f32| {
+        (has_location && bindings.is_true(has_value)).then_some(value as f64)
+      };
+      let (mut left, mut top) = (value(has_left, left), value(has_top, top));
+
+      // pdfium reads location of XYZ destinations only, fit views have it in their parameters
+      if !has_location {
+        let (mut params_count, mut params) = (0, [0.0f32; 4]);
+        let view = bindings.FPDFDest_GetView(destination, &mut params_count, params.as_mut_ptr());
+        let param = |index: usize| (index < params_count as usize).then_some(params[index] as f64);
+        match view {
+          DEST_VIEW_FIT_H | DEST_VIEW_FIT_BH => top = param(0),
+          DEST_VIEW_FIT_V | DEST_VIEW_FIT_BV => left = param(0),
+          DEST_VIEW_FIT_R => (left, top) = (param(0), param(3)),
+          _ => {}
+        }
+      }
+
+      result.push(NamedDestination {
+        name: String::from_utf16_lossy(&units),
+        page_index: (page_index >= 0).then_some(page_index as u32),
+        left,
+        top,
+        zoom: value(has_zoom, zoom).filter(|zoom| *zoom != 0.0),
+      });
+    }
+
+    Ok(result)
+  })
+  .await
+}
+
+#[napi(object)]
+/// Document information dictionary
+pub struct DocumentMetadata {
+  pub title: Option<String>,
+  pub author: Option<String>,
+  pub subject: Option<String>,
+  pub keywords: Option<String>,
+  pub creator: Option<String>,
+  pub producer: Option<String>,
+  /// Creation date in PDF date format like "D:20240102030405Z"
+  pub creation_date: Option<String>,
+  /// Last modification date in PDF date format
+  pub modification_date: Option<String>,
+  pub page_count: u32,
+}
+
+#[napi(catch_unwind)]
+/// Read document metadata without loading its pages
+pub async fn get_document_metadata(
+  pdfium_dir: String,
+  pdf_path: String,
+) -> napi::Result<DocumentMetadata> {
+  run_blocking(move || {
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let document = load_document(pdfium, &pdf_path)?;
+    let raw_document = RawDocument::new(pdfium.bindings(), &pdf_path);
+
+    Ok(document_metadata(&document, &raw_document))
+  })
+  .await
+}
+
+pub fn document_metadata(document: &PdfDocument, raw_document: &RawDocument) -> DocumentMetadata {
+  let metadata = document.metadata();
+  let tag = |tag_type: PdfDocumentMetadataTagType| {
+    metadata
+      .get(tag_type)
+      .map(|tag| tag.value().to_owned())
+      .filter(|value| !value.is_empty())
+  };
+
+  DocumentMetadata {
+    title: tag(PdfDocumentMetadataTagType::Title),
+    author: tag(PdfDocumentMetadataTagType::Author),
+    subject: tag(PdfDocumentMetadataTagType::Subject),
+    keywords: tag(PdfDocumentMetadataTagType::Keywords),
+    creator: tag(PdfDocumentMetadataTagType::Creator),
+    producer: tag(PdfDocumentMetadataTagType::Producer),
+    creation_date: tag(PdfDocumentMetadataTagType::CreationDate),
+    // pdfium-render reads modification date from "ModificationDate" instead of "ModDate" key
+    modification_date: raw_document
+      .handle()
+      .and_then(|handle| {
+        let bindings = raw_document.bindings();
+        pdfium_string(|buffer, length| bindings.FPDF_GetMetaText(handle, "ModDate", buffer, length))
+      })
+      .filter(|value| !value.is_empty()),
+    page_count: document.pages().len() as u32,
+  }
+}
+
+#[napi(catch_unwind)]
+/// Count document pages without loading them
+pub async fn get_page_count(pdfium_dir: String, pdf_path: String) -> napi::Result<u32> {
+  run_blocking(move || {
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let document = load_document(pdfium, &pdf_path)?;
+
+    Ok(document.pages().len() as u32)
+  })
+  .await
+}
+
+#[napi(object)]
+/// Page size and rotation
+pub struct PageDimensions {
+  pub page_index: u32,
+  /// Page number printed on the page like "iv" or "A-1", set when the document defines page
+  /// labels
+  pub page_label: Option<String>,
+  /// Page width in points as displayed, with rotation applied
+  pub width: f64,
+  /// Page height in points as displayed, with rotation applied
+  pub height: f64,
+  /// Clockwise page rotation in degrees, 0, 90, 180 or 270
+  pub rotation: u32,
+  pub orientation: PageOrientation,
+  /// Page is a scanned image without extractable text
+  pub is_scanned: bool,
+}
+
+#[napi(string_enum = "lowercase")]
+/// Displayed page orientation
+pub enum PageOrientation {
+  Portrait,
+  Landscape,
+}
+
+#[napi(catch_unwind)]
+/// Read pages size, rotation, orientation and whether they are scanned
+pub async fn get_page_dimensions(
+  pdfium_dir: String,
+  pdf_path: String,
+) -> napi::Result<Vec<PageDimensions>> {
+  run_blocking(move || {
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let document = load_document(pdfium, &pdf_path)?;
+
+    Ok(
+      document
+        .pages()
+        .iter()
+        .enumerate()
+        .map(|(page_index, page)| PageDimensions {
+          page_index: page_index as u32,
+          page_label: page.label().map(str::to_owned),
+          width: page.width().value as f64,
+          height: page.height().value as f64,
+          rotation: rotation_degrees(&page),
+          orientation: if page.is_landscape() {
+            PageOrientation::Landscape
+          } else {
+            PageOrientation::Portrait
+          },
+          is_scanned: is_scanned_page(&page),
+        })
+        .collect(),
+    )
+  })
+  .await
+}
+
+/// Clockwise page rotation in degrees
+pub fn rotation_degrees(page: &PdfPage) -> u32 {
+  match page.rotation() {
+    Ok(PdfPageRenderRotation::Degrees90) => 90,
+    Ok(PdfPageRenderRotation::Degrees180) => 180,
+    Ok(PdfPageRenderRotation::Degrees270) => 270,
+    _ => 0,
+  }
+}
+
+#[napi(catch_unwind)]
+/// Check the document is linearized for fast web view and was not updated since
+pub async fn is_linearized(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+) -> napi::Result<bool> {
+  run_blocking(move || {
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    // fail with the reason reported by pdfium-render before reading the document directly
+    drop(load_document(pdfium, &pdf_path)?);
+    let file = File::open(&pdf_path).map_err(|err| {
+      PdfError::new(ErrorCode::LoadFailed, "Failed to open pdf document").caused_by(err)
+    })?;
+    let read_failed =
+      |err| PdfError::new(ErrorCode::LoadFailed, "Failed to read pdf document").caused_by(err);
+    let file_length = file.metadata().map_err(read_failed)?.len();
+
+    // linearization dictionary is the first object of the file
+    let mut header = vec![];
+    file
+      .take(LINEARIZATION_HEADER_LENGTH)
+      .read_to_end(&mut header)
+      .map_err(read_failed)?;
+    let header = String::from_utf8_lossy(&header);
+
+    let start = match header.find("/Linearized") {
+      Some(start) => start,
+      None => return Ok(false),
+    };
+    let end = header[start..]
+      .find(">>")
+      .map_or(header.len(), |end| start + end);
+    let dictionary = &header[start..end];
+    // file length written on linearization changes with incremental updates
+    let length = dictionary_integer(dictionary, "/L");
+
+    Ok(length == Some(file_length as i64))
+  })
+  .await
+}
+
+#[napi(object)]
+/// Document encryption and permissions granted to the user
+pub struct SecurityInfo {
+  pub is_encrypted: bool,
+  /// Standard security handler revision, not set for unprotected documents
+  pub encryption_revision: Option<u32>,
+  pub can_print: bool,
+  pub can_print_high_quality: bool,
+  /// Copy or extract text and graphics
+  pub can_copy: bool,
+  pub can_modify: bool,
+  /// Add or modify annotations
+  pub can_annotate: bool,
+  pub can_fill_forms: bool,
+  /// Insert, rotate or delete pages
+  pub can_assemble: bool,
+}
+
+#[napi(catch_unwind)]
+/// Read document encryption and permissions
+pub async fn get_security_info(pdfium_dir: String, pdf_path: String) -> napi::Result<SecurityInfo> {
+  run_blocking(move || {
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let document = load_document(pdfium, &pdf_path)?;
+
+    let is_encrypted = !matches!(
+      document.permissions().security_handler_revision(),
+      Ok(PdfSecurityHandlerRevision::Unprotected)
+    );
+    if !is_encrypted {
+      return Ok(SecurityInfo {
+        is_encrypted,
+        encryption_revision: None,
+        can_print: true,
+        can_print_high_quality: true,
+        can_copy: true,
+        can_modify: true,
+        can_annotate: true,
+        can_fill_forms: true,
+        can_assemble: true,
+      });
+    }
+
+    // pdfium-render checks accessibility extraction bit for copying and does not know newer
+    // revisions, read revision and permission bits from encryption dictionary instead
+    let data = fs::read(&pdf_path).map_err(|err| {
+      PdfError::new(ErrorCode::LoadFailed, "Failed to open pdf document").caused_by(err)
+    })?;
+    let (revision, permissions) = match encryption_dictionary(&data) {
+      Some(dictionary) => (
+        dictionary_integer(&dictionary, "/R").map(|revision| revision as u32),
+        dictionary_integer(&dictionary, "/P").unwrap_or(0) as u32,
+      ),
+      // nothing is granted when permissions are unknown
+      None => (None, 0),
+    };
+    let bit = |n: u32| permissions & (1 << (n - 1)) != 0;
+    let revision_3 = revision.is_none_or(|revision| revision >= 3);
+
+    Ok(SecurityInfo {
+      is_encrypted,
+      encryption_revision: revision,
+      can_print: bit(3),
+      can_print_high_quality: bit(3) && (!revision_3 || bit(12)),
+      can_copy: bit(5),
+      can_modify: bit(4),
+      can_annotate: bit(6),
+      can_fill_forms: bit(6) || (revision_3 && bit(9)),
+      can_assemble: if revision_3 { bit(11) } else { bit(4) },
+    })
+  })
+  .await
+}
+
+/// Encryption dictionary of the last trailer, never compressed or encrypted
+fn encryption_dictionary(data: &[u8]) -> Option<String> {
+  let start = find_last(data, b"/Encrypt")? + b"/Encrypt".len();
+  let rest = String::from_utf8_lossy(&data[start..data.len().min(start + 64)]);
+
+  // direct dictionary is written in the trailer itself
+  if rest.trim_start().starts_with("<<") {
+    let end = find(&data[start..], b"startxref").map_or(data.len(), |end| start + end);
+    return Some(String::from_utf8_lossy(&data[start..end]).into_owned());
+  }
+
+  let mut parts = rest.split_whitespace();
+  let (number, generation) = (
+    parts.next()?.parse::<u32>().ok()?,
+    parts.next()?.parse::<u32>().ok()?,
+  );
+  let object = format!("{} {} obj", number, generation);
+  let object_start = data
+    .windows(object.len())
+    .enumerate()
+    // object number must not be the end of a longer number
+    .find(|(i, window)| *window == object.as_bytes() && (*i == 0 || !data[i - 1].is_ascii_digit()))?
+    .0;
+  let object_end = object_start + find(&data[object_start..], b"endobj")?;
+  Some(String::from_utf8_lossy(&data[object_start..object_end]).into_owned())
+}
+
+fn dictionary_integer(dictionary: &str, key: &str) -> Option<i64> {
+  dictionary.split(key).skip(1).find_map(|rest| {
+    // skip other keys sharing the prefix
+    if !rest.starts_with(char::is_whitespace) {
+      return None;
+    }
+    rest.split_whitespace().next()?.parse::<i64>().ok()
+  })
+}
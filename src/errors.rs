@@ -0,0 +1,149 @@
+use pdfium_render::prelude::{PdfiumError, PdfiumInternalError};
+use std::fmt::{self, Display, Formatter};
+
+/// Stable code of a failed call, the error message starts with it as `CODE: ...` as errors of
+/// napi async functions can't have custom codes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ErrorCode {
+  LoadFailed,
+  BadPassword,
+  PageFailed,
+  ImageSaveFailed,
+  LimitExceeded,
+  Unsupported,
+  InvalidArgument,
+  EditFailed,
+  SaveFailed,
+  DocumentClosed,
+  OcrFailed,
+  BindFailed,
+  TaskFailed,
+  Cancelled,
+}
+
+impl ErrorCode {
+  pub fn as_str(self) -> &'static str {
+    match self {
+      ErrorCode::LoadFailed => "LOAD_FAILED",
+      ErrorCode::BadPassword => "BAD_PASSWORD",
+      ErrorCode::PageFailed => "PAGE_FAILED",
+      ErrorCode::ImageSaveFailed => "IMAGE_SAVE_FAILED",
+      ErrorCode::LimitExceeded => "LIMIT_EXCEEDED",
+      ErrorCode::Unsupported => "UNSUPPORTED",
+      ErrorCode::InvalidArgument => "INVALID_ARGUMENT",
+      ErrorCode::EditFailed => "EDIT_FAILED",
+      ErrorCode::SaveFailed => "SAVE_FAILED",
+      ErrorCode::DocumentClosed => "DOCUMENT_CLOSED",
+      ErrorCode::OcrFailed => "OCR_FAILED",
+      ErrorCode::BindFailed => "BIND_FAILED",
+      ErrorCode::TaskFailed => "TASK_FAILED",
+      ErrorCode::Cancelled => "CANCELLED",
+    }
+  }
+}
+
+/// Error with code, index of the page it occurred on and the underlying error, turned into
+/// `CODE: message (page index N) - cause` message
+#[derive(Debug)]
+pub struct PdfError {
+  code: ErrorCode,
+  message: String,
+  page_index: Option<usize>,
+  cause: Option<String>,
+}
+
+impl PdfError {
+  pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+    PdfError {
+      code,
+      message: message.into(),
+      page_index: None,
+      cause: None,
+    }
+  }
+
+  /// Error of page which failed to load or read
+  pub fn page(page_index: usize, message: impl Into<String>) -> Self {
+    PdfError::new(ErrorCode::PageFailed, message).on_page(page_index)
+  }
+
+  /// Error of document or page objects which failed to be created or changed
+  pub fn edit(message: impl Into<String>) -> Self {
+    PdfError::new(ErrorCode::EditFailed, message)
+  }
+
+  /// Error of page index argument which is not less than the document page count
+  pub fn page_out_of_range(page_index: usize) -> Self {
+    PdfError::new(ErrorCode::InvalidArgument, "Page is out of range").on_page(page_index)
+  }
+
+  /// Error of document which failed to load, wrong or missing password has its own code
+  pub fn load(err: PdfiumError) -> Self {
+    match err {
+      PdfiumError::PdfiumLibraryInternalError(PdfiumInternalError::PasswordError) => PdfError::new(
+        ErrorCode::BadPassword,
+        "Pdf document is protected by password",
+      ),
+      err => PdfError::new(ErrorCode::LoadFailed, "Failed to read pdf document").caused_by(err),
+    }
+  }
+
+  pub fn on_page(mut self, page_index: usize) -> Self {
+    self.page_index = Some(page_index);
+    self
+  }
+
+  pub fn caused_by(mut self, cause: impl ErrorCause) -> Self {
+    self.cause = Some(cause.describe());
+    self
+  }
+}
+
+/// Underlying error described on a single line
+pub trait ErrorCause {
+  fn describe(&self) -> String;
+}
+
+// display of pdfium errors spans multiple lines
+impl ErrorCause for PdfiumError {
+  fn describe(&self) -> String {
+    format!("{:?}", self)
+  }
+}
+
+impl ErrorCause for std::io::Error {
+  fn describe(&self) -> String {
+    self.to_string()
+  }
+}
+
+impl ErrorCause for napi::tokio::task::JoinError {
+  fn describe(&self) -> String {
+    self.to_string()
+  }
+}
+
+impl ErrorCause for image::ImageError {
+  fn describe(&self) -> String {
+    self.to_string()
+  }
+}
+
+impl Display for PdfError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "{}: {}", self.code.as_str(), self.message)?;
+    if let Some(page_index) = self.page_index {
+      write!(f, " (page index {})", page_index)?;
+    }
+    if let Some(cause) = &self.cause {
+      write!(f, " - {}", cause)?;
+    }
+    Ok(())
+  }
+}
+
+impl From<PdfError> for napi::Error {
+  fn from(err: PdfError) -> Self {
+    napi::Error::from_reason(err.to_string())
+  }
+}
@@ -0,0 +1,867 @@
+use pdfium_render::prelude::*;
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::document::rotation_degrees;
+use crate::errors::PdfError;
+use crate::layout::{starts_paragraph, Bounds, PageRotation};
+use crate::options::ExtractOptions;
+use crate::tables::{page_tables, ExtractedTable};
+use crate::text::{page_words, ExtractedWord};
+use crate::{
+  check_aborted, check_page_limit, extract_pages, get_pdfium, image_rect, load_document,
+  page_images_bounds, report_progress, run_blocking, ExtractedImageMeta, ExtractedTextLine,
+  FileIndices, RawDocument, TextLineKind,
+};
+
+// page words grouped into lines, with page size in the coordinate space of words
+struct PageLayout {
+  width: f64,
+  height: f64,
+  lines: Vec<Vec<ExtractedWord>>,
+}
+
+fn page_layout(
+  page: &PdfPage,
+  page_index: u32,
+  options: &ExtractOptions,
+) -> napi::Result<PageLayout> {
+  let text_page = page.text().map_err(|err| {
+    PdfError::page(page_index as usize, "Failed to read pdf document page").caused_by(err)
+  })?;
+  let mut words = page_words(&text_page, page_index);
+
+  let rotation = PageRotation::of(page, options);
+  let (width, height) = page_size(page, options);
+  if let Some(rotation) = rotation {
+    for w in words.iter_mut() {
+      (w.left, w.top, w.right, w.bottom) = rotation.apply((w.left, w.top, w.right, w.bottom));
+    }
+  }
+
+  Ok(PageLayout {
+    width,
+    height,
+    lines: group_lines(words),
+  })
+}
+
+/// Page width and height in the coordinate space of extracted bounds
+fn page_size(page: &PdfPage, options: &ExtractOptions) -> (f64, f64) {
+  // pdfium reports page size with rotation applied
+  match (PageRotation::of(page, options), rotation_degrees(page)) {
+    (None, 90 | 270) => (page.height().value as f64, page.width().value as f64),
+    _ => (page.width().value as f64, page.height().value as f64),
+  }
+}
+
+/// Consecutive words with vertical middle inside the previous word continue its line
+fn group_lines(words: Vec<ExtractedWord>) -> Vec<Vec<ExtractedWord>> {
+  let mut lines: Vec<Vec<ExtractedWord>> = vec![];
+  for word in words {
+    let middle = (word.top + word.bottom) / 2.0;
+    let previous = lines.last().and_then(|line| line.last());
+    let same_line = previous.is_some_and(|previous| {
+      middle <= previous.top && middle >= previous.bottom && word.left >= previous.left
+    });
+    match lines.last_mut() {
+      Some(line) if same_line => line.push(word),
+      _ => lines.push(vec![word]),
+    }
+  }
+  lines
+}
+
+/// Page text with words placed in character columns and rows by their positions, keeping
+/// columns and tables aligned
+pub fn layout_text(
+  page: &PdfPage,
+  page_index: u32,
+  options: &ExtractOptions,
+) -> napi::Result<String> {
+  let mut words: Vec<ExtractedWord> = page_layout(page, page_index, options)?
+    .lines
+    .into_iter()
+    .flatten()
+    .collect();
+  if words.is_empty() {
+    return Ok(String::new());
+  }
+
+  // typical character width and line height of the page
+  let median = |mut values: Vec<f64>| {
+    values.sort_by(|a, b| a.total_cmp(b));
+    values[values.len() / 2]
+  };
+  let char_width = median(
+    words
+      .iter()
+      .map(|w| (w.right - w.left) / w.text.chars().count() as f64)
+      .collect(),
+  )
+  .max(1.0);
+  let line_height = median(words.iter().map(|w| w.top - w.bottom).collect()).max(1.0);
+
+  // rows from top to bottom, words with vertical middle inside the row continue it
+  words.sort_by(|a, b| b.top.total_cmp(&a.top));
+  let mut rows: Vec<Vec<ExtractedWord>> = vec![];
+  for word in words {
+    let middle = (word.top + word.bottom) / 2.0;
+    match rows.last_mut() {
+      Some(row) if middle >= words_bounds(row.iter()).3 => row.push(word),
+      _ => rows.push(vec![word]),
+    }
+  }
+
+  let first_column = rows
+    .iter()
+    .flatten()
+    .map(|w| (w.left / char_width).round() as usize)
+    .min()
+    .unwrap_or(0);
+  let mut text = String::new();
+  let mut last_bottom: Option<f64> = None;
+  for mut row in rows {
+    row.sort_by(|a, b| a.left.total_cmp(&b.left));
+    let (_, top, _, bottom) = words_bounds(row.iter());
+
+    // keep vertical gaps as empty lines
+    if let Some(last_bottom) = last_bottom {
+      let empty_lines = ((last_bottom - top) / line_height).round().max(0.0) as usize;
+      text.push_str(&"\n".repeat(empty_lines + 1));
+    }
+    last_bottom = Some(bottom);
+
+    let mut line = String::new();
+    let mut last_right: Option<f64> = None;
+    for w in row {
+      let column = ((w.left / char_width).round() as usize).saturating_sub(first_column);
+      let length = line.chars().count();
+      let padding = match last_right {
+        None => column,
+        // words of larger fonts take more columns, keep single space between close words
+        Some(last_right) if w.left - last_right < char_width * 2.0 => 1,
+        Some(_) => column.saturating_sub(length).max(1),
+      };
+      last_right = Some(w.right);
+      line.push_str(&" ".repeat(padding));
+      line.push_str(&w.text);
+    }
+    text.push_str(&line);
+  }
+
+  Ok(text)
+}
+
+/// Union of words bounds as left, top, right, bottom
+fn words_bounds<'a>(words: impl Iterator<Item = &'a ExtractedWord>) -> (f64, f64, f64, f64) {
+  words.fold(
+    (
+      f64::INFINITY,
+      f64::NEG_INFINITY,
+      f64::NEG_INFINITY,
+      f64::INFINITY,
+    ),
+    |(left, top, right, bottom), w| {
+      (
+        left.min(w.left),
+        top.max(w.top),
+        right.max(w.right),
+        bottom.min(w.bottom),
+      )
+    },
+  )
+}
+
+/// Consecutive lines split into blocks at paragraph breaks
+fn line_blocks(lines: &[Vec<ExtractedWord>]) -> Vec<&[Vec<ExtractedWord>]> {
+  let bounds = |line: &[ExtractedWord]| -> Bounds {
+    let (left, top, right, bottom) = words_bounds(line.iter());
+    (left as f32, bottom as f32, right as f32, top as f32)
+  };
+  lines
+    .chunk_by(|last, current| !starts_paragraph(bounds(last), bounds(current)))
+    .collect()
+}
+
+#[napi(catch_unwind)]
+/// Export page words and lines as hOCR documents, one for each page, with bounding boxes in
+/// points from the top left page corner
+pub async fn export_hocr(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+  options: Option<ExtractOptions>,
+) -> napi::Result<Vec<String>> {
+  run_blocking(move || {
+    let options = options.unwrap_or_default();
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let document = load_document(pdfium, &pdf_path)?;
+    check_page_limit(&document, &options)?;
+
+    let mut result: Vec<String> = vec![];
+    let total_pages = document.pages().len() as u32;
+
+    for (page_index, page) in document.pages().iter().enumerate() {
+      check_aborted(&options)?;
+      let layout = page_layout(&page, page_index as u32, &options)?;
+      let page_number = page_index + 1;
+
+      // hOCR boxes are measured from the top left corner
+      let bbox = |(left, top, right, bottom): (f64, f64, f64, f64)| {
+        format!(
+          "bbox {} {} {} {}",
+          left.round(),
+          (layout.height - top).round(),
+          right.round(),
+          (layout.height - bottom).round()
+        )
+      };
+
+      let mut hocr = String::from(HOCR_HEADER);
+      hocr.push_str(&format!(
+        "  <div class=\"ocr_page\" id=\"page_{}\" title=\"{}; ppageno {}\">\n",
+        page_number,
+        bbox((0.0, layout.height, layout.width, 0.0)),
+        page_index
+      ));
+      for (line_index, line) in layout.lines.iter().enumerate() {
+        hocr.push_str(&format!(
+          "   <span class=\"ocr_line\" id=\"line_{}_{}\" title=\"{}\">",
+          page_number,
+          line_index + 1,
+          bbox(words_bounds(line.iter()))
+        ));
+        for (word_index, w) in line.iter().enumerate() {
+          if word_index > 0 {
+            hocr.push(' ');
+          }
+          hocr.push_str(&format!(
+            "<span class=\"ocrx_word\" id=\"word_{}_{}_{}\" title=\"{}\">{}</span>",
+            page_number,
+            line_index + 1,
+            word_index + 1,
+            bbox((w.left, w.top, w.right, w.bottom)),
+            escape_xml(&w.text)
+          ));
+        }
+        hocr.push_str("</span>\n");
+      }
+      hocr.push_str("  </div>\n </body>\n</html>\n");
+      result.push(hocr);
+
+      report_progress(&options, page_index as u32, total_pages, 0);
+    }
+
+    Ok(result)
+  })
+  .await
+}
+
+#[napi(catch_unwind)]
+/// Export document layout as ALTO XML with text blocks, lines and strings, positions are in
+/// pixels at 72 DPI from the top left page corner
+pub async fn export_alto(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+  options: Option<ExtractOptions>,
+) -> napi::Result<String> {
+  run_blocking(move || {
+    let options = options.unwrap_or_default();
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let document = load_document(pdfium, &pdf_path)?;
+    check_page_limit(&document, &options)?;
+
+    let file_name = Path::new(&pdf_path)
+      .file_name()
+      .map(|name| name.to_string_lossy().into_owned())
+      .unwrap_or_default();
+    let mut alto = ALTO_HEADER.replace("{file_name}", &escape_xml(&file_name));
+    let total_pages = document.pages().len() as u32;
+
+    for (page_index, page) in document.pages().iter().enumerate() {
+      check_aborted(&options)?;
+      let layout = page_layout(&page, page_index as u32, &options)?;
+      let page_number = page_index + 1;
+
+      // ALTO positions are measured from the top left corner
+      let position = |(left, top, right, bottom): (f64, f64, f64, f64)| {
+        format!(
+          "HPOS=\"{}\" VPOS=\"{}\" WIDTH=\"{}\" HEIGHT=\"{}\"",
+          left.round(),
+          (layout.height - top).round(),
+          (right - left).round(),
+          (top - bottom).round()
+        )
+      };
+      let page_position = position((0.0, layout.height, layout.width, 0.0));
+
+      alto.push_str(&format!(
+        "    <Page ID=\"page_{}\" PHYSICAL_IMG_NR=\"{}\" WIDTH=\"{}\" HEIGHT=\"{}\">\n",
+        page_number,
+        page_number,
+        layout.width.round(),
+        layout.height.round()
+      ));
+      alto.push_str(&format!("      <PrintSpace {}>\n", page_position));
+      let mut line_number = 0;
+      let mut string_number = 0;
+      for (block_index, block) in line_blocks(&layout.lines).into_iter().enumerate() {
+        let block_bounds = words_bounds(block.iter().flatten());
+        alto.push_str(&format!(
+          "        <TextBlock ID=\"block_{}_{}\" {}>\n",
+          page_number,
+          block_index + 1,
+          position(block_bounds)
+        ));
+        for line in block {
+          line_number += 1;
+          alto.push_str(&format!(
+            "          <TextLine ID=\"line_{}_{}\" {}>\n",
+            page_number,
+            line_number,
+            position(words_bounds(line.iter()))
+          ));
+          for (word_index, w) in line.iter().enumerate() {
+            if word_index > 0 {
+              alto.push_str("            <SP/>\n");
+            }
+            string_number += 1;
+            alto.push_str(&format!(
+              "            <String ID=\"string_{}_{}\" {} CONTENT=\"{}\"/>\n",
+              page_number,
+              string_number,
+              position((w.left, w.top, w.right, w.bottom)),
+              escape_xml(&w.text)
+            ));
+          }
+          alto.push_str("          </TextLine>\n");
+        }
+        alto.push_str("        </TextBlock>\n");
+      }
+      alto.push_str("      </PrintSpace>\n    </Page>\n");
+
+      report_progress(&options, page_index as u32, total_pages, 0);
+    }
+    alto.push_str("  </Layout>\n</alto>\n");
+
+    Ok(alto)
+  })
+  .await
+}
+
+#[napi(catch_unwind)]
+/// Convert document into Markdown, one string for each page, with headings, lists, tables and
+/// images saved into images folder or returned as buffers like in text and images extraction
+pub async fn convert_to_markdown(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+  options: Option<ExtractOptions>,
+) -> napi::Result<Vec<String>> {
+  run_blocking(move || {
+    let options = options.unwrap_or_default();
+    // headings are classified from text lines style
+    let options = ExtractOptions {
+      detect_headings: Some(true),
+      ..options
+    };
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let document = load_document(pdfium, &pdf_path)?;
+    let raw_document = RawDocument::new(document.bindings(), &pdf_path);
+    let pages = extract_pages(
+      &document,
+      &raw_document,
+      None,
+      None,
+      &mut FileIndices::default(),
+      &options,
+      true,
+    )?;
+
+    let mut result: Vec<String> = vec![];
+    for (page_index, extracted) in pages.into_iter().enumerate() {
+      let page = document
+        .pages()
+        .get(page_index as PdfPageIndex)
+        .map_err(|err| {
+          PdfError::page(page_index, "Failed to read pdf document page").caused_by(err)
+        })?;
+      let tables = page_tables(&page, page_index as u32, &options)?;
+      let lines = extracted.page_text_line_bounds.unwrap_or_default();
+      let mut images = extracted.page_images;
+
+      let mut blocks: Vec<String> = vec![];
+      let mut paragraph: Vec<String> = vec![];
+      let mut list: Vec<String> = vec![];
+      let flush =
+        |blocks: &mut Vec<String>, paragraph: &mut Vec<String>, list: &mut Vec<String>| {
+          if !paragraph.is_empty() {
+            blocks.push(std::mem::take(paragraph).join(" "));
+          }
+          if !list.is_empty() {
+            blocks.push(std::mem::take(list).join("\n"));
+          }
+        };
+      let mut written_tables: HashSet<usize> = HashSet::new();
+      let mut last_line: Option<&ExtractedTextLine> = None;
+
+      for line in &lines {
+        // table is written in place of its first line
+        let (middle_x, middle_y) = (
+          (line.left + line.right) / 2.0,
+          (line.top + line.bottom) / 2.0,
+        );
+        if let Some(table_index) = tables.iter().position(|table| {
+          middle_x >= table.left
+            && middle_x <= table.right
+            && middle_y >= table.bottom
+            && middle_y <= table.top
+        }) {
+          flush(&mut blocks, &mut paragraph, &mut list);
+          if written_tables.insert(table_index) {
+            blocks.push(markdown_table(&tables[table_index]));
+          }
+          last_line = None;
+          continue;
+        }
+
+        match line.kind {
+          Some(TextLineKind::H1) => {
+            flush(&mut blocks, &mut paragraph, &mut list);
+            blocks.push(format!("# {}", line.text.trim()));
+          }
+          Some(TextLineKind::H2) => {
+            flush(&mut blocks, &mut paragraph, &mut list);
+            blocks.push(format!("## {}", line.text.trim()));
+          }
+          _ => match list_item(&line.text) {
+            Some(item) => {
+              if !paragraph.is_empty() {
+                flush(&mut blocks, &mut paragraph, &mut list);
+              }
+              list.push(item);
+            }
+            None => {
+              let is_new_paragraph = last_line.is_none_or(|last| {
+                starts_paragraph(text_line_bounds(last), text_line_bounds(line))
+              });
+              // lines continuing list item are written as new paragraph
+              if is_new_paragraph || !list.is_empty() {
+                flush(&mut blocks, &mut paragraph, &mut list);
+              }
+              paragraph.push(line.text.trim().to_owned());
+            }
+          },
+        }
+        last_line = Some(line);
+
+        // images follow the closest text line
+        let (placed, rest): (Vec<ExtractedImageMeta>, Vec<ExtractedImageMeta>) = images
+          .into_iter()
+          .partition(|image| image.related_text.first() == Some(&line.text));
+        images = rest;
+        if !placed.is_empty() {
+          flush(&mut blocks, &mut paragraph, &mut list);
+          blocks.extend(placed.iter().map(markdown_image));
+          last_line = None;
+        }
+      }
+      flush(&mut blocks, &mut paragraph, &mut list);
+      blocks.extend(images.iter().map(markdown_image));
+      blocks.extend(
+        tables
+          .iter()
+          .enumerate()
+          .filter(|(table_index, _)| !written_tables.contains(table_index))
+          .map(|(_, table)| markdown_table(table)),
+      );
+
+      result.push(blocks.join("\n\n"));
+    }
+
+    Ok(result)
+  })
+  .await
+}
+
+fn text_line_bounds(line: &ExtractedTextLine) -> Bounds {
+  (
+    line.left as f32,
+    line.bottom as f32,
+    line.right as f32,
+    line.top as f32,
+  )
+}
+
+/// Bulleted or numbered list item in Markdown
+fn list_item(text: &str) -> Option<String> {
+  let text = text.trim();
+  if let Some(rest) = text.strip_prefix(LIST_BULLETS) {
+    let is_ascii_bullet = text.starts_with(['-', '*']);
+    if !is_ascii_bullet || rest.starts_with(char::is_whitespace) {
+      return Some(format!("- {}", rest.trim()));
+    }
+  }
+
+  // numbers followed by dot or parenthesis and space
+  let marker_end = text.find(|c: char| !c.is_ascii_digit())?;
+  let rest = &text[marker_end..];
+  if marker_end == 0 || marker_end > 3 {
+    return None;
+  }
+  rest
+    .strip_prefix(['.', ')'])
+    .filter(|rest| rest.starts_with(char::is_whitespace))
+    .map(|rest| format!("{}. {}", &text[..marker_end], rest.trim()))
+}
+
+static LIST_BULLETS: [char; 10] = ['•', '◦', '▪', '‣', '●', '○', '■', '·', '-', '*'];
+
+fn markdown_image(image: &ExtractedImageMeta) -> String {
+  format!(
+    "![]({})",
+    image.duplicate_of.as_ref().unwrap_or(&image.filename)
+  )
+}
+
+fn markdown_table(table: &ExtractedTable) -> String {
+  let columns = table.rows.iter().map(|row| row.len()).max().unwrap_or(0);
+  let row = |cells: Vec<String>| format!("| {} |", cells.join(" | "));
+
+  let mut rows: Vec<String> = table
+    .rows
+    .iter()
+    .map(|cells| {
+      let mut cells: Vec<String> = cells
+        .iter()
+        .map(|cell| cell.text.replace('|', "\\|").replace('\n', "<br>"))
+        .collect();
+      cells.resize(columns, String::new());
+      row(cells)
+    })
+    .collect();
+  // first row is the header
+  rows.insert(1.min(rows.len()), row(vec!["---".to_owned(); columns]));
+  rows.join("\n")
+}
+
+#[napi(catch_unwind)]
+/// Export document as HTML with each page word in absolutely positioned span, sizes in points
+pub async fn export_html(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+  options: Option<ExtractOptions>,
+) -> napi::Result<String> {
+  run_blocking(move || {
+    let options = options.unwrap_or_default();
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let document = load_document(pdfium, &pdf_path)?;
+    check_page_limit(&document, &options)?;
+
+    let mut html = String::from(HTML_HEADER);
+    let total_pages = document.pages().len() as u32;
+
+    for (page_index, page) in document.pages().iter().enumerate() {
+      check_aborted(&options)?;
+      let layout = page_layout(&page, page_index as u32, &options)?;
+
+      html.push_str(&format!(
+        "<div class=\"page\" id=\"page-{}\" style=\"width:{:.2}pt;height:{:.2}pt\">\n",
+        page_index + 1,
+        layout.width,
+        layout.height
+      ));
+      for line in &layout.lines {
+        html.push_str("<div class=\"line\">");
+        for w in line {
+          // word box height is used as font size, positions are from the top left corner
+          html.push_str(&format!(
+            "<span style=\"left:{:.2}pt;top:{:.2}pt;font-size:{:.2}pt\">{}</span>",
+            w.left,
+            layout.height - w.top,
+            w.top - w.bottom,
+            escape_xml(&w.text)
+          ));
+        }
+        html.push_str("</div>\n");
+      }
+      html.push_str("</div>\n");
+
+      report_progress(&options, page_index as u32, total_pages, 0);
+    }
+    html.push_str("</body>\n</html>\n");
+
+    Ok(html)
+  })
+  .await
+}
+
+#[napi(object)]
+/// Page layout with blocks in reading order, bounds in page space points, origin at the bottom
+/// left page corner
+pub struct LayoutPage {
+  pub page_index: u32,
+  pub width: f64,
+  pub height: f64,
+  pub blocks: Vec<LayoutBlock>,
+}
+
+#[napi(string_enum = "lowercase")]
+/// Layout block type
+pub enum LayoutBlockKind {
+  Heading,
+  Paragraph,
+  List,
+  Table,
+  Image,
+}
+
+#[napi(object)]
+/// Block of text lines, table or image
+pub struct LayoutBlock {
+  pub kind: LayoutBlockKind,
+  /// Heading level 1 or 2, set for headings
+  pub heading_level: Option<u32>,
+  pub left: f64,
+  pub top: f64,
+  pub right: f64,
+  pub bottom: f64,
+  /// Block text lines, empty for images
+  pub lines: Vec<LayoutLine>,
+}
+
+#[napi(object)]
+/// Text line with its dominant font style and words
+pub struct LayoutLine {
+  pub text: String,
+  pub left: f64,
+  pub top: f64,
+  pub right: f64,
+  pub bottom: f64,
+  pub font_name: String,
+  pub font_size: f64,
+  /// Font weight from 100 to 900, 400 is normal and 700 is bold
+  pub font_weight: u32,
+  pub is_italic: bool,
+  /// Text fill color in hex like "#FF0000"
+  pub fill_color: String,
+  pub words: Vec<ExtractedWord>,
+}
+
+#[napi(catch_unwind)]
+/// Extract document layout as pages of headings, paragraphs, lists, tables and images blocks
+/// with their lines and words
+pub async fn extract_layout(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+  options: Option<ExtractOptions>,
+) -> napi::Result<Vec<LayoutPage>> {
+  run_blocking(move || {
+    let options = options.unwrap_or_default();
+    let options = ExtractOptions {
+      detect_headings: Some(true),
+      ..options
+    };
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let document = load_document(pdfium, &pdf_path)?;
+    let raw_document = RawDocument::new(document.bindings(), &pdf_path);
+    // images are listed by their position only, skip decoding them
+    let pages = extract_pages(
+      &document,
+      &raw_document,
+      None,
+      None,
+      &mut FileIndices::default(),
+      &options,
+      false,
+    )?;
+
+    let mut result: Vec<LayoutPage> = vec![];
+    for (page_index, extracted) in pages.into_iter().enumerate() {
+      let page = document
+        .pages()
+        .get(page_index as PdfPageIndex)
+        .map_err(|err| {
+          PdfError::page(page_index, "Failed to read pdf document page").caused_by(err)
+        })?;
+      let (width, height) = page_size(&page, &options);
+      let tables = page_tables(&page, page_index as u32, &options)?;
+      let mut words: Vec<ExtractedWord> = page_layout(&page, page_index as u32, &options)?
+        .lines
+        .into_iter()
+        .flatten()
+        .collect();
+
+      let mut blocks: Vec<LayoutBlock> = vec![];
+      let mut last_line: Option<(LayoutBlockKind, Bounds)> = None;
+      for line in extracted.page_text_line_bounds.unwrap_or_default() {
+        let (middle_x, middle_y) = (
+          (line.left + line.right) / 2.0,
+          (line.top + line.bottom) / 2.0,
+        );
+        let table = tables.iter().find(|table| {
+          middle_x >= table.left
+            && middle_x <= table.right
+            && middle_y >= table.bottom
+            && middle_y <= table.top
+        });
+        let (kind, heading_level) = match (table, line.kind) {
+          (Some(_), _) => (LayoutBlockKind::Table, None),
+          (None, Some(TextLineKind::H1)) => (LayoutBlockKind::Heading, Some(1)),
+          (None, Some(TextLineKind::H2)) => (LayoutBlockKind::Heading, Some(2)),
+          _ if list_item(&line.text).is_some() => (LayoutBlockKind::List, None),
+          _ => (LayoutBlockKind::Paragraph, None),
+        };
+        let bounds = text_line_bounds(&line);
+
+        // words with their middle inside the line
+        let (line_words, rest): (Vec<ExtractedWord>, Vec<ExtractedWord>) =
+          words.into_iter().partition(|w| {
+            let (x, y) = ((w.left + w.right) / 2.0, (w.top + w.bottom) / 2.0);
+            x >= line.left && x <= line.right && y >= line.bottom && y <= line.top
+          });
+        words = rest;
+        let layout_line = LayoutLine {
+          text: line.text,
+          left: line.left,
+          top: line.top,
+          right: line.right,
+          bottom: line.bottom,
+          font_name: line.font_name,
+          font_size: line.font_size,
+          font_weight: line.font_weight,
+          is_italic: line.is_italic,
+          fill_color: line.fill_color,
+          words: line_words,
+        };
+
+        // list items continue list, table lines continue their table
+        let continues_block = match (&last_line, &kind) {
+          (Some((LayoutBlockKind::Paragraph, last)), LayoutBlockKind::Paragraph) => {
+            !starts_paragraph(*last, bounds)
+          }
+          (Some((LayoutBlockKind::List, _)), LayoutBlockKind::List) => true,
+          (Some((LayoutBlockKind::Table, _)), LayoutBlockKind::Table) => blocks
+            .last()
+            .zip(table)
+            .is_some_and(|(block, table)| block.top == table.top && block.left == table.left),
+          _ => false,
+        };
+        last_line = Some((kind, bounds));
+
+        match blocks.last_mut() {
+          Some(block) if continues_block => {
+            if !matches!(kind, LayoutBlockKind::Table) {
+              block.left = block.left.min(layout_line.left);
+              block.top = block.top.max(layout_line.top);
+              block.right = block.right.max(layout_line.right);
+              block.bottom = block.bottom.min(layout_line.bottom);
+            }
+            block.lines.push(layout_line);
+          }
+          _ => {
+            let (left, top, right, bottom) = match table {
+              Some(table) => (table.left, table.top, table.right, table.bottom),
+              None => (
+                layout_line.left,
+                layout_line.top,
+                layout_line.right,
+                layout_line.bottom,
+              ),
+            };
+            blocks.push(LayoutBlock {
+              kind,
+              heading_level,
+              left,
+              top,
+              right,
+              bottom,
+              lines: vec![layout_line],
+            })
+          }
+        }
+      }
+
+      // images are placed before the first text block below them, including images of form
+      // XObjects and inline images
+      let rotation = PageRotation::of(&page, &options);
+      for bounds in page_images_bounds(page) {
+        let (left, top, right, bottom) = image_rect(Some(bounds), rotation);
+        let position = blocks
+          .iter()
+          .position(|block| block.top < top)
+          .unwrap_or(blocks.len());
+        blocks.insert(
+          position,
+          LayoutBlock {
+            kind: LayoutBlockKind::Image,
+            heading_level: None,
+            left,
+            top,
+            right,
+            bottom,
+            lines: vec![],
+          },
+        );
+      }
+
+      result.push(LayoutPage {
+        page_index: page_index as u32,
+        width,
+        height,
+        blocks,
+      });
+    }
+
+    Ok(result)
+  })
+  .await
+}
+
+static HTML_HEADER: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<style>
+.page { position: relative; overflow: hidden; margin: 1em auto; background: #fff; box-shadow: 0 0 4px #999; }
+.page span { position: absolute; line-height: 1; white-space: pre; font-family: sans-serif; }
+</style>
+</head>
+<body>
+"#;
+
+static ALTO_HEADER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<alto xmlns="http://www.loc.gov/standards/alto/ns-v4#" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:schemaLocation="http://www.loc.gov/standards/alto/ns-v4# http://www.loc.gov/alto/v4/alto-4-2.xsd">
+  <Description>
+    <MeasurementUnit>pixel</MeasurementUnit>
+    <sourceImageInformation>
+      <fileName>{file_name}</fileName>
+    </sourceImageInformation>
+  </Description>
+  <Layout>
+"#;
+
+static HOCR_HEADER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE html PUBLIC "-//W3C//DTD XHTML 1.0 Transitional//EN" "http://www.w3.org/TR/xhtml1/DTD/xhtml1-transitional.dtd">
+<html xmlns="http://www.w3.org/1999/xhtml">
+ <head>
+  <title></title>
+  <meta http-equiv="Content-Type" content="text/html;charset=utf-8" />
+  <meta name="ocr-system" content="pdf-lib" />
+  <meta name="ocr-capabilities" content="ocr_page ocr_line ocrx_word" />
+ </head>
+ <body>
+"#;
+
+fn escape_xml(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+    .replace('\'', "&apos;")
+}
@@ -0,0 +1,421 @@
+use napi::bindgen_prelude::Buffer;
+use pdfium_render::prelude::*;
+use std::collections::HashSet;
+use std::fs::create_dir_all;
+use std::os::raw::{c_char, c_int};
+use std::path::Path;
+
+use crate::errors::{ErrorCode, PdfError};
+use crate::objects::{decrypted_data, name_value, PdfObjects};
+use crate::options::FontOptions;
+use crate::{get_pdfium, load_document, run_blocking, RawDocument};
+
+// pdfium text and form page object types
+static PAGE_OBJECT_TEXT: c_int = 1;
+static PAGE_OBJECT_FORM: c_int = 5;
+
+#[napi(string_enum = "lowercase")]
+/// Embedded font program format, detected from its data
+pub enum FontFormat {
+  /// TrueType
+  Ttf,
+  /// OpenType with CFF outlines
+  Otf,
+  /// Bare CFF, usually of Type1C and CIDFontType0C fonts
+  Cff,
+  /// Type 1 PostScript font
+  Type1,
+  Unknown,
+}
+
+impl FontFormat {
+  fn detect(data: &[u8]) -> FontFormat {
+    match data {
+      [0x00, 0x01, 0x00, 0x00, ..] | [b't', b'r', b'u', b'e', ..] => FontFormat::Ttf,
+      [b'O', b'T', b'T', b'O', ..] => FontFormat::Otf,
+      // header major version 1 and header size 4
+      [0x01, 0x00, 0x04, ..] => FontFormat::Cff,
+      [b'%', b'!', ..] | [0x80, 0x01, ..] => FontFormat::Type1,
+      _ => FontFormat::Unknown,
+    }
+  }
+
+  fn extension(&self) -> &'static str {
+    match self {
+      FontFormat::Ttf => "ttf",
+      FontFormat::Otf => "otf",
+      FontFormat::Cff => "cff",
+      FontFormat::Type1 => "pfa",
+      FontFormat::Unknown => "bin",
+    }
+  }
+}
+
+#[napi(object)]
+/// Font program embedded in the document
+pub struct ExtractedFont {
+  /// Base font name without subset prefix, like "Arial-BoldMT"
+  pub name: String,
+  /// Prefix of subset fonts with only used glyphs embedded, like "ABCDEF"
+  pub subset_prefix: Option<String>,
+  pub format: FontFormat,
+  pub size_bytes: u32,
+  /// First page using the font
+  pub page_index: u32,
+  /// Saved file name, set when fonts are saved into output folder
+  pub filename: Option<String>,
+  /// Font program, set when fonts are returned as buffers
+  pub data: Option<Buffer>,
+}
+
+#[napi(object)]
+/// Font used by document text
+pub struct FontInfo {
+  /// Base font name without subset prefix, like "Arial-BoldMT"
+  pub name: String,
+  /// Prefix of subset fonts with only used glyphs embedded, like "ABCDEF"
+  pub subset_prefix: Option<String>,
+  /// Font dictionary subtype, like "TrueType" or "Type0"
+  pub font_type: Option<String>,
+  pub embedded: bool,
+  pub subset: bool,
+  /// Encoding name like "WinAnsiEncoding" or "Identity-H", "Custom" for encoding dictionaries and
+  /// "Builtin" for font program encoding
+  pub encoding: Option<String>,
+  /// Font maps character codes to unicode text, text of fonts without it and without standard
+  /// encoding is often extracted wrong
+  pub has_to_unicode: Option<bool>,
+  /// First page using the font
+  pub page_index: u32,
+}
+
+#[napi(catch_unwind)]
+/// List fonts used by document text, font type, encoding and unicode map are read from font
+/// dictionaries and are not set when no dictionary matches the font
+pub async fn list_fonts(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+) -> napi::Result<Vec<FontInfo>> {
+  run_blocking(move || {
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let raw_document = RawDocument::new(pdfium.bindings(), &pdf_path);
+    let fonts = document_fonts(pdfium, &raw_document, false)?;
+    let dictionaries = font_dictionaries(&raw_document);
+
+    Ok(
+      fonts
+        .into_iter()
+        .map(|font| {
+          let dictionary = font_dictionary(&dictionaries, &font.name);
+          let (subset_prefix, name) =
+            split_subset_prefix(dictionary.map_or(&font.name, |dictionary| &dictionary.base_font));
+          FontInfo {
+            name: name.to_owned(),
+            subset: subset_prefix.is_some(),
+            subset_prefix: subset_prefix.map(str::to_owned),
+            font_type: dictionary.and_then(|dictionary| dictionary.subtype.clone()),
+            embedded: font.embedded,
+            encoding: dictionary.map(|dictionary| {
+              dictionary
+                .encoding
+                .clone()
+                .unwrap_or_else(|| "Builtin".to_owned())
+            }),
+            has_to_unicode: dictionary.map(|dictionary| dictionary.has_to_unicode),
+            page_index: font.page_index,
+          }
+        })
+        .collect(),
+    )
+  })
+  .await
+}
+
+#[napi(catch_unwind)]
+/// List fonts embedded in the document with their programs, optionally saving them or returning
+/// their data
+pub async fn extract_fonts(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+  options: Option<FontOptions>,
+) -> napi::Result<Vec<ExtractedFont>> {
+  run_blocking(move || {
+    let options = options.unwrap_or_default();
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+
+    let as_buffers = options.as_buffers.unwrap_or(false);
+    let output_folder_path = options
+      .output_folder_path
+      .as_deref()
+      .filter(|path| !as_buffers && !path.is_empty())
+      .map(Path::new);
+    if let Some(output_folder_path) = output_folder_path {
+      create_dir_all(output_folder_path).map_err(|err| {
+        PdfError::new(ErrorCode::SaveFailed, "Failed to create output folder").caused_by(err)
+      })?;
+    }
+
+    let raw_document = RawDocument::new(pdfium.bindings(), &pdf_path);
+    let fonts = document_fonts(pdfium, &raw_document, true)?;
+    // pdfium names fonts without subset prefix
+    let dictionaries = font_dictionaries(&raw_document);
+
+    let mut filenames = HashSet::new();
+    let mut result: Vec<ExtractedFont> = vec![];
+    for font in fonts {
+      let data = match font.data {
+        Some(data) => data,
+        None => continue,
+      };
+      let base_name = font_dictionary(&dictionaries, &font.name)
+        .map_or(&font.name, |dictionary| &dictionary.base_font);
+      let (subset_prefix, name) = split_subset_prefix(base_name);
+      let format = FontFormat::detect(&data);
+
+      let filename = match output_folder_path {
+        Some(output_folder_path) => {
+          // font names are not trusted paths
+          let stem: String = base_name
+            .chars()
+            .map(|c| match c {
+              'a'..='z' | 'A'..='Z' | '0'..='9' | '+' | '-' | '_' => c,
+              _ => '_',
+            })
+            .collect();
+          let mut filename = format!("{}.{}", stem, format.extension());
+          let mut n = 2;
+          while !filenames.insert(filename.clone()) {
+            filename = format!("{}-{}.{}", stem, n, format.extension());
+            n += 1;
+          }
+          std::fs::write(output_folder_path.join(&filename), &data).map_err(|err| {
+            PdfError::new(
+              ErrorCode::SaveFailed,
+              format!("Failed to save font {}", filename),
+            )
+            .caused_by(err)
+          })?;
+          Some(filename)
+        }
+        None => None,
+      };
+
+      result.push(ExtractedFont {
+        name: name.to_owned(),
+        subset_prefix: subset_prefix.map(str::to_owned),
+        format,
+        size_bytes: data.len() as u32,
+        page_index: font.page_index,
+        filename,
+        data: as_buffers.then(|| Buffer::from(data)),
+      });
+    }
+
+    Ok(result)
+  })
+  .await
+}
+
+/// Font used by document text as pdfium reads it
+struct DocumentFont {
+  name: String,
+  embedded: bool,
+  page_index: u32,
+  /// Decoded font program of embedded fonts when requested
+  data: Option<Vec<u8>>,
+}
+
+/// Fonts of document text objects in order of first use, read through pdfium bindings as
+/// pdfium-render keeps font handles private
+fn document_fonts(
+  pdfium: &Pdfium,
+  raw_document: &RawDocument,
+  with_data: bool,
+) -> napi::Result<Vec<DocumentFont>> {
+  // loaded for failure reason
+  drop(load_document(pdfium, raw_document.pdf_path())?);
+
+  let bindings = raw_document.bindings();
+  let document = raw_document.handle().ok_or_else(|| {
+    napi::Error::from(PdfError::new(
+      ErrorCode::LoadFailed,
+      "Failed to read pdf document",
+    ))
+  })?;
+
+  // font PostScript name, UTF-8 with NUL terminator from pdfium
+  let base_font_name = |font| {
+    let length = bindings.FPDFFont_GetBaseFontName(font, std::ptr::null_mut(), 0);
+    let mut buffer = vec![0u8; length];
+    bindings.FPDFFont_GetBaseFontName(font, buffer.as_mut_ptr() as *mut c_char, length);
+    String::from_utf8_lossy(&buffer)
+      .trim_end_matches('\0')
+      .to_owned()
+  };
+  // decoded font program
+  let font_data = |font| {
+    let mut length = 0;
+    if !bindings.is_true(bindings.FPDFFont_GetFontData(font, std::ptr::null_mut(), 0, &mut length))
+      || length == 0
+    {
+      return None;
+    }
+    let mut data = vec![0u8; length];
+    bindings
+      .is_true(bindings.FPDFFont_GetFontData(font, data.as_mut_ptr(), length, &mut length))
+      .then_some(data)
+  };
+
+  let mut fonts = vec![];
+  let mut seen_fonts = HashSet::new();
+  for page_index in 0..bindings.FPDF_GetPageCount(document) {
+    let page = bindings.FPDF_LoadPage(document, page_index);
+    if page.is_null() {
+      continue;
+    }
+    let mut objects: Vec<_> = (0..bindings.FPDFPage_CountObjects(page))
+      .rev()
+      .map(|idx| bindings.FPDFPage_GetObject(page, idx))
+      .collect();
+    while let Some(object) = objects.pop() {
+      if object.is_null() {
+        continue;
+      }
+      let object_type = bindings.FPDFPageObj_GetType(object);
+      if object_type == PAGE_OBJECT_FORM {
+        objects.extend(
+          (0..bindings.FPDFFormObj_CountObjects(object).max(0) as u64)
+            .rev()
+            .map(|idx| bindings.FPDFFormObj_GetObject(object, idx as _)),
+        );
+        continue;
+      }
+      if object_type != PAGE_OBJECT_TEXT {
+        continue;
+      }
+
+      // pdfium keeps one font for each font dictionary
+      let font = bindings.FPDFTextObj_GetFont(object);
+      if font.is_null() || !seen_fonts.insert(font as usize) {
+        continue;
+      }
+      let embedded = bindings.FPDFFont_GetIsEmbedded(font) == 1;
+      fonts.push(DocumentFont {
+        name: base_font_name(font),
+        embedded,
+        page_index: page_index as u32,
+        data: if embedded && with_data {
+          font_data(font)
+        } else {
+          None
+        },
+      });
+    }
+    bindings.FPDF_ClosePage(page);
+  }
+
+  Ok(fonts)
+}
+
+/// Font dictionary entries pdfium does not expose
+#[derive(PartialEq)]
+struct FontDictionary {
+  /// Base font name with subset prefix
+  base_font: String,
+  subtype: Option<String>,
+  encoding: Option<String>,
+  has_to_unicode: bool,
+}
+
+/// Font dictionaries of the document saved through pdfium, which writes objects of compressed
+/// object streams uncompressed
+fn font_dictionaries(raw_document: &RawDocument) -> Vec<FontDictionary> {
+  let data = decrypted_data(raw_document).unwrap_or_default();
+  let objects = match PdfObjects::parse(&data) {
+    Some(objects) => objects,
+    None => return vec![],
+  };
+
+  let mut result = vec![];
+  for number in objects.numbers() {
+    let object = match objects.dictionary(number) {
+      Some(object) => object,
+      None => continue,
+    };
+    if name_value(&object, "/Type").as_deref() != Some("Font") {
+      continue;
+    }
+    let subtype = name_value(&object, "/Subtype");
+    // descendant fonts of Type0 fonts share their base font
+    if matches!(
+      subtype.as_deref(),
+      Some("CIDFontType0") | Some("CIDFontType2")
+    ) {
+      continue;
+    }
+    let base_font = match name_value(&object, "/BaseFont") {
+      Some(base_font) => base_font,
+      None => continue,
+    };
+
+    let encoding = object.split("/Encoding").skip(1).find_map(|rest| {
+      // skip other keys sharing the prefix
+      let value = rest.trim_start();
+      if value.len() == rest.len() && !value.starts_with(['/', '<']) {
+        return None;
+      }
+      // encoding dictionary or reference to it, with differences to a base encoding
+      Some(name_value(&object, "/Encoding").unwrap_or_else(|| "Custom".to_owned()))
+    });
+    result.push(FontDictionary {
+      base_font,
+      subtype,
+      encoding,
+      has_to_unicode: object.contains("/ToUnicode"),
+    });
+  }
+
+  result
+}
+
+/// Dictionary of pdfium font named without subset prefix, unless dictionaries sharing the name
+/// differ
+fn font_dictionary<'a>(
+  dictionaries: &'a [FontDictionary],
+  name: &str,
+) -> Option<&'a FontDictionary> {
+  let named = |exact: bool| {
+    dictionaries.iter().filter(move |dictionary| {
+      let (_, base_font) = split_subset_prefix(&dictionary.base_font);
+      if exact {
+        return base_font == name;
+      }
+      // Type0 fonts are often named after their CMap too, like "Arial-Identity-H"
+      dictionary.subtype.as_deref() == Some("Type0")
+        && base_font
+          .strip_prefix(name)
+          .is_some_and(|rest| rest.starts_with('-'))
+    })
+  };
+  let mut candidates: Vec<_> = named(true).collect();
+  if candidates.is_empty() {
+    candidates = named(false).collect();
+  }
+  let (dictionary, others) = candidates.split_first()?;
+  others
+    .iter()
+    .all(|other| other == dictionary)
+    .then_some(*dictionary)
+}
+
+/// Split "ABCDEF+" tag of subset fonts
+fn split_subset_prefix(base_font: &str) -> (Option<&str>, &str) {
+  match base_font.split_once('+') {
+    Some((prefix, name)) if prefix.len() == 6 && prefix.chars().all(|c| c.is_ascii_uppercase()) => {
+      (Some(prefix), name)
+    }
+    _ => (None, base_font),
+  }
+}
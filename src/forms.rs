@@ -0,0 +1,356 @@
+use pdfium_render::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::os::raw::{c_int, c_void};
+
+use crate::errors::{ErrorCode, PdfError};
+use crate::layout::{rect_bounds, PageRotation};
+use crate::objects::saved_data;
+use crate::options::ExtractOptions;
+use crate::structure::pdfium_string;
+use crate::{
+  check_aborted, check_page_limit, get_pdfium, load_document, report_progress, run_blocking,
+  RawDocument,
+};
+
+// pdfium form field types
+static FORM_FIELD_CHECKBOX: c_int = 2;
+static FORM_FIELD_RADIO_BUTTON: c_int = 3;
+static FORM_FIELD_COMBO_BOX: c_int = 4;
+static FORM_FIELD_LIST_BOX: c_int = 5;
+static FORM_FIELD_TEXT: c_int = 6;
+
+#[napi(object)]
+/// Form field widget with its rectangle in page space points, radio buttons and checkboxes
+/// of one group share the same name
+pub struct ExtractedFormField {
+  pub page_index: u32,
+  pub name: Option<String>,
+  /// Form field type like `Text`, `Checkbox`, `RadioButton`, `ComboBox` or `ListBox`
+  pub field_type: String,
+  /// Entered text, selected option or group value of checkboxes and radio buttons
+  pub value: Option<String>,
+  /// Set for checkboxes and radio buttons
+  pub is_checked: Option<bool>,
+  /// Choices of combo and list boxes
+  pub options: Vec<String>,
+  pub left: f64,
+  pub top: f64,
+  pub right: f64,
+  pub bottom: f64,
+}
+
+#[napi(catch_unwind)]
+/// Extract interactive form fields with their values
+pub async fn extract_form_fields(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+  options: Option<ExtractOptions>,
+) -> napi::Result<Vec<ExtractedFormField>> {
+  run_blocking(move || {
+    let options = options.unwrap_or_default();
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let document = load_document(pdfium, &pdf_path)?;
+    check_page_limit(&document, &options)?;
+
+    let mut result: Vec<ExtractedFormField> = vec![];
+    let total_pages = document.pages().len() as u32;
+
+    for (page_index, page) in document.pages().iter().enumerate() {
+      check_aborted(&options)?;
+      let rotation = PageRotation::of(&page, &options);
+
+      for annotation in page.annotations().iter() {
+        let field = match annotation.as_form_field() {
+          Some(field) => field,
+          None => continue,
+        };
+        let bounds = annotation.bounds().unwrap_or(PdfRect::ZERO);
+        let (left, top, right, bottom) = rect_bounds(&bounds, rotation);
+
+        let (value, is_checked, field_options) = match field.field_type() {
+          PdfFormFieldType::Text => (field.as_text_field().and_then(|f| f.value()), None, vec![]),
+          PdfFormFieldType::Checkbox => match field.as_checkbox_field() {
+            Some(f) => (f.group_value(), f.is_checked().ok(), vec![]),
+            None => (None, None, vec![]),
+          },
+          PdfFormFieldType::RadioButton => match field.as_radio_button_field() {
+            Some(f) => (f.group_value(), f.is_checked().ok(), vec![]),
+            None => (None, None, vec![]),
+          },
+          PdfFormFieldType::ComboBox => match field.as_combo_box_field() {
+            Some(f) => (f.value(), None, option_labels(f.options())),
+            None => (None, None, vec![]),
+          },
+          PdfFormFieldType::ListBox => match field.as_list_box_field() {
+            Some(f) => (f.value(), None, option_labels(f.options())),
+            None => (None, None, vec![]),
+          },
+          _ => (None, None, vec![]),
+        };
+
+        result.push(ExtractedFormField {
+          page_index: page_index as u32,
+          name: field.name().filter(|name| !name.is_empty()),
+          field_type: format!("{:?}", field.field_type()),
+          value: value.filter(|value| !value.is_empty()),
+          is_checked,
+          options: field_options,
+          left,
+          top,
+          right,
+          bottom,
+        });
+      }
+
+      report_progress(&options, page_index as u32, total_pages, 0);
+    }
+
+    Ok(result)
+  })
+  .await
+}
+
+fn option_labels(options: &PdfFormFieldOptions) -> Vec<String> {
+  options
+    .iter()
+    .filter_map(|option| option.label().cloned())
+    .collect()
+}
+
+#[napi(catch_unwind)]
+/// Set form fields values by field name and save filled document into output path, checkboxes
+/// take "true" or "false", radio buttons the export value of the button to select and combo and
+/// list boxes the label of the option to select
+pub async fn fill_form(
+  pdfium_dir: String,
+  pdf_path: String,
+  values: HashMap<String, String>,
+  output_path: String,
+) -> napi::Result<()> {
+  run_blocking(move || {
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let raw_document = RawDocument::new(pdfium.bindings(), &pdf_path);
+    let data = fill_fields(&raw_document, &values)?;
+    // source is closed before writing, output path may be the source itself
+    drop(raw_document);
+    std::fs::write(&output_path, data).map_err(|err| {
+      PdfError::new(ErrorCode::SaveFailed, "Failed to write pdf document").caused_by(err)
+    })?;
+    Ok(())
+  })
+  .await
+}
+
+/// Fill fields by clicking buttons and focusing text and choice fields in pdfium form
+/// environment, which regenerates their appearances and keeps other buttons of the group and
+/// field values in sync, and return the filled document data
+fn fill_fields(
+  raw_document: &RawDocument,
+  values: &HashMap<String, String>,
+) -> napi::Result<Vec<u8>> {
+  let bindings = raw_document.bindings();
+  let document = raw_document.loaded_handle()?;
+  // pdfium keeps the pointer until the environment exits
+  let mut form_fill_info = FormFillInfo::default();
+  let form = bindings
+    .FPDFDOC_InitFormFillEnvironment(document, &mut form_fill_info as *mut FormFillInfo as *mut _);
+  if form.is_null() {
+    return Err(PdfError::edit("Failed to read pdf document form").into());
+  }
+
+  let click = |page, annotation| {
+    let mut rect = Rect::default();
+    if bindings.is_true(bindings.FPDFAnnot_GetRect(annotation, &mut rect as *mut Rect as *mut _)) {
+      let x = (rect.left + rect.right) as f64 / 2.0;
+      let y = (rect.top + rect.bottom) as f64 / 2.0;
+      bindings.FORM_OnLButtonDown(form, page, 0, x, y);
+      bindings.FORM_OnLButtonUp(form, page, 0, x, y);
+    }
+  };
+  let is_checked = |annotation| bindings.is_true(bindings.FPDFAnnot_IsChecked(form, annotation));
+  // whether the field is filled, radio buttons and choices without the value are not
+  let fill = |page, annotation, name: &str, value: &str| -> napi::Result<bool> {
+    let edit_failed = || PdfError::edit(format!("Failed to set form field {} value", name));
+    let field_type = bindings.FPDFAnnot_GetFormFieldType(form, annotation);
+
+    if field_type == FORM_FIELD_TEXT {
+      // typed text replaces the whole field text and is written into it when it loses focus
+      let text = bindings.get_pdfium_utf16le_bytes_from_str(value);
+      let is_focused = bindings.is_true(bindings.FORM_SetFocusedAnnot(form, annotation));
+      if is_focused {
+        bindings.FORM_SelectAllText(form, page);
+        bindings.FORM_ReplaceSelection(form, page, text.as_ptr() as *const _);
+      }
+      bindings.FORM_ForceToKillFocus(form);
+      let filled_value = pdfium_string(|buffer, length| {
+        bindings.FPDFAnnot_GetFormFieldValue(form, annotation, buffer as *mut _, length)
+      });
+      if !is_focused || filled_value.as_deref().unwrap_or_default() != value {
+        return Err(edit_failed().into());
+      }
+      Ok(true)
+    } else if field_type == FORM_FIELD_CHECKBOX {
+      let checked = match value {
+        "true" => true,
+        "false" => false,
+        _ => {
+          return Err(
+            PdfError::new(
+              ErrorCode::InvalidArgument,
+              format!(
+                "Checkbox form field {} takes true or false, not {}",
+                name, value
+              ),
+            )
+            .into(),
+          )
+        }
+      };
+      if is_checked(annotation) != checked {
+        click(page, annotation);
+      }
+      if is_checked(annotation) != checked {
+        return Err(edit_failed().into());
+      }
+      Ok(true)
+    } else if field_type == FORM_FIELD_RADIO_BUTTON {
+      let export_value = pdfium_string(|buffer, length| {
+        bindings.FPDFAnnot_GetFormFieldExportValue(form, annotation, buffer as *mut _, length)
+      });
+      if export_value.as_deref() != Some(value) {
+        return Ok(false);
+      }
+      if !is_checked(annotation) {
+        click(page, annotation);
+      }
+      if !is_checked(annotation) {
+        return Err(edit_failed().into());
+      }
+      Ok(true)
+    } else if field_type == FORM_FIELD_COMBO_BOX || field_type == FORM_FIELD_LIST_BOX {
+      let index = (0..bindings.FPDFAnnot_GetOptionCount(form, annotation)).find(|index| {
+        let label = pdfium_string(|buffer, length| {
+          bindings.FPDFAnnot_GetOptionLabel(form, annotation, *index, buffer as *mut _, length)
+        });
+        label.as_deref() == Some(value)
+      });
+      let index = match index {
+        Some(index) => index,
+        None => return Ok(false),
+      };
+      // options are selected in focused field and written into it when it loses focus
+      let is_selected = bindings.is_true(bindings.FORM_SetFocusedAnnot(form, annotation))
+        && bindings.is_true(bindings.FORM_SetIndexSelected(form, page, index, 1));
+      bindings.FORM_ForceToKillFocus(form);
+      if !is_selected {
+        return Err(edit_failed().into());
+      }
+      Ok(true)
+    } else {
+      Err(
+        PdfError::new(
+          ErrorCode::Unsupported,
+          format!("Filling form field {} of this type is not supported", name),
+        )
+        .into(),
+      )
+    }
+  };
+
+  let mut requested: HashSet<String> = HashSet::new();
+  let mut filled: HashSet<String> = HashSet::new();
+  let mut result = Ok(());
+  for page_index in 0..bindings.FPDF_GetPageCount(document) {
+    let page = bindings.FPDF_LoadPage(document, page_index);
+    if page.is_null() {
+      continue;
+    }
+    bindings.FORM_OnAfterLoadPage(page, form);
+
+    for idx in 0..bindings.FPDFPage_GetAnnotCount(page) {
+      let annotation = bindings.FPDFPage_GetAnnot(page, idx);
+      if annotation.is_null() {
+        continue;
+      }
+      let name = pdfium_string(|buffer, length| {
+        bindings.FPDFAnnot_GetFormFieldName(form, annotation, buffer as *mut _, length)
+      });
+      if let Some((name, value)) = name.and_then(|name| values.get_key_value(&name)) {
+        requested.insert(name.to_owned());
+        result = fill(page, annotation, name, value).map(|is_filled| {
+          if is_filled {
+            filled.insert(name.to_owned());
+          }
+        });
+      }
+      bindings.FPDFPage_CloseAnnot(annotation);
+      if result.is_err() {
+        break;
+      }
+    }
+
+    bindings.FORM_OnBeforeClosePage(page, form);
+    bindings.FPDF_ClosePage(page);
+    if result.is_err() {
+      break;
+    }
+  }
+
+  let data = saved_data(bindings, document, 0);
+  bindings.FPDFDOC_ExitFormFillEnvironment(form);
+  result?;
+
+  if let Some(name) = values.keys().find(|name| !requested.contains(*name)) {
+    return Err(
+      PdfError::new(
+        ErrorCode::InvalidArgument,
+        format!("Form field {} not found", name),
+      )
+      .into(),
+    );
+  }
+  if let Some(name) = requested.iter().find(|name| !filled.contains(*name)) {
+    return Err(
+      PdfError::new(
+        ErrorCode::InvalidArgument,
+        format!("Form field {} has no option {}", name, values[name]),
+      )
+      .into(),
+    );
+  }
+  data.ok_or_else(|| PdfError::new(ErrorCode::SaveFailed, "Failed to save pdf document").into())
+}
+
+/// Form environment callbacks layout of pdfium `FPDF_FORMFILLINFO` version 1, documents are
+/// filled without user interaction so no callbacks are set
+#[repr(C)]
+struct FormFillInfo {
+  version: c_int,
+  callbacks: [*mut c_void; 15],
+  js_platform: *mut c_void,
+  xfa_disabled: c_int,
+  xfa_callbacks: [*mut c_void; 17],
+}
+
+impl Default for FormFillInfo {
+  fn default() -> Self {
+    FormFillInfo {
+      version: 1,
+      callbacks: [std::ptr::null_mut(); 15],
+      js_platform: std::ptr::null_mut(),
+      xfa_disabled: 1,
+      xfa_callbacks: [std::ptr::null_mut(); 17],
+    }
+  }
+}
+
+/// Rectangle layout of pdfium `FS_RECTF`
+#[repr(C)]
+#[derive(Default)]
+struct Rect {
+  left: f32,
+  top: f32,
+  right: f32,
+  bottom: f32,
+}
@@ -0,0 +1,171 @@
+use pdfium_render::prelude::*;
+use std::sync::{Arc, Mutex};
+
+use crate::document::{document_metadata, DocumentMetadata};
+use crate::errors::{ErrorCode, PdfError};
+use crate::options::ExtractOptions;
+use crate::{
+  extract_pages, get_pdfium, load_document, page_text, run_blocking, ExtractedImageMeta,
+  FileIndices, RawDocument, PDFIUM_ACCESS,
+};
+
+/// Loaded document with the pdfium library it is loaded by, fields drop in order so the document
+/// is closed before the library may be released
+struct OpenDocument {
+  document: PdfDocument<'static>,
+  raw_document: RawDocument<'static>,
+  // extracted files are numbered across queries so pages extracted one by one keep their files
+  file_indices: FileIndices,
+  _pdfium: Arc<Pdfium>,
+}
+
+#[napi]
+/// Pdf document opened once for repeated queries until it is closed
+pub struct PdfDocumentHandle {
+  options: ExtractOptions,
+  document: Arc<Mutex<Option<OpenDocument>>>,
+}
+
+#[napi(catch_unwind)]
+/// Open pdf document for repeated queries, configured with options used by all of them
+pub async fn open_document(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+  options: Option<ExtractOptions>,
+) -> napi::Result<PdfDocumentHandle> {
+  run_blocking(move || {
+    let options = options.unwrap_or_default();
+    let pdfium = get_pdfium(&pdfium_dir)?;
+    let document = load_document(&pdfium, &pdf_path)?;
+    // SAFETY: the document is kept with the pdfium library it borrows, which is dropped after it
+    let document =
+      unsafe { std::mem::transmute::<PdfDocument<'_>, PdfDocument<'static>>(document) };
+    let document = OpenDocument {
+      raw_document: RawDocument::new(document.bindings(), &pdf_path),
+      document,
+      file_indices: FileIndices::default(),
+      _pdfium: pdfium.pdfium.clone(),
+    };
+
+    Ok(PdfDocumentHandle {
+      options,
+      document: Arc::new(Mutex::new(Some(document))),
+    })
+  })
+  .await
+}
+
+#[napi]
+impl PdfDocumentHandle {
+  #[napi(catch_unwind)]
+  /// Count document pages
+  pub async fn page_count(&self) -> napi::Result<u32> {
+    self
+      .query(|open, _| Ok(open.document.pages().len() as u32))
+      .await
+  }
+
+  #[napi(catch_unwind)]
+  /// Extract page text like `extractTextWithOptions`
+  pub async fn page_text(&self, page_index: u32) -> napi::Result<String> {
+    self
+      .query(move |open, options| {
+        let page = get_page(&open.document, page_index)?;
+        page_text(&page, page_index as usize, options)
+      })
+      .await
+  }
+
+  #[napi(catch_unwind)]
+  /// Extract page images with related text like `extractTextAndImagesWithOptions`
+  pub async fn page_images(&self, page_index: u32) -> napi::Result<Vec<ExtractedImageMeta>> {
+    self
+      .query(move |open, options| {
+        let page = extract_pages(
+          &open.document,
+          &open.raw_document,
+          Some(page_index),
+          None,
+          &mut open.file_indices,
+          options,
+          true,
+        )?
+        .pop();
+        Ok(page.map(|page| page.page_images).unwrap_or_default())
+      })
+      .await
+  }
+
+  #[napi(catch_unwind)]
+  /// Read document metadata
+  pub async fn metadata(&self) -> napi::Result<DocumentMetadata> {
+    self
+      .query(|open, _| Ok(document_metadata(&open.document, &open.raw_document)))
+      .await
+  }
+
+  #[napi(catch_unwind)]
+  /// Close document, queries fail once it is closed
+  pub async fn close(&self) -> napi::Result<()> {
+    let document = self.document.clone();
+    run_blocking(move || {
+      close_document(&document);
+      Ok(())
+    })
+    .await
+  }
+
+  /// Run query on open document holding pdfium
+  async fn query<T: Send + 'static>(
+    &self,
+    query: impl FnOnce(&mut OpenDocument, &ExtractOptions) -> napi::Result<T> + Send + 'static,
+  ) -> napi::Result<T> {
+    let document = self.document.clone();
+    let options = self.options.clone();
+    run_blocking(move || {
+      let _access = PDFIUM_ACCESS.lock().unwrap_or_else(|err| err.into_inner());
+      let mut document = document.lock().unwrap_or_else(|err| err.into_inner());
+      match document.as_mut() {
+        Some(open) => query(open, &options),
+        None => Err(PdfError::new(ErrorCode::DocumentClosed, "Pdf document is closed").into()),
+      }
+    })
+    .await
+  }
+}
+
+// document collected without being closed is closed on another thread once pdfium is not used
+// by other functions, garbage collection does not wait for them
+impl Drop for PdfDocumentHandle {
+  fn drop(&mut self) {
+    let is_closed = self
+      .document
+      .try_lock()
+      .is_ok_and(|document| document.is_none());
+    if !is_closed {
+      let document = self.document.clone();
+      std::thread::spawn(move || close_document(&document));
+    }
+  }
+}
+
+fn close_document(document: &Mutex<Option<OpenDocument>>) {
+  let _access = PDFIUM_ACCESS.lock().unwrap_or_else(|err| err.into_inner());
+  let mut document = document.lock().unwrap_or_else(|err| err.into_inner());
+  drop(document.take());
+}
+
+fn get_page<'a>(document: &'a PdfDocument, page_index: u32) -> napi::Result<PdfPage<'a>> {
+  if page_index >= document.pages().len() as u32 {
+    return Err(PdfError::page_out_of_range(page_index as usize).into());
+  }
+  document
+    .pages()
+    .get(page_index as PdfPageIndex)
+    .map_err(|err| {
+      PdfError::page(page_index as usize, "Failed to read pdf document page")
+        .caused_by(err)
+        .into()
+    })
+}
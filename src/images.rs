@@ -0,0 +1,370 @@
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView, ImageFormat, ImageResult};
+use pdfium_render::prelude::*;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::io::Cursor;
+use std::os::raw::{c_int, c_void};
+
+use crate::layout::Bounds;
+use crate::options::ImageOutputFormat;
+use crate::RawDocument;
+
+// default JPEG quality when not set in options
+pub static DEFAULT_JPEG_QUALITY: u8 = 80;
+// extracted image filename when no template is set in options
+pub static DEFAULT_IMAGE_FILENAME_TEMPLATE: &str = "image-{n}.{ext}";
+// pdfium image page object type
+pub static PAGE_OBJECT_IMAGE: c_int = 3;
+// allowed bounds difference to match image object read through pdfium bindings
+static BOUNDS_TOLERANCE: f32 = 0.01;
+// image matrix values smaller than this part of its scale are taken as zero
+static MATRIX_TOLERANCE: f32 = 0.0001;
+
+impl ImageOutputFormat {
+  /// Image filename extension
+  pub fn extension(&self) -> &'static str {
+    match self {
+      ImageOutputFormat::Png => "png",
+      ImageOutputFormat::Jpeg => "jpg",
+      ImageOutputFormat::Webp => "webp",
+    }
+  }
+}
+
+/// Encode image in output format, quality is used only for lossy formats
+pub fn encode_image(
+  image: &DynamicImage,
+  format: ImageOutputFormat,
+  quality: Option<u8>,
+) -> ImageResult<Vec<u8>> {
+  let mut data = Cursor::new(vec![]);
+  match format {
+    ImageOutputFormat::Png => image.write_to(&mut data, ImageFormat::Png)?,
+    ImageOutputFormat::Jpeg => {
+      // JPEG has no alpha channel
+      let encoder = JpegEncoder::new_with_quality(
+        &mut data,
+        quality.unwrap_or(DEFAULT_JPEG_QUALITY).clamp(1, 100),
+      );
+      DynamicImage::ImageRgb8(image.to_rgb8()).write_with_encoder(encoder)?
+    }
+    ImageOutputFormat::Webp => {
+      // only lossless WebP encoding is supported, which requires 8 bit RGB(A)
+      let encoder = WebPEncoder::new_lossless(&mut data);
+      DynamicImage::ImageRgba8(image.to_rgba8()).write_with_encoder(encoder)?
+    }
+  };
+  Ok(data.into_inner())
+}
+
+/// Image filename from template with placeholders replaced, page and image numbers start at 1
+pub fn image_filename(
+  template: &str,
+  pdf_stem: &str,
+  page: usize,
+  index: u32,
+  n: u32,
+  extension: &str,
+) -> String {
+  template
+    .replace("{pdfStem}", pdf_stem)
+    .replace("{page}", &page.to_string())
+    .replace("{index}", &index.to_string())
+    .replace("{n}", &n.to_string())
+    .replace("{ext}", extension)
+}
+
+/// Hash of image dimensions and pixels to find identical images
+pub fn hash_image(image: &DynamicImage) -> u64 {
+  let mut hasher = DefaultHasher::new();
+  image.width().hash(&mut hasher);
+  image.height().hash(&mut hasher);
+  image.as_bytes().hash(&mut hasher);
+  hasher.finish()
+}
+
+/// Render image object at the size it appears on the page in given DPI, fitted into maximum
+/// dimension
+pub fn render_image_object(
+  document: &PdfDocument,
+  image: &PdfPageImageObject,
+  dpi: f32,
+  max_dimension: Option<u32>,
+) -> Result<DynamicImage, PdfiumError> {
+  let bounds = image.bounds()?;
+  // page space is measured in points, 72 per inch
+  let scale = dpi / 72.0;
+  let width = (bounds.width().value * scale).round().max(1.0) as u32;
+  let height = (bounds.height().value * scale).round().max(1.0) as u32;
+  let (width, height) = match max_dimension {
+    Some(max_dimension) => fit_size(width, height, max_dimension),
+    None => (width, height),
+  };
+  image.get_processed_image_with_size(document, width as Pixels, height as Pixels)
+}
+
+/// Embedded image size in pixels without decoding it, pdfium derives image DPI from its pixel
+/// size and bounds
+pub fn image_pixel_size(image: &PdfPageImageObject) -> Option<(u32, u32)> {
+  let bounds = image.bounds().ok()?;
+  let width = image.horizontal_dpi().ok()? * bounds.width().value / 72.0;
+  let height = image.vertical_dpi().ok()? * bounds.height().value / 72.0;
+  (width >= 1.0 && height >= 1.0).then(|| (width.round() as u32, height.round() as u32))
+}
+
+/// Embedded image resolution in DPI at its size on the page, lower of horizontal and vertical,
+/// measured along image axes so turned images are not taken by their bounding box
+pub fn image_resolution(image: &PdfPageImageObject) -> Option<f64> {
+  let (width, height) = image_pixel_size(image)?;
+  let matrix = image.matrix().ok()?;
+  // image space unit square is drawn as a parallelogram with these side lengths in points
+  let displayed_width = (matrix.a() as f64).hypot(matrix.b() as f64);
+  let displayed_height = (matrix.c() as f64).hypot(matrix.d() as f64);
+  if displayed_width == 0.0 || displayed_height == 0.0 {
+    return None;
+  }
+  let horizontal = width as f64 * 72.0 / displayed_width;
+  let vertical = height as f64 * 72.0 / displayed_height;
+  Some(horizontal.min(vertical))
+}
+
+/// Size scaled down to fit into maximum dimension keeping its aspect ratio
+pub fn fit_size(width: u32, height: u32, max_dimension: u32) -> (u32, u32) {
+  let largest = width.max(height);
+  if largest <= max_dimension {
+    return (width, height);
+  }
+  let scale = max_dimension.max(1) as f64 / largest as f64;
+  let fit = |size: u32| ((size as f64 * scale).round() as u32).max(1);
+  (fit(width), fit(height))
+}
+
+/// Embedded image larger than maximum dimension rendered by pdfium in the fitted size when it
+/// is displayed upright, so the full size image is not decoded
+pub fn downscaled_image(
+  document: &PdfDocument,
+  image: &PdfPageImageObject,
+  max_dimension: u32,
+) -> Result<DynamicImage, PdfiumError> {
+  let is_upright = image
+    .matrix()
+    .is_ok_and(|m| m.a() > 0.0 && m.b() == 0.0 && m.c() == 0.0 && m.d() > 0.0);
+  match image_pixel_size(image) {
+    Some((width, height))
+      if width.max(height) > max_dimension && is_upright && !image.has_transparency() =>
+    {
+      let (width, height) = fit_size(width, height, max_dimension);
+      let image =
+        image.get_processed_image_with_size(document, width as Pixels, height as Pixels)?;
+      // pdfium renders image only approximately in requested size
+      if image.dimensions() == (width, height) {
+        return Ok(image);
+      }
+      Ok(image.resize_exact(width, height, FilterType::Triangle))
+    }
+    _ => image_with_soft_mask(document, image),
+  }
+}
+
+/// Image scaled down to fit into maximum dimension, unchanged when it fits
+pub fn fit_image(image: DynamicImage, max_dimension: u32) -> DynamicImage {
+  let (width, height) = fit_size(image.width(), image.height(), max_dimension);
+  if (width, height) == image.dimensions() {
+    return image;
+  }
+  image.resize_exact(width, height, FilterType::Triangle)
+}
+
+/// Embedded image with alpha of its soft mask, which pdfium leaves out of raw image. The alpha
+/// is taken from image rendered in its size, unless it is rotated or drawn with transparent
+/// graphics state, which is not part of the image
+pub fn image_with_soft_mask(
+  document: &PdfDocument,
+  image: &PdfPageImageObject,
+) -> Result<DynamicImage, PdfiumError> {
+  let raw = image.get_raw_image()?;
+  let is_axis_aligned = image.matrix().is_ok_and(|m| m.b() == 0.0 && m.c() == 0.0);
+  if !is_axis_aligned || image.has_transparency() {
+    return Ok(raw);
+  }
+  let (width, height) = (raw.width() as Pixels, raw.height() as Pixels);
+  let rendered = match image.get_processed_image_with_size(document, width, height) {
+    Ok(rendered) if !is_opaque(&rendered) => rendered.to_rgba8(),
+    _ => return Ok(raw),
+  };
+  let mut image = raw.to_rgba8();
+  if image.dimensions() != rendered.dimensions() {
+    return Ok(raw);
+  }
+  for (pixel, rendered) in image.pixels_mut().zip(rendered.pixels()) {
+    pixel[3] = rendered[3];
+  }
+  Ok(DynamicImage::ImageRgba8(image))
+}
+
+/// Image has no transparent pixels
+pub fn is_opaque(image: &DynamicImage) -> bool {
+  !image.color().has_alpha() || image.to_rgba8().pixels().all(|pixel| pixel[3] == u8::MAX)
+}
+
+/// Linear part a/b/c/d of image matrix as the image is displayed on the page turned by its
+/// clockwise rotation, none when the image is displayed upright
+pub fn displayed_matrix(matrix: PdfMatrix, page_rotation: u32) -> Option<(f32, f32, f32, f32)> {
+  let (a, b, c, d) = (matrix.a(), matrix.b(), matrix.c(), matrix.d());
+  let (a, b, c, d) = match page_rotation {
+    90 => (b, -a, d, -c),
+    180 => (-a, -b, -c, -d),
+    270 => (-b, a, -d, c),
+    _ => (a, b, c, d),
+  };
+  let tolerance = a.abs().max(b.abs()).max(c.abs()).max(d.abs()) * MATRIX_TOLERANCE;
+  let zero = |value: f32| if value.abs() < tolerance { 0.0 } else { value };
+  let (a, b, c, d) = (zero(a), zero(b), zero(c), zero(d));
+  let is_upright = b == 0.0 && c == 0.0 && a > 0.0 && d > 0.0;
+  (!is_upright).then_some((a, b, c, d))
+}
+
+/// Turn and mirror image as it is displayed by image matrix linear part. Images turned by
+/// quarters are only reordered, images turned by other angles or skewed are resampled into
+/// their bounding box with transparent corners
+pub fn transform_image(image: DynamicImage, (a, b, c, d): (f32, f32, f32, f32)) -> DynamicImage {
+  // image space rows go from top, x axis is mirrored by negative a and y axis by negative d
+  if b == 0.0 && c == 0.0 {
+    let image = if a < 0.0 { image.fliph() } else { image };
+    return if d < 0.0 { image.flipv() } else { image };
+  }
+  // image x axis is displayed vertically and y axis horizontally
+  if a == 0.0 && d == 0.0 {
+    let transposed = image.rotate90().fliph();
+    let image = if b > 0.0 {
+      transposed.flipv()
+    } else {
+      transposed
+    };
+    return if c > 0.0 { image.fliph() } else { image };
+  }
+
+  let source = image.to_rgba8();
+  let (width, height) = (source.width() as f32, source.height() as f32);
+  let determinant = a * d - b * c;
+  if determinant == 0.0 {
+    return image;
+  }
+  // keep the number of pixels
+  let scale = (width * height / determinant.abs()).sqrt();
+  let corners = [(0.0, 0.0), (a, b), (c, d), (a + c, b + d)];
+  let (min_x, max_x, min_y, max_y) = corners.iter().fold(
+    (f32::MAX, f32::MIN, f32::MAX, f32::MIN),
+    |(min_x, max_x, min_y, max_y), (x, y)| {
+      (min_x.min(*x), max_x.max(*x), min_y.min(*y), max_y.max(*y))
+    },
+  );
+  let output_width = ((max_x - min_x) * scale).round().max(1.0) as u32;
+  let output_height = ((max_y - min_y) * scale).round().max(1.0) as u32;
+  let output = image::RgbaImage::from_fn(output_width, output_height, |column, row| {
+    // displayed point back in image space
+    let x = min_x + (column as f32 + 0.5) / scale;
+    let y = max_y - (row as f32 + 0.5) / scale;
+    let u = (d * x - c * y) / determinant;
+    let v = (a * y - b * x) / determinant;
+    if (0.0..1.0).contains(&u) && (0.0..1.0).contains(&v) {
+      *source.get_pixel((u * width) as u32, ((1.0 - v) * height) as u32)
+    } else {
+      image::Rgba([0, 0, 0, 0])
+    }
+  });
+  DynamicImage::ImageRgba8(output)
+}
+
+/// Image object has JPEG data in gray or RGB colors, which is its only filter. CMYK JPEG is
+/// often stored inverted with decode array in image dictionary, which is lost in original data,
+/// and is converted into RGB by pdfium instead
+pub fn is_jpeg_image(image: &PdfPageImageObject) -> bool {
+  let filters = image.filters();
+  let is_jpeg = filters.len() == 1
+    && filters
+      .get(0)
+      .is_ok_and(|filter| filter.name() == "DCTDecode");
+  let is_gray_or_rgb = matches!(
+    image.color_space(),
+    Ok(
+      PdfColorSpace::DeviceGray
+        | PdfColorSpace::DeviceRGB
+        | PdfColorSpace::CalibratedCIEGray
+        | PdfColorSpace::CalibratedCIERGB
+        | PdfColorSpace::CalibratedICCProfile
+    )
+  ) && matches!(image.bits_per_pixel(), Ok(8 | 24));
+  is_jpeg && is_gray_or_rgb
+}
+
+/// Original JPEG data of DCT encoded images on the page with their bounds, read through pdfium
+/// bindings as pdfium-render exposes only decoded images
+pub fn page_jpeg_images(raw_document: &RawDocument, page_index: usize) -> Vec<(Bounds, Vec<u8>)> {
+  let bindings = raw_document.bindings();
+  let Some(document) = raw_document.handle() else {
+    return vec![];
+  };
+  let page = bindings.FPDF_LoadPage(document, page_index as c_int);
+  let mut images = vec![];
+  if !page.is_null() {
+    for idx in 0..bindings.FPDFPage_CountObjects(page) {
+      let object = bindings.FPDFPage_GetObject(page, idx);
+      if object.is_null()
+        || bindings.FPDFPageObj_GetType(object) != PAGE_OBJECT_IMAGE
+        || bindings.FPDFImageObj_GetImageFilterCount(object) != 1
+      {
+        continue;
+      }
+
+      // filter name is NUL terminated
+      let mut filter = vec![0u8; 16];
+      let filter_length = bindings.FPDFImageObj_GetImageFilter(
+        object,
+        0,
+        filter.as_mut_ptr() as *mut c_void,
+        filter.len() as _,
+      ) as usize;
+      if filter_length == 0
+        || filter_length > filter.len()
+        || filter[..filter_length - 1] != *b"DCTDecode"
+      {
+        continue;
+      }
+
+      let (mut left, mut bottom, mut right, mut top) = (0.0, 0.0, 0.0, 0.0);
+      if !bindings.is_true(bindings.FPDFPageObj_GetBounds(
+        object,
+        &mut left,
+        &mut bottom,
+        &mut right,
+        &mut top,
+      )) {
+        continue;
+      }
+
+      let length = bindings.FPDFImageObj_GetImageDataRaw(object, std::ptr::null_mut(), 0);
+      let mut data = vec![0u8; length as usize];
+      bindings.FPDFImageObj_GetImageDataRaw(object, data.as_mut_ptr() as *mut c_void, length);
+      images.push(((left, bottom, right, top), data));
+    }
+    bindings.FPDF_ClosePage(page);
+  }
+  images
+}
+
+/// JPEG data of image object with the bounds, none when images with different data match them
+pub fn find_jpeg_image(jpeg_images: &[(Bounds, Vec<u8>)], bounds: Bounds) -> Option<Vec<u8>> {
+  let matches = |(a, _): &&(Bounds, Vec<u8>)| {
+    (a.0 - bounds.0).abs() < BOUNDS_TOLERANCE
+      && (a.1 - bounds.1).abs() < BOUNDS_TOLERANCE
+      && (a.2 - bounds.2).abs() < BOUNDS_TOLERANCE
+      && (a.3 - bounds.3).abs() < BOUNDS_TOLERANCE
+  };
+  let mut found = jpeg_images.iter().filter(matches).map(|(_, data)| data);
+  let data = found.next()?;
+  found.all(|other| other == data).then(|| data.clone())
+}
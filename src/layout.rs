@@ -0,0 +1,834 @@
+use pdfium_render::prelude::*;
+
+use std::collections::HashMap;
+use unicode_bidi::{bidi_class, BidiClass, BidiInfo};
+
+use crate::document::rotation_degrees;
+use crate::options::{ExtractOptions, RelatedTextPosition};
+use crate::{ExtractedPage, TextLineKind, TextLineOrImage};
+
+/// Bounds left/bottom/right/top in page space points
+pub type Bounds = (f32, f32, f32, f32);
+
+// figure and table labels starting image captions
+static CAPTION_LABELS: [&str; 14] = [
+  "figure",
+  "fig.",
+  "fig",
+  "table",
+  "tab.",
+  "image",
+  "chart",
+  "photo",
+  "illustration",
+  "abbildung",
+  "abb.",
+  "tabelle",
+  "bild",
+  "grafik",
+];
+// closest text lines on each image side searched for its caption
+static CAPTION_SEARCH_LINES: usize = 3;
+
+// vertical gap between lines relative to line height to start a new paragraph
+static PARAGRAPH_GAP_RATIO: f32 = 0.8;
+// line height difference relative to previous line height to start a new paragraph
+static PARAGRAPH_LINE_HEIGHT_RATIO: f32 = 0.3;
+// first line indentation to start a new paragraph
+static PARAGRAPH_INDENT: f32 = 10.0;
+// font size relative to body text for first and second level headings
+static H1_FONT_SIZE_RATIO: f64 = 1.5;
+static H2_FONT_SIZE_RATIO: f64 = 1.15;
+// longer lines are paragraphs text
+static MAX_HEADING_CHARS: usize = 100;
+// page margin relative to page height where headers and footers are searched
+static HEADER_FOOTER_MARGIN_RATIO: f32 = 0.12;
+// share of pages text must repeat on to be considered header or footer
+static HEADER_FOOTER_MIN_PAGES_RATIO: f32 = 0.5;
+// allowed vertical position difference of repeating header or footer text
+static HEADER_FOOTER_POSITION_DIFF: f32 = 5.0;
+// share of pages image must repeat on to be considered decorative
+static RECURRING_IMAGE_MIN_PAGES_RATIO: f32 = 0.5;
+// allowed bounds difference of image repeating at the same position
+static RECURRING_IMAGE_POSITION_DIFF: f32 = 5.0;
+// minimal horizontal gap between columns, narrower gaps separate words
+static MIN_COLUMN_GAP: f32 = 12.0;
+// gap between text objects relative to their average glyph width to separate words
+static WORD_GAP_RATIO: f32 = 0.3;
+// typical glyph width relative to font size
+static AVERAGE_GLYPH_WIDTH: f32 = 0.5;
+// part of text area covered by the same text to be considered drawn twice
+pub static DUPLICATE_OVERLAP_RATIO: f32 = 0.5;
+
+/// Move the rest of words hyphenated at line end from the next line starting lowercase,
+/// dropping the hyphen and lines left empty
+pub fn dehyphenate(page_text_lines_and_images: &mut Vec<TextLineOrImage>) {
+  for idx in 1..page_text_lines_and_images.len() {
+    let (before, after) = page_text_lines_and_images.split_at_mut(idx);
+    let (previous, next) = match (&mut before[idx - 1], &mut after[0]) {
+      (TextLineOrImage::TextLine(previous, ..), TextLineOrImage::TextLine(next, ..)) => {
+        (previous, next)
+      }
+      _ => continue,
+    };
+    let stem = match previous.strip_suffix(['-', '\u{00AD}', '\u{2010}']) {
+      Some(stem) if stem.ends_with(char::is_alphabetic) => stem.len(),
+      _ => continue,
+    };
+    if !next.starts_with(char::is_lowercase) {
+      continue;
+    }
+
+    let end = next.find(char::is_whitespace).unwrap_or(next.len());
+    previous.truncate(stem);
+    previous.push_str(&next[..end]);
+    *next = next[end..].trim_start().to_owned();
+  }
+
+  page_text_lines_and_images
+    .retain(|item| !matches!(item, TextLineOrImage::TextLine(text, ..) if text.is_empty()));
+}
+
+/// Closest text lines to the image at index, ordered by distance from it
+pub fn related_text(
+  page_text_lines_and_images: &[&TextLineOrImage],
+  idx: usize,
+  options: &ExtractOptions,
+) -> Vec<String> {
+  let image_bounds = match page_text_lines_and_images.get(idx) {
+    Some(TextLineOrImage::Image(_, bounds)) => *bounds,
+    _ => return vec![],
+  };
+  let lines_count = options.related_text_lines.unwrap_or(2) as usize;
+  let max_distance = options
+    .related_text_max_distance
+    .map(|distance| distance as f32);
+  // text lines with their vertical distance to image and whether they are adjacent to it,
+  // from the closest
+  let lines = |items: &mut dyn Iterator<Item = &&TextLineOrImage>, count: usize| {
+    items
+      .filter_map(|item| match item {
+        TextLineOrImage::TextLine(text, bounds, _) => {
+          let (distance, is_adjacent) = match (image_bounds, bounds) {
+            (Some(image), Some(line)) => {
+              let distance = vertical_distance(image, *line);
+              let overlaps_horizontally = line.0 < image.2 && image.0 < line.2;
+              (
+                distance,
+                overlaps_horizontally && distance <= line.3 - line.1,
+              )
+            }
+            _ => (0.0, false),
+          };
+          Some((distance, text.clone(), is_adjacent))
+        }
+        _ => None,
+      })
+      .take_while(|(distance, ..)| max_distance.is_none_or(|max| *distance <= max))
+      .take(count)
+      .collect::<Vec<_>>()
+  };
+  let above = |count| lines(&mut page_text_lines_and_images[..idx].iter().rev(), count);
+  let below = |count| lines(&mut page_text_lines_and_images[idx + 1..].iter(), count);
+
+  let mut related = match options.related_text_position {
+    Some(RelatedTextPosition::Above) => above(lines_count),
+    Some(RelatedTextPosition::Below) => below(lines_count),
+    Some(RelatedTextPosition::Both) => {
+      let mut related = above(lines_count);
+      related.extend(below(lines_count));
+      related.sort_by(|(a, ..), (b, ..)| a.total_cmp(b));
+      related
+    }
+    // image at page top relates to text below it
+    None if idx == 0 => below(lines_count),
+    None => above(lines_count),
+  };
+
+  // captions and lines adjacent to image on allowed sides go first
+  let mut candidates = match options.related_text_position {
+    Some(RelatedTextPosition::Above) => above(CAPTION_SEARCH_LINES),
+    Some(RelatedTextPosition::Below) => below(CAPTION_SEARCH_LINES),
+    _ => {
+      let mut candidates = above(CAPTION_SEARCH_LINES);
+      candidates.extend(below(CAPTION_SEARCH_LINES));
+      candidates.sort_by(|(a, ..), (b, ..)| a.total_cmp(b));
+      candidates
+    }
+  };
+  if candidates.iter().any(|(_, text, _)| is_caption(text)) {
+    candidates.retain(|(_, text, _)| is_caption(text));
+  } else {
+    candidates.retain(|(_, _, is_adjacent)| *is_adjacent);
+  }
+  related.retain(|(_, text, _)| !candidates.iter().any(|(_, caption, _)| caption == text));
+  candidates.append(&mut related);
+
+  candidates
+    .into_iter()
+    .take(lines_count)
+    .map(|(_, text, _)| text)
+    .collect()
+}
+
+/// Text line starts with figure or table label followed by its number, like "Figure 3:" or
+/// "Abb. 2"
+fn is_caption(text: &str) -> bool {
+  let text = text.trim().to_lowercase();
+  CAPTION_LABELS.iter().any(|label| {
+    text
+      .strip_prefix(label)
+      .is_some_and(|rest| rest.trim_start().starts_with(|c: char| c.is_ascii_digit()))
+  })
+}
+
+/// Vertical gap between bounds, 0 when they overlap vertically
+fn vertical_distance(a: Bounds, b: Bounds) -> f32 {
+  (a.1 - b.3).max(b.1 - a.3).max(0.0)
+}
+
+/// Merge consecutive page text lines into paragraphs, images break paragraphs
+pub fn page_paragraphs(page_text_lines_and_images: &[TextLineOrImage]) -> Vec<String> {
+  let mut paragraphs: Vec<String> = vec![];
+  let mut paragraph = String::new();
+  let mut last_bounds: Option<Bounds> = None;
+
+  for item in page_text_lines_and_images {
+    let (text, bounds) = match item {
+      TextLineOrImage::TextLine(text, bounds, _) => (text, bounds),
+      TextLineOrImage::Image(..) => {
+        if !paragraph.is_empty() {
+          paragraphs.push(std::mem::take(&mut paragraph));
+        }
+        last_bounds = None;
+        continue;
+      }
+    };
+
+    let is_new_paragraph = match (last_bounds, bounds) {
+      (Some(last), Some(current)) => starts_paragraph(last, *current),
+      _ => true,
+    };
+    if is_new_paragraph && !paragraph.is_empty() {
+      paragraphs.push(std::mem::take(&mut paragraph));
+    }
+
+    if !paragraph.is_empty() {
+      paragraph.push(' ');
+    }
+    paragraph.push_str(text.trim());
+    last_bounds = *bounds;
+  }
+
+  if !paragraph.is_empty() {
+    paragraphs.push(paragraph);
+  }
+
+  paragraphs
+}
+
+/// Vertical gap, indentation or line height change between lines starts a new paragraph
+pub fn starts_paragraph(last: Bounds, current: Bounds) -> bool {
+  let (last_left, last_bottom, _, last_top) = last;
+  let (left, _, _, top) = current;
+  let last_height = last_top - last_bottom;
+  let height = current.3 - current.1;
+
+  let gap = last_bottom - top;
+  let is_large_gap = gap > last_height.max(height) * PARAGRAPH_GAP_RATIO;
+  let is_indented = left - last_left > PARAGRAPH_INDENT;
+  let is_different_size = (height - last_height).abs() > last_height * PARAGRAPH_LINE_HEIGHT_RATIO;
+
+  is_large_gap || is_indented || is_different_size
+}
+
+pub fn object_bounds(object: &PdfPageObject) -> Option<Bounds> {
+  object.bounds().ok().map(|b| {
+    (
+      b.left().value,
+      b.bottom().value,
+      b.right().value,
+      b.top().value,
+    )
+  })
+}
+
+/// Text objects on the same line are separate words when space is written at their edges or
+/// their gap is wider than a part of their average glyph width
+pub fn is_word_gap(
+  previous: &PdfPageObject,
+  current: &PdfPageObject,
+  text_page: &PdfPageText,
+) -> bool {
+  let (previous, current) = match (previous.as_text_object(), current.as_text_object()) {
+    (Some(previous), Some(current)) => (previous, current),
+    _ => return true,
+  };
+  let (previous_text, current_text) = (
+    text_page.for_object(previous),
+    text_page.for_object(current),
+  );
+  if previous_text.ends_with(char::is_whitespace) || current_text.starts_with(char::is_whitespace) {
+    return true;
+  }
+  let (a, b) = match (previous.bounds(), current.bounds()) {
+    (Ok(a), Ok(b)) => (a, b),
+    _ => return true,
+  };
+
+  let glyph_width = |object: &PdfPageTextObject, text: &str, bounds: &PdfQuadPoints| {
+    match text.trim().chars().count() {
+      // bounds of single glyph are narrower than its advance for glyphs like "l"
+      0 | 1 => object.scaled_font_size().value * AVERAGE_GLYPH_WIDTH,
+      count => bounds.width().value / count as f32,
+    }
+  };
+  let average_glyph_width =
+    (glyph_width(previous, &previous_text, &a) + glyph_width(current, &current_text, &b)) / 2.0;
+  // right to left lines are ordered from right
+  let gap = (b.left() - a.right()).max(a.left() - b.right()).value;
+  gap > average_glyph_width * WORD_GAP_RATIO
+}
+
+/// Part of the first bounds area covered by the second bounds
+pub fn overlap_ratio(a: Bounds, b: Bounds) -> f32 {
+  let width = a.2.min(b.2) - a.0.max(b.0);
+  let height = a.3.min(b.3) - a.1.max(b.1);
+  let area = (a.2 - a.0) * (a.3 - a.1);
+  if width <= 0.0 || height <= 0.0 || area <= 0.0 {
+    return 0.0;
+  }
+  width * height / area
+}
+
+/// Drop text objects drawn again over another object with the same text, like fake bold or
+/// drop shadows, pdfium drops only repeated objects split into the same text
+pub fn remove_overlapping_duplicates(objects: &mut Vec<PdfPageObject>, text_page: &PdfPageText) {
+  let texts: Vec<Option<(String, Bounds)>> = objects
+    .iter()
+    .map(|o| {
+      let text = text_page.for_object(o.as_text_object()?).trim().to_owned();
+      (!text.is_empty()).then_some((text, object_bounds(o)?))
+    })
+    .collect();
+
+  let is_duplicate = |idx: usize| {
+    let (text, bounds) = match &texts[idx] {
+      Some(text) => text,
+      None => return false,
+    };
+    texts
+      .iter()
+      .enumerate()
+      .any(|(other_idx, other)| match other {
+        // of the same text the later object is dropped, parts of longer text are dropped
+        Some((other_text, other_bounds)) if other_idx != idx => {
+          overlap_ratio(*bounds, *other_bounds) > DUPLICATE_OVERLAP_RATIO
+            && other_text.contains(text.as_str())
+            && (other_text != text || other_idx < idx)
+        }
+        _ => false,
+      })
+  };
+  let duplicates: Vec<bool> = (0..objects.len()).map(is_duplicate).collect();
+
+  let mut duplicates = duplicates.into_iter();
+  objects.retain(|_| !duplicates.next().unwrap_or(false));
+}
+
+/// Split items into blocks in reading order with recursive XY-cut at the widest gaps between
+/// items projections, blocks above first and columns from left to right
+pub fn xy_cut<T>(items: Vec<(Bounds, T)>) -> Vec<Vec<T>> {
+  if items.len() < 2 {
+    return vec![items.into_iter().map(|(_, item)| item).collect()];
+  }
+
+  // vertical projection, gaps between lines from top to bottom
+  let y_gap = widest_gap(items.iter().map(|(b, _)| (-b.3, -b.1)).collect(), 0.0);
+  // horizontal projection, gaps between columns from left to right
+  let x_gap = widest_gap(
+    items.iter().map(|(b, _)| (b.0, b.2)).collect(),
+    MIN_COLUMN_GAP,
+  );
+
+  // wide vertical gaps separate full width titles and sections, columns are cut next and
+  // narrow gaps between lines spanning whole page last
+  let (first, second): (Vec<_>, Vec<_>) = match (y_gap, x_gap) {
+    (Some((y_size, y)), _) if y_size >= MIN_COLUMN_GAP => {
+      items.into_iter().partition(|(b, _)| -b.1 <= y)
+    }
+    (_, Some((_, x))) => items.into_iter().partition(|(b, _)| b.2 <= x),
+    (Some((_, y)), None) => items.into_iter().partition(|(b, _)| -b.1 <= y),
+    (None, None) => return vec![items.into_iter().map(|(_, item)| item).collect()],
+  };
+
+  let mut blocks = xy_cut(first);
+  blocks.append(&mut xy_cut(second));
+  blocks
+}
+
+/// Widest gap size and start between intervals wider than min gap
+fn widest_gap(mut intervals: Vec<(f32, f32)>, min_gap: f32) -> Option<(f32, f32)> {
+  intervals.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+  let mut widest: Option<(f32, f32)> = None;
+  let mut end = intervals.first()?.1;
+  for (start, interval_end) in intervals.into_iter().skip(1) {
+    let gap = start - end;
+    if gap > min_gap && widest.is_none_or(|(size, _)| gap > size) {
+      widest = Some((gap, end));
+    }
+    end = end.max(interval_end);
+  }
+
+  widest
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Direction {
+  LeftToRight,
+  RightToLeft,
+  Neutral,
+}
+
+/// Count of left to right and right to left strong characters
+fn strong_chars_count(text: &str) -> (usize, usize) {
+  text
+    .chars()
+    .fold((0, 0), |(ltr, rtl), c| match bidi_class(c) {
+      BidiClass::L => (ltr + 1, rtl),
+      BidiClass::R | BidiClass::AL => (ltr, rtl + 1),
+      _ => (ltr, rtl),
+    })
+}
+
+/// Reorder objects of one line sorted from left to right into logical reading order, runs of
+/// right to left text like Arabic or Hebrew are read from right to left and the whole line
+/// when most of its letters are right to left
+pub fn bidi_logical_order(line: &mut [PdfPageObject], text_page: &PdfPageText) {
+  let counts: Vec<(usize, usize)> = line
+    .iter()
+    .map(|o| match o.as_text_object() {
+      Some(text) => strong_chars_count(&text_page.for_object(text)),
+      None => (0, 0),
+    })
+    .collect();
+  let (ltr, rtl) = counts
+    .iter()
+    .fold((0, 0), |(ltr, rtl), count| (ltr + count.0, rtl + count.1));
+  if rtl == 0 {
+    return;
+  }
+  let base = if rtl > ltr {
+    Direction::RightToLeft
+  } else {
+    Direction::LeftToRight
+  };
+
+  let mut directions: Vec<Direction> = counts
+    .iter()
+    .map(|(ltr, rtl)| match (ltr, rtl) {
+      (0, 0) => Direction::Neutral,
+      (ltr, rtl) if rtl > ltr => Direction::RightToLeft,
+      _ => Direction::LeftToRight,
+    })
+    .collect();
+  // numbers and punctuation take direction of surrounding text, line direction in between
+  // text of different directions
+  for i in 0..directions.len() {
+    if directions[i] != Direction::Neutral {
+      continue;
+    }
+    let before = directions[..i]
+      .iter()
+      .rev()
+      .find(|d| **d != Direction::Neutral);
+    let after = directions[i..].iter().find(|d| **d != Direction::Neutral);
+    directions[i] = match (before, after) {
+      (Some(before), Some(after)) if before == after => *before,
+      _ => base,
+    };
+  }
+
+  if base == Direction::RightToLeft {
+    line.reverse();
+    directions.reverse();
+  }
+  // runs against line direction keep their own reading order
+  let mut start = 0;
+  while start < line.len() {
+    let end = start
+      + directions[start..]
+        .iter()
+        .take_while(|d| **d == directions[start])
+        .count();
+    if directions[start] != base {
+      line[start..end].reverse();
+    }
+    start = end;
+  }
+}
+
+/// Text objects written from top to bottom detected by their shape, objects of single CJK
+/// character by neighbour objects stacked in the same column
+pub fn vertical_text_objects(objects: &[PdfPageObject], text_page: &PdfPageText) -> Vec<bool> {
+  // not set for single character objects
+  let advances: Vec<Option<bool>> = objects
+    .iter()
+    .map(|o| {
+      let (text, bounds) = match (o.as_text_object(), object_bounds(o)) {
+        (Some(text), Some(bounds)) => (text, bounds),
+        _ => return Some(false),
+      };
+      let object_text = text_page.for_object(text);
+      let mut chars = object_text.chars().filter(|c| !c.is_whitespace());
+      match (chars.next(), chars.next()) {
+        (Some(c), None) if is_cjk(c) => None,
+        // characters advance downwards in column higher than one line
+        (Some(_), Some(_)) => {
+          let (width, height) = (bounds.2 - bounds.0, bounds.3 - bounds.1);
+          Some(height > text.scaled_font_size().value * 1.5 && height > width)
+        }
+        _ => Some(false),
+      }
+    })
+    .collect();
+
+  let is_stacked_with = |idx: usize, other: usize| {
+    advances[other] != Some(false)
+      && match (object_bounds(&objects[idx]), object_bounds(&objects[other])) {
+        (Some(a), Some(b)) => is_stacked(a, b),
+        _ => false,
+      }
+  };
+  (0..objects.len())
+    .map(|idx| {
+      advances[idx].unwrap_or_else(|| {
+        (idx > 0 && is_stacked_with(idx, idx - 1))
+          || (idx + 1 < objects.len() && is_stacked_with(idx, idx + 1))
+      })
+    })
+    .collect()
+}
+
+/// Bounds are in the same column one right above or below the other
+fn is_stacked(a: Bounds, b: Bounds) -> bool {
+  let overlap = a.2.min(b.2) - a.0.max(b.0);
+  let width = (a.2 - a.0).min(b.2 - b.0);
+  let height = (a.3 - a.1).max(b.3 - b.1);
+  let distance = ((a.1 + a.3) - (b.1 + b.3)).abs() / 2.0;
+  overlap > width / 2.0 && distance > height / 2.0 && distance < height * 2.0
+}
+
+fn is_cjk(c: char) -> bool {
+  matches!(c as u32,
+    // CJK symbols and punctuation, hiragana and katakana
+    0x3000..=0x30FF
+    // CJK unified ideographs and extension A
+    | 0x3400..=0x4DBF | 0x4E00..=0x9FFF
+    // hangul syllables
+    | 0xAC00..=0xD7AF
+    // compatibility ideographs
+    | 0xF900..=0xFAFF
+    // halfwidth and fullwidth forms
+    | 0xFF00..=0xFFEF
+  )
+}
+
+/// Group vertical text objects into columns from right to left, objects in column from top to
+/// bottom
+pub fn vertical_columns(objects: Vec<PdfPageObject>) -> Vec<Vec<PdfPageObject>> {
+  let mut objects: Vec<(Bounds, PdfPageObject)> = objects
+    .into_iter()
+    .map(|o| (object_bounds(&o).unwrap_or_default(), o))
+    .collect();
+  objects.sort_by(|(a, _), (b, _)| (b.0 + b.2).total_cmp(&(a.0 + a.2)));
+
+  let mut columns: Vec<Vec<(Bounds, PdfPageObject)>> = vec![];
+  for (bounds, object) in objects {
+    let column = columns.last_mut().filter(|column| {
+      let left = column
+        .iter()
+        .map(|(b, _)| b.0)
+        .fold(f32::INFINITY, f32::min);
+      let right = column
+        .iter()
+        .map(|(b, _)| b.2)
+        .fold(f32::NEG_INFINITY, f32::max);
+      right.min(bounds.2) - left.max(bounds.0) > (bounds.2 - bounds.0) / 2.0
+    });
+    match column {
+      Some(column) => column.push((bounds, object)),
+      None => columns.push(vec![(bounds, object)]),
+    }
+  }
+
+  columns
+    .into_iter()
+    .map(|mut column| {
+      column.sort_by(|(a, _), (b, _)| b.3.total_cmp(&a.3));
+      column.into_iter().map(|(_, o)| o).collect()
+    })
+    .collect()
+}
+
+/// Text in visual order from left to right as displayed, right to left runs reversed
+pub fn visual_order(text: &str) -> String {
+  let bidi = BidiInfo::new(text, None);
+  bidi
+    .paragraphs
+    .iter()
+    .map(|paragraph| bidi.reorder_line(paragraph, paragraph.range.clone()))
+    .collect()
+}
+
+/// Classify text lines as headings relative to the document body text, the font size and
+/// weight with most characters
+pub fn classify_headings(pages: &mut [ExtractedPage]) {
+  let lines = || {
+    pages
+      .iter()
+      .flat_map(|page| page.page_text_line_bounds.iter().flatten())
+  };
+
+  // characters count by font size in half points and weight
+  let mut chars_by_style: HashMap<(u32, u32), usize> = HashMap::new();
+  for line in lines() {
+    let style = ((line.font_size * 2.0).round() as u32, line.font_weight);
+    *chars_by_style.entry(style).or_default() += line.text.chars().count();
+  }
+  let (body_font_size, body_font_weight) = match chars_by_style
+    .into_iter()
+    .max_by_key(|(style, chars_count)| (*chars_count, *style))
+  {
+    Some(((font_size, font_weight), _)) => (font_size as f64 / 2.0, font_weight),
+    None => return,
+  };
+
+  for line in pages
+    .iter_mut()
+    .flat_map(|page| page.page_text_line_bounds.iter_mut().flatten())
+  {
+    let ratio = line.font_size / body_font_size;
+    let is_heading_candidate = line.text.chars().count() <= MAX_HEADING_CHARS
+      && line.text.chars().any(|c| c.is_alphabetic());
+
+    line.kind = Some(if !is_heading_candidate {
+      TextLineKind::Body
+    } else if ratio >= H1_FONT_SIZE_RATIO {
+      TextLineKind::H1
+    } else if ratio >= H2_FONT_SIZE_RATIO
+      || (ratio > 1.0 / H2_FONT_SIZE_RATIO && line.font_weight > body_font_weight)
+    {
+      TextLineKind::H2
+    } else {
+      TextLineKind::Body
+    });
+  }
+}
+
+/// Text repeating at the same vertical position in page top or bottom margin across pages
+pub struct HeadersAndFooters {
+  // normalized text and top position
+  texts: Vec<(String, f32)>,
+}
+
+impl HeadersAndFooters {
+  pub fn detect(document: &PdfDocument) -> Self {
+    // normalized texts and top positions in margins by page
+    let pages_texts: Vec<Vec<(String, f32)>> = document
+      .pages()
+      .iter()
+      .map(|page| {
+        let text_page = match page.text() {
+          Ok(text_page) => text_page,
+          Err(_) => return vec![],
+        };
+        let page_height = page.height().value;
+        page
+          .objects()
+          .iter()
+          .filter_map(|o| {
+            let text = text_page.for_object(o.as_text_object()?);
+            let top = o.bounds().ok()?.top().value;
+            (!text.trim().is_empty() && is_in_margin(top, page_height))
+              .then(|| (normalize_repeating_text(&text), top))
+          })
+          .collect()
+      })
+      .collect();
+
+    let min_pages =
+      ((pages_texts.len() as f32 * HEADER_FOOTER_MIN_PAGES_RATIO).ceil() as usize).max(2);
+    let mut texts: Vec<(String, f32)> = vec![];
+    for (text, top) in pages_texts.iter().flatten() {
+      if texts.iter().any(|item| is_same_text(item, text, *top)) {
+        continue;
+      }
+      let pages_count = pages_texts
+        .iter()
+        .filter(|page_texts| page_texts.iter().any(|item| is_same_text(item, text, *top)))
+        .count();
+      if pages_count >= min_pages {
+        texts.push((text.clone(), *top));
+      }
+    }
+
+    HeadersAndFooters { texts }
+  }
+
+  pub fn contains(&self, text: &str, top: f32, page_height: f32) -> bool {
+    if !is_in_margin(top, page_height) {
+      return false;
+    }
+    let text = normalize_repeating_text(text);
+    self.texts.iter().any(|item| is_same_text(item, &text, top))
+  }
+}
+
+fn is_in_margin(top: f32, page_height: f32) -> bool {
+  let margin = page_height * HEADER_FOOTER_MARGIN_RATIO;
+  top >= page_height - margin || top <= margin
+}
+
+fn is_same_text((item_text, item_top): &(String, f32), text: &str, top: f32) -> bool {
+  item_text == text && (item_top - top).abs() < HEADER_FOOTER_POSITION_DIFF
+}
+
+/// Images repeating at the same position across pages, like logos and border art
+pub struct RecurringImages {
+  bounds: Vec<Bounds>,
+}
+
+impl RecurringImages {
+  /// Detect images in bounds of images by page
+  pub fn detect(pages_bounds: &[Vec<Bounds>]) -> Self {
+    let min_pages =
+      ((pages_bounds.len() as f32 * RECURRING_IMAGE_MIN_PAGES_RATIO).ceil() as usize).max(2);
+    let mut bounds: Vec<Bounds> = vec![];
+    for image_bounds in pages_bounds.iter().flatten() {
+      if bounds.iter().any(|b| is_same_position(b, image_bounds)) {
+        continue;
+      }
+      let pages_count = pages_bounds
+        .iter()
+        .filter(|page_bounds| {
+          page_bounds
+            .iter()
+            .any(|b| is_same_position(b, image_bounds))
+        })
+        .count();
+      if pages_count >= min_pages {
+        bounds.push(*image_bounds);
+      }
+    }
+
+    RecurringImages { bounds }
+  }
+
+  pub fn contains(&self, bounds: &Bounds) -> bool {
+    self.bounds.iter().any(|b| is_same_position(b, bounds))
+  }
+}
+
+fn is_same_position(a: &Bounds, b: &Bounds) -> bool {
+  (a.0 - b.0).abs() < RECURRING_IMAGE_POSITION_DIFF
+    && (a.1 - b.1).abs() < RECURRING_IMAGE_POSITION_DIFF
+    && (a.2 - b.2).abs() < RECURRING_IMAGE_POSITION_DIFF
+    && (a.3 - b.3).abs() < RECURRING_IMAGE_POSITION_DIFF
+}
+
+/// Trimmed text with numbers replaced so page numbers repeat
+fn normalize_repeating_text(text: &str) -> String {
+  let mut result = String::new();
+  for c in text.trim().chars() {
+    if !c.is_ascii_digit() {
+      result.push(c);
+    } else if !result.ends_with('#') {
+      result.push('#');
+    }
+  }
+  result
+}
+
+/// Clockwise page rotation mapping page space bounds into displayed page space
+#[derive(Clone, Copy)]
+pub struct PageRotation {
+  degrees: u32,
+  // page size before rotation
+  width: f64,
+  height: f64,
+}
+
+impl PageRotation {
+  /// Rotation of the page when requested in options and the page is rotated
+  pub fn of(page: &PdfPage, options: &ExtractOptions) -> Option<Self> {
+    if !options.normalize_rotation.unwrap_or(false) {
+      return None;
+    }
+    let degrees = rotation_degrees(page);
+    // pdfium reports page size with rotation applied
+    let (width, height) = (page.width().value as f64, page.height().value as f64);
+    match degrees {
+      0 => None,
+      90 | 270 => Some(PageRotation {
+        degrees,
+        width: height,
+        height: width,
+      }),
+      _ => Some(PageRotation {
+        degrees,
+        width,
+        height,
+      }),
+    }
+  }
+
+  /// Rotated left/top/right/bottom bounds
+  pub fn apply(&self, (left, top, right, bottom): (f64, f64, f64, f64)) -> (f64, f64, f64, f64) {
+    let (x1, y1) = self.apply_point(left, top);
+    let (x2, y2) = self.apply_point(right, bottom);
+    (x1.min(x2), y1.max(y2), x1.max(x2), y1.min(y2))
+  }
+
+  /// Unrotated left/top/right/bottom bounds of bounds as displayed
+  pub fn revert(&self, (left, top, right, bottom): (f64, f64, f64, f64)) -> (f64, f64, f64, f64) {
+    let (x1, y1) = self.revert_point(left, top);
+    let (x2, y2) = self.revert_point(right, bottom);
+    (x1.min(x2), y1.max(y2), x1.max(x2), y1.min(y2))
+  }
+
+  /// Counter clockwise angle in degrees as displayed
+  pub fn apply_angle(&self, degrees: f64) -> f64 {
+    (degrees - self.degrees as f64).rem_euclid(360.0)
+  }
+
+  fn apply_point(&self, x: f64, y: f64) -> (f64, f64) {
+    match self.degrees {
+      90 => (y, self.width - x),
+      180 => (self.width - x, self.height - y),
+      270 => (self.height - y, x),
+      _ => (x, y),
+    }
+  }
+
+  fn revert_point(&self, x: f64, y: f64) -> (f64, f64) {
+    match self.degrees {
+      90 => (self.width - y, x),
+      180 => (self.width - x, self.height - y),
+      270 => (y, self.height - x),
+      _ => (x, y),
+    }
+  }
+}
+
+/// Rectangle left/top/right/bottom bounds, rotated when page rotation is given
+pub fn rect_bounds(rect: &PdfRect, rotation: Option<PageRotation>) -> (f64, f64, f64, f64) {
+  let bounds = (
+    rect.left().value as f64,
+    rect.top().value as f64,
+    rect.right().value as f64,
+    rect.bottom().value as f64,
+  );
+  rotation.map_or(bounds, |rotation| rotation.apply(bounds))
+}
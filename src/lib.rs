@@ -2,15 +2,103 @@
 
 #[macro_use]
 extern crate napi_derive;
-use image::ImageFormat;
 use itertools::{Itertools, Position};
-use once_cell::sync::OnceCell;
+use napi::bindgen_prelude::Buffer;
+use napi::threadsafe_function::ThreadsafeFunctionCallMode;
 use pdfium_render::prelude::*;
-use std::env;
+use std::cell::OnceCell;
+use std::collections::{HashMap, HashSet};
 use std::fs::{create_dir_all, File};
-use std::path::{Path, PathBuf};
+use std::ops::Deref;
+use std::os::raw::c_void;
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
 
-static PDFIUM: OnceCell<Pdfium> = OnceCell::new();
+mod abort;
+mod annotations;
+mod attachments;
+mod create;
+mod document;
+mod errors;
+mod export;
+mod fonts;
+mod forms;
+mod handle;
+mod images;
+mod layout;
+mod logger;
+mod objects;
+mod ocr;
+mod optimize;
+mod options;
+mod pages;
+mod redact;
+mod render;
+mod signatures;
+mod stamp;
+mod structure;
+mod svg;
+mod tables;
+mod text;
+mod xmp;
+
+pub use annotations::{extract_annotations, extract_links, ExtractedAnnotation, ExtractedLink};
+pub use attachments::{extract_attachments, ExtractedAttachment};
+pub use create::{create_pdf, ImageBlock, PageContent, StandardFont, TextBlock};
+pub use document::{
+  extract_bookmarks, extract_named_destinations, get_document_metadata, get_page_count,
+  get_page_dimensions, get_security_info, is_linearized, DocumentMetadata, ExtractedBookmark,
+  NamedDestination, PageDimensions, PageOrientation, SecurityInfo,
+};
+use errors::{ErrorCode, PdfError};
+pub use export::{
+  convert_to_markdown, export_alto, export_hocr, export_html, extract_layout, LayoutBlock,
+  LayoutBlockKind, LayoutLine, LayoutPage,
+};
+pub use fonts::{extract_fonts, list_fonts, ExtractedFont, FontFormat, FontInfo};
+pub use forms::{extract_form_fields, fill_form, ExtractedFormField};
+pub use handle::{open_document, PdfDocumentHandle};
+use images::{
+  displayed_matrix, downscaled_image, encode_image, find_jpeg_image, fit_image, hash_image,
+  image_filename, image_pixel_size, image_resolution, image_with_soft_mask, is_jpeg_image,
+  is_opaque, render_image_object, transform_image,
+};
+use layout::Bounds;
+use logger::log;
+pub use logger::{set_logger, LogLevel, LogMessage};
+pub use ocr::{make_searchable, SearchablePage};
+pub use optimize::{optimize_pdf, OptimizedDocument};
+pub use options::{
+  AttachmentOptions, CompareOptions, ExtractOptions, FontOptions, ImageOutputFormat,
+  ImagesToPdfOptions, OptimizeOptions, RedactOptions, RelatedTextPosition, RenderOptions,
+  SearchOptions, SearchableOptions, SplitOptions, StampOptions, UnicodeNormalization,
+};
+pub use pages::{
+  images_to_pdf, merge_pdfs, remove_pages, reorder_pages, rotate_pages, split_pdf, SplitDocument,
+};
+pub use redact::{redact_text, RedactedMatch};
+pub use render::{
+  compare_pages, render_document, render_pages, render_region, render_tiles, PageDifference,
+  RenderedPage, RenderedTile,
+};
+pub use signatures::{extract_signatures, DocumentSignatures, ExtractedSignature};
+pub use stamp::stamp_pages;
+pub use structure::{
+  check_accessibility, extract_structure_tree, AccessibilityReport, StructureElement,
+};
+pub use tables::{extract_tables, ExtractedTable, ExtractedTableCell};
+pub use text::{
+  extract_chars, extract_words, get_text_stats, search_text, ExtractedChar, ExtractedWord,
+  PageTextStats, TextSearchMatch, TextStats,
+};
+use text::{text_object_style, TextStyle};
+pub use xmp::{get_xmp_metadata, XmpMetadata};
+
+// bound pdfium library and path it was bound from
+static PDFIUM: Mutex<Option<(String, Arc<Pdfium>)>> = Mutex::new(None);
+// pdfium is not thread safe, functions using it run one at a time holding this lock
+static PDFIUM_ACCESS: Mutex<()> = Mutex::new(());
 
 #[napi(object)]
 /// Extracted image metadata
@@ -18,29 +106,140 @@ pub struct ExtractedImageMeta {
   /// Image filename
   pub filename: String,
   pub file_size_bytes: u32,
-  /// Two closest to image text lines above or below
+  /// Closest to image text lines above or below, two by default
   pub related_text: Vec<String>,
+  /// Encoded image, set when images are returned as buffers instead of saved
+  pub data: Option<Buffer>,
+  /// Filename of the first identical image when images are deduplicated
+  pub duplicate_of: Option<String>,
+  pub page_index: u32,
+  /// Image bounding rectangle on the page in page space points, origin at the bottom left page
+  /// corner
+  pub left: f64,
+  pub top: f64,
+  pub right: f64,
+  pub bottom: f64,
+  /// Extracted image size in pixels, not set for vector graphics
+  pub width: Option<u32>,
+  pub height: Option<u32>,
+  /// Embedded image resolution at its size on the page, lower of horizontal and vertical
+  pub dpi: Option<f64>,
 }
 
 #[napi(object)]
 pub struct ExtractedPage {
+  /// Page number printed on the page like "iv" or "A-1", set when the document defines page
+  /// labels
+  pub page_label: Option<String>,
   /// Page images
   pub page_images: Vec<ExtractedImageMeta>,
   /// Page text lines
   pub page_text_lines: Vec<String>,
+  /// Page vector graphics as SVG, set when vector graphics are exported
+  pub page_vector_graphics: Option<Vec<ExtractedImageMeta>>,
+  /// Page text lines with their bounds, set when text line bounds are requested
+  pub page_text_line_bounds: Option<Vec<ExtractedTextLine>>,
+  /// Page text lines merged into paragraphs, set when paragraphs detection is requested
+  pub page_paragraphs: Option<Vec<String>>,
+  /// OCR confidence of each page text line from 0 to 100, set when page text is recognized by
+  /// OCR
+  pub page_text_line_confidences: Option<Vec<f64>>,
+  /// Page is a scanned image without extractable text, regardless of OCR
+  pub is_scanned: bool,
+  /// ISO 639-3 code of page text language like "eng", set when language detection is
+  /// requested and the language is reliably detected
+  pub language: Option<String>,
+  /// What was skipped on the page, set when bad pages are skipped
+  pub warnings: Option<Vec<String>>,
+  /// Extraction stage timings, set when timings are requested
+  pub timings: Option<PageTimings>,
+}
+
+#[napi(object)]
+/// Text line with its bounding rectangle in page space points, origin at the bottom left page
+/// corner, and dominant font style and color
+pub struct ExtractedTextLine {
+  pub text: String,
+  pub page_index: u32,
+  pub left: f64,
+  pub top: f64,
+  pub right: f64,
+  pub bottom: f64,
+  /// Dominant font of the line
+  pub font_name: String,
+  pub font_size: f64,
+  /// Font weight from 100 to 900, 400 is normal and 700 is bold
+  pub font_weight: u32,
+  pub is_italic: bool,
+  /// Text fill color in hex like "#FF0000"
+  pub fill_color: String,
+  /// Heading level or body text, set when headings detection is requested
+  pub kind: Option<TextLineKind>,
+  /// OCR confidence from 0 to 100, set when the line is recognized by OCR
+  pub confidence: Option<f64>,
+}
+
+#[napi(string_enum = "lowercase")]
+/// Text line classification
+pub enum TextLineKind {
+  H1,
+  H2,
+  Body,
+}
+
+#[napi(object)]
+/// Rectangle in page space points, origin at the bottom left page corner
+pub struct PageRect {
+  pub left: f64,
+  pub top: f64,
+  pub right: f64,
+  pub bottom: f64,
+}
+
+#[napi(object)]
+/// Extraction progress reported after each processed page
+pub struct ExtractProgress {
+  pub page_index: u32,
+  pub total_pages: u32,
+  /// Images extracted so far over all processed pages
+  pub images_extracted: u32,
+}
+
+#[napi(object)]
+/// Page extraction stage timings in milliseconds
+pub struct PageTimings {
+  /// Document load, set on the first page
+  pub document_load_ms: Option<f64>,
+  /// Whole page extraction
+  pub page_ms: f64,
+  /// Page extraction without images decoding, encoding and OCR, mostly text extraction
+  pub text_ms: f64,
+  pub image_decode_ms: f64,
+  pub image_encode_ms: f64,
+  /// Scanned page text recognition
+  pub ocr_ms: f64,
 }
 
 // top y position and item
-#[derive(Clone)]
 enum TextLineOrImage {
-  TextLine(String),
-  /// image filename
-  Image(String),
+  /// text line, its bounds and dominant style
+  TextLine(String, Option<Bounds>, Option<TextStyle>),
+  /// image metadata and bounds, related text is set after page lines are complete
+  Image(ExtractedImageMeta, Option<Bounds>),
 }
 
 // allowed vertical objects position difference to consider them same line
 static SAME_LINE_RANGE_DIFF: f32 = 5.0;
 
+/// Allowed vertical position difference of object to consider it on the same line, relative to
+/// font size of text objects when tolerance is set
+fn same_line_range(object: &PdfPageObject, same_line_tolerance: Option<f32>) -> f32 {
+  match (same_line_tolerance, object.as_text_object()) {
+    (Some(tolerance), Some(text)) => text.scaled_font_size().value * tolerance,
+    _ => SAME_LINE_RANGE_DIFF,
+  }
+}
+
 #[napi(catch_unwind)]
 /// Extract text from pdf files in lines and images with related text
 pub async fn extract_text_and_images(
@@ -49,116 +248,295 @@ pub async fn extract_text_and_images(
   pdf_path: String,
   images_folder_path: String,
 ) -> napi::Result<Vec<ExtractedPage>> {
-  let pdfium = PDFIUM.get_or_try_init(|| init_pdfium(pdfium_dir))?;
+  run_blocking(move || {
+    let options = ExtractOptions {
+      images_folder_path: Some(images_folder_path),
+      ..Default::default()
+    };
+    extract_text_and_images_from_document(&pdfium_dir, &pdf_path, None, &options)
+  })
+  .await
+}
 
-  // Create images folder if not exist
-  let images_folder_path = Path::new(&images_folder_path);
-  create_dir_all(images_folder_path)?;
-  let mut image_filename_idx = 1;
+#[napi(catch_unwind)]
+/// Extract text from pdf files in lines and images with related text, configured with options
+pub async fn extract_text_and_images_with_options(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+  options: Option<ExtractOptions>,
+) -> napi::Result<Vec<ExtractedPage>> {
+  run_blocking(move || {
+    let options = options.unwrap_or_default();
+    extract_text_and_images_from_document(&pdfium_dir, &pdf_path, None, &options)
+  })
+  .await
+}
 
-  // Pdfium will only load the portions of the document it actually needs into memory. This is more efficient than loading the entire document into memory, especially when working with large documents, and allows for working with documents larger than the amount of available memory.
-  let reader =
-    File::open(pdf_path).map_err(|_| napi::Error::from_reason("Failed to open pdf document"))?;
-  let document: PdfDocument<'_> = pdfium
-    .load_pdf_from_reader(reader, None)
-    .map_err(|_| napi::Error::from_reason("Failed to read pdf document"))?;
+#[napi(catch_unwind)]
+/// Extract text lines and images with related text of single pdf page, configured with options,
+/// images of pages saved into one folder need `{page}` in image filename template
+pub async fn extract_page(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+  page_index: u32,
+  options: Option<ExtractOptions>,
+) -> napi::Result<ExtractedPage> {
+  run_blocking(move || {
+    let options = options.unwrap_or_default();
+    extract_text_and_images_from_document(&pdfium_dir, &pdf_path, Some(page_index), &options)?
+      .pop()
+      .ok_or_else(|| PdfError::page(page_index as usize, "Failed to extract pdf page").into())
+  })
+  .await
+}
+
+/// Extract all document pages or only the page of given index
+fn extract_text_and_images_from_document(
+  pdfium_dir: &str,
+  pdf_path: &str,
+  only_page_index: Option<u32>,
+  options: &ExtractOptions,
+) -> napi::Result<Vec<ExtractedPage>> {
+  let pdfium = &get_pdfium(pdfium_dir)?;
+  let load_start = Instant::now();
+  let document = load_document(pdfium, pdf_path)?;
+  let document_load = load_start.elapsed();
+  let raw_document = RawDocument::new(document.bindings(), pdf_path);
+  extract_pages(
+    &document,
+    &raw_document,
+    only_page_index,
+    Some(document_load),
+    &mut FileIndices::default(),
+    options,
+    true,
+  )
+}
+
+/// Extract pages of loaded document, its load time is reported in first page timings, images
+/// are not decoded nor listed without `with_images`
+fn extract_pages(
+  document: &PdfDocument,
+  raw_document: &RawDocument,
+  only_page_index: Option<u32>,
+  mut document_load: Option<Duration>,
+  file_indices: &mut FileIndices,
+  options: &ExtractOptions,
+  with_images: bool,
+) -> napi::Result<Vec<ExtractedPage>> {
+  let images_as_buffers = options.images_as_buffers.unwrap_or(false);
+  let images_folder_path = Path::new(options.images_folder_path.as_deref().unwrap_or_default());
+  if with_images && !images_as_buffers {
+    if images_folder_path.as_os_str().is_empty() {
+      return Err(
+        PdfError::new(ErrorCode::InvalidArgument, "Images folder path is required").into(),
+      );
+    }
+    // Create images folder if not exist
+    create_dir_all(images_folder_path).map_err(|err| {
+      PdfError::new(ErrorCode::ImageSaveFailed, "Failed to create images folder").caused_by(err)
+    })?;
+  }
+  let image_format = options.image_format.unwrap_or(ImageOutputFormat::Png);
+  let image_quality = options.image_quality.map(|q| q.min(100) as u8);
+  let image_dpi = options.image_dpi.map(|dpi| dpi as f32);
+  let keep_original_jpeg = options.keep_original_jpeg.unwrap_or(false);
+  let apply_image_transform = options.apply_image_transform.unwrap_or(false);
+  let max_image_dimension = options.max_image_dimension;
+  let deduplicate_images = options.deduplicate_images.unwrap_or(false);
+  let min_image_width = options.min_image_width.unwrap_or(0);
+  let min_image_height = options.min_image_height.unwrap_or(0);
+  let min_file_size_bytes = options.min_file_size_bytes.unwrap_or(0);
+  let export_vector_graphics = options.export_vector_graphics.unwrap_or(false);
+  let detect_headings = options.detect_headings.unwrap_or(false);
+  // headings are classified by text lines style
+  let include_text_line_bounds =
+    options.include_text_line_bounds.unwrap_or(false) || detect_headings;
+  let detect_paragraphs = options.detect_paragraphs.unwrap_or(false);
+  let column_reading_order = options.column_reading_order.unwrap_or(false);
+  let structure_reading_order = options.structure_reading_order.unwrap_or(false);
+  let strip_headers_and_footers = options.strip_headers_and_footers.unwrap_or(false);
+  let exclude_recurring_images = options.exclude_recurring_images.unwrap_or(false);
+  let ocr = options.ocr.unwrap_or(false);
+  let detect_language = options.detect_language.unwrap_or(false);
+  let bidi_visual_order = options.bidi_visual_order.unwrap_or(false);
+  let dehyphenate = options.dehyphenate.unwrap_or(false);
+  let skip_bad_pages = options.skip_bad_pages.unwrap_or(false);
+  let include_timings = options.include_timings.unwrap_or(false);
+  let same_line_tolerance = options
+    .same_line_tolerance
+    .map(|tolerance| tolerance as f32);
+  let image_filename_template = options
+    .image_filename_template
+    .as_deref()
+    .unwrap_or(images::DEFAULT_IMAGE_FILENAME_TEMPLATE);
+  let pdf_stem = Path::new(raw_document.pdf_path())
+    .file_stem()
+    .map(|stem| stem.to_string_lossy())
+    .unwrap_or_default();
+  // extracted image filename and size by content hash
+  let mut extracted_images_by_hash: HashMap<u64, (String, u32)> = HashMap::new();
+
+  check_page_limit(document, options)?;
+  let headers_and_footers =
+    strip_headers_and_footers.then(|| layout::HeadersAndFooters::detect(document));
+  let recurring_images = exclude_recurring_images.then(|| {
+    let pages_bounds: Vec<Vec<Bounds>> = document.pages().iter().map(page_images_bounds).collect();
+    layout::RecurringImages::detect(&pages_bounds)
+  });
 
   let mut result: Vec<ExtractedPage> = vec![];
+  let total_pages = document.pages().len() as u32;
+  let mut images_extracted = 0;
+  let mut images_bytes: u64 = 0;
+
+  let page_indices = match only_page_index {
+    Some(index) if index >= total_pages => {
+      return Err(PdfError::page_out_of_range(index as usize).into())
+    }
+    Some(index) => index..index + 1,
+    None => 0..total_pages,
+  };
+  // structure tree of tagged pages is read once for all extracted pages
+  let reading_orders = if structure_reading_order {
+    structure::reading_orders(raw_document, only_page_index.map(|index| index as usize))
+  } else {
+    HashMap::new()
+  };
+
+  for page_index in page_indices.map(|index| index as usize) {
+    check_aborted(options)?;
+    let page_start = Instant::now();
+    let mut image_decode = Duration::ZERO;
+    let mut image_encode = Duration::ZERO;
+    let mut page_ocr = Duration::ZERO;
+    let mut page = match document.pages().get(page_index as PdfPageIndex) {
+      Ok(page) => page,
+      Err(err) => {
+        let err = PdfError::page(page_index, "Failed to read pdf document page").caused_by(err);
+        result.push(skipped_page(err, options)?);
+        report_progress(options, page_index as u32, total_pages, images_extracted);
+        continue;
+      }
+    };
+    // Rendering image objects temporarily adjusts their matrices, page content must not be
+    // regenerated from it or later text objects of the page come out garbled
+    page.set_content_regeneration_strategy(PdfPageContentRegenerationStrategy::Manual);
 
-  for page in document.pages().iter() {
     // Retrieving the text from a text object is done internally by loading the "text page"
     // associated with the page the object is attached to, then asking that text page for the
     // text related to the object. Therefore, when iterating over many text objects (as we
     // are doing here), it is slightly faster to load the text page once rather than loading
     // it and closing it every time we access an object:
-    let text_page: PdfPageText<'_> = page
-      .text()
-      .map_err(|_| napi::Error::from_reason("Failed to read pdf document page"))?;
+    let text_page: PdfPageText<'_> = match page.text() {
+      Ok(text_page) => text_page,
+      Err(err) => {
+        let err = PdfError::page(page_index, "Failed to read pdf document page").caused_by(err);
+        result.push(skipped_page(err, options)?);
+        report_progress(options, page_index as u32, total_pages, images_extracted);
+        continue;
+      }
+    };
 
+    let mut page_image_filename_idx = 1;
+    let page_height = page.height().value;
+    let page_rotation = layout::PageRotation::of(&page, options);
+    let page_rotation_degrees = document::rotation_degrees(&page);
+    // embedded images are rendered in requested DPI instead
+    let has_jpeg_images = with_images
+      && keep_original_jpeg
+      && image_dpi.is_none()
+      && page
+        .objects()
+        .iter()
+        .any(|o| o.as_image_object().is_some_and(is_jpeg_image));
+    let page_jpeg_images = if has_jpeg_images {
+      images::page_jpeg_images(raw_document, page_index)
+    } else {
+      vec![]
+    };
+    // images of form XObjects are extracted in page space, restored after page is processed
+    transform_form_images(&page, true);
     let mut texts_and_images = page
       .objects()
       .iter()
+      .flat_map(with_form_images)
       .filter(|o| match o.object_type() {
-        PdfPageObjectType::Image => true,
+        PdfPageObjectType::Image => !recurring_images
+          .as_ref()
+          .is_some_and(|r| layout::object_bounds(o).is_some_and(|bounds| r.contains(&bounds))),
         PdfPageObjectType::Text => {
           if let Some(text) = o.as_text_object() {
             let object_text = text_page.for_object(text);
-            return !object_text.trim().is_empty();
+            let is_header_or_footer = headers_and_footers.as_ref().is_some_and(|h| {
+              o.bounds()
+                .is_ok_and(|b| h.contains(&object_text, b.top().value, page_height))
+            });
+            return !object_text.trim().is_empty() && !is_header_or_footer;
           }
           false
         }
         _ => false,
       })
       .collect::<Vec<_>>();
-
-    // Sort from top to bottom
-    texts_and_images.sort_by(|a, b| {
-      let a_bounds = a.bounds();
-      let b_bounds = b.bounds();
-
-      if a_bounds.is_err() || b_bounds.is_err() {
-        return std::cmp::Ordering::Equal;
-      }
-
-      let a_bounds = a_bounds.unwrap();
-      let b_bounds = b_bounds.unwrap();
-
-      b_bounds.top().cmp(&a_bounds.top())
-    });
-
-    // Sort items on the same line or close from left to right
-    let mut groups: Vec<(f32, usize, usize)> = vec![]; // same line items line top/start/end
-
-    // Combine items on the same line in groups
-    for i in 0..texts_and_images.len() {
-      let current = texts_and_images.get(i);
-      if current.is_none() {
-        break;
+    // page images are checked before any of them is decoded
+    if let Some(max_image_pixels) = options.max_image_pixels.filter(|_| with_images) {
+      for (width, height) in texts_and_images
+        .iter()
+        .filter_map(|o| image_pixel_size(o.as_image_object()?))
+      {
+        if width as u64 * height as u64 > max_image_pixels as u64 {
+          return Err(
+            PdfError::new(
+              ErrorCode::LimitExceeded,
+              format!(
+                "image of {}x{} pixels is larger than {} pixels",
+                width, height, max_image_pixels
+              ),
+            )
+            .on_page(page_index)
+            .into(),
+          );
+        }
       }
+    }
+    layout::remove_overlapping_duplicates(&mut texts_and_images, &text_page);
 
-      let current_bounds = current.unwrap().bounds();
-      if current_bounds.is_err() {
-        break;
-      }
-      let current_bounds = current_bounds.unwrap();
-
-      if groups.is_empty() {
-        let current_group: (f32, usize, usize) = (current_bounds.top().value, i, i);
-        groups.push(current_group);
-        continue;
-      }
-
-      let last_group = groups.last().unwrap();
-      let is_same_line = current_bounds.top().value == last_group.0
-        || (current_bounds.top().value - last_group.0).abs() < SAME_LINE_RANGE_DIFF;
-
-      if is_same_line {
-        let updated_last_group = (last_group.0, last_group.1, i);
-        groups.pop();
-        groups.push(updated_last_group);
-        continue;
-      } else {
-        let current_group: (f32, usize, usize) = (current_bounds.top().value, i, i);
-        groups.push(current_group);
-        continue;
+    // Sort in reading order, column by column or whole page from top to bottom
+    let mut block_starts: HashSet<usize> = HashSet::new();
+    let mut vertical_lines: HashMap<usize, bool> = HashMap::new();
+    if column_reading_order {
+      let blocks = layout::xy_cut(
+        texts_and_images
+          .into_iter()
+          .map(|o| (layout::object_bounds(&o).unwrap_or_default(), o))
+          .collect(),
+      );
+      texts_and_images = vec![];
+      for mut block in blocks {
+        let offset = texts_and_images.len();
+        let block_vertical_lines = sort_page_objects(&mut block, &text_page, same_line_tolerance);
+        vertical_lines.extend(
+          block_vertical_lines
+            .into_iter()
+            .map(|(idx, continues_column)| (offset + idx, continues_column)),
+        );
+        block_starts.insert(offset);
+        texts_and_images.append(&mut block);
       }
+    } else {
+      vertical_lines = sort_page_objects(&mut texts_and_images, &text_page, same_line_tolerance);
     }
-
-    // Sort groups from left to right
-    for item in groups {
-      texts_and_images[item.1..item.2 + 1].sort_by(|a, b| {
-        let a_bounds = a.bounds();
-        let b_bounds = b.bounds();
-
-        if a_bounds.is_err() || b_bounds.is_err() {
-          return std::cmp::Ordering::Equal;
-        }
-
-        let a_bounds = a_bounds.unwrap();
-        let b_bounds = b_bounds.unwrap();
-
-        a_bounds.left().cmp(&b_bounds.left())
-      });
+    // tagged content goes in the order of page structure tree instead
+    if let Some(reading_order) = reading_orders.get(&page_index) {
+      structure::apply_reading_order(
+        &mut texts_and_images,
+        reading_order,
+        &mut block_starts,
+        &mut vertical_lines,
+      );
     }
 
     // sorted page text lines and images
@@ -166,12 +544,18 @@ pub async fn extract_text_and_images(
 
     // iterator helpers
     let mut page_text_line: String = "".to_owned();
+    let mut page_text_line_bounds: Option<Bounds> = None;
+    // style of line text object with most characters
+    let mut page_text_line_style: Option<(usize, TextStyle)> = None;
     let mut last_top_pos: f32 = -1.0;
+    // images which fail to encode are skipped, failing to save one fails the extraction
+    let mut image_save_error: Option<PdfError> = None;
 
     texts_and_images
       .iter()
       .with_position()
-      .for_each(|(position, o)| {
+      .enumerate()
+      .for_each(|(idx, (position, o))| {
         let top_pos = match o.bounds() {
           Ok(v) => v.top().value,
           Err(_) => 0.0,
@@ -179,147 +563,486 @@ pub async fn extract_text_and_images(
 
         match o.object_type() {
           // extract images with related text
-          PdfPageObjectType::Image => {
+          PdfPageObjectType::Image if with_images => {
             if let Some(image) = o.as_image_object() {
-              if let Ok(image) = image.get_raw_image() {
-                let image_filename = format!("image-{}.png", image_filename_idx);
-                image_filename_idx += 1;
-                let img_path = images_folder_path.join(&image_filename);
-
-                let result = image.save_with_format(img_path, ImageFormat::Png);
-
-                match result {
-                  Ok(_) => {
-                    // push text line if present
-                    if !page_text_line.is_empty() {
-                      page_text_lines_and_images
-                        .push(TextLineOrImage::TextLine(page_text_line.clone()));
-                      page_text_line = "".to_owned();
-                    }
-
-                    page_text_lines_and_images.push(TextLineOrImage::Image(image_filename.clone()));
-                  }
-                  Err(err) => {
-                    eprintln!("failed to save image - {}, {}", image_filename, err)
-                  }
+              // original JPEG is saved as is instead of encoding decoded image
+              let jpeg_data = layout::object_bounds(o)
+                .filter(|_| !page_jpeg_images.is_empty() && is_jpeg_image(image))
+                .and_then(|bounds| find_jpeg_image(&page_jpeg_images, bounds));
+              let dpi = image_resolution(image);
+              let is_downscaled = max_image_dimension
+                .zip(image_pixel_size(image))
+                .is_some_and(|(max, (width, height))| width.max(height) > max);
+              let decode_start = Instant::now();
+              let image = match (image_dpi, max_image_dimension) {
+                (Some(dpi), _) => render_image_object(document, image, dpi, max_image_dimension),
+                (None, Some(max)) => downscaled_image(document, image, max),
+                (None, None) => image_with_soft_mask(document, image),
+              };
+              let displayed_matrix = (apply_image_transform && image_dpi.is_none())
+                .then(|| o.matrix().ok())
+                .flatten()
+                .and_then(|matrix| displayed_matrix(matrix, page_rotation_degrees));
+              let image = match displayed_matrix {
+                Some(matrix) => image.map(|image| transform_image(image, matrix)),
+                None => image,
+              };
+              // turned image bounding box may be larger than the image
+              let image = match max_image_dimension {
+                Some(max) => image.map(|image| fit_image(image, max)),
+                None => image,
+              };
+              image_decode += decode_start.elapsed();
+              // original JPEG data has no soft mask and is stored upright in full size
+              let jpeg_data = jpeg_data.filter(|_| {
+                displayed_matrix.is_none() && !is_downscaled && image.as_ref().is_ok_and(is_opaque)
+              });
+              let extension = match jpeg_data {
+                Some(_) => ImageOutputFormat::Jpeg.extension(),
+                None => image_format.extension(),
+              };
+              // skip decorative images smaller than required
+              let image = image.ok().filter(|image| {
+                image.width() >= min_image_width && image.height() >= min_image_height
+              });
+              if let Some(image) = image {
+                let (left, top, right, bottom) =
+                  image_rect(layout::object_bounds(o), page_rotation);
+                let image_hash = deduplicate_images.then(|| hash_image(&image));
+                let original = image_hash.and_then(|hash| extracted_images_by_hash.get(&hash));
+
+                let result = match original {
+                  // identical image is already extracted, refer to it
+                  Some((filename, file_size_bytes)) => Ok(ExtractedImageMeta {
+                    filename: filename.clone(),
+                    file_size_bytes: *file_size_bytes,
+                    related_text: vec![],
+                    data: None,
+                    duplicate_of: Some(filename.clone()),
+                    page_index: page_index as u32,
+                    left,
+                    top,
+                    right,
+                    bottom,
+                    width: Some(image.width()),
+                    height: Some(image.height()),
+                    dpi,
+                  }),
+                  None => jpeg_data
+                    .map_or_else(
+                      || {
+                        let encode_start = Instant::now();
+                        let data = encode_image(&image, image_format, image_quality);
+                        image_encode += encode_start.elapsed();
+                        data
+                      },
+                      Ok,
+                    )
+                    .map_err(|err| {
+                      log(
+                        LogLevel::Warn,
+                        format!(
+                          "Failed to encode image on page index {} - {}",
+                          page_index, err
+                        ),
+                      )
+                    })
+                    .and_then(|data| {
+                      let file_size_bytes = data.len() as u32;
+                      if file_size_bytes < min_file_size_bytes {
+                        return Err(());
+                      }
+
+                      let image_filename = image_filename(
+                        image_filename_template,
+                        &pdf_stem,
+                        page_index + 1,
+                        page_image_filename_idx,
+                        file_indices.image,
+                        extension,
+                      );
+                      file_indices.image += 1;
+                      page_image_filename_idx += 1;
+
+                      let data = if images_as_buffers {
+                        Some(data)
+                      } else {
+                        let img_path = images_folder_path.join(&image_filename);
+                        std::fs::write(img_path, data).map_err(|err| {
+                          image_save_error.get_or_insert(
+                            PdfError::new(
+                              ErrorCode::ImageSaveFailed,
+                              format!("Failed to save image {}", image_filename),
+                            )
+                            .on_page(page_index)
+                            .caused_by(err),
+                          );
+                        })?;
+                        None
+                      };
+
+                      if let Some(hash) = image_hash {
+                        extracted_images_by_hash
+                          .insert(hash, (image_filename.clone(), file_size_bytes));
+                      }
+
+                      Ok(ExtractedImageMeta {
+                        filename: image_filename,
+                        file_size_bytes,
+                        related_text: vec![],
+                        data: data.map(Buffer::from),
+                        duplicate_of: None,
+                        page_index: page_index as u32,
+                        left,
+                        top,
+                        right,
+                        bottom,
+                        width: Some(image.width()),
+                        height: Some(image.height()),
+                        dpi,
+                      })
+                    }),
                 };
+
+                if let Ok(meta) = result {
+                  // push text line if present
+                  if !page_text_line.is_empty() {
+                    page_text_lines_and_images.push(TextLineOrImage::TextLine(
+                      page_text_line.clone(),
+                      page_text_line_bounds.take(),
+                      page_text_line_style.take().map(|(_, style)| style),
+                    ));
+                    page_text_line = "".to_owned();
+                  }
+
+                  page_text_lines_and_images
+                    .push(TextLineOrImage::Image(meta, layout::object_bounds(o)));
+                }
               }
             }
           }
           // extract text in lines
           PdfPageObjectType::Text => {
             if let Some(t) = o.as_text_object() {
-              if last_top_pos == -1.0 {
-                page_text_line.push_str(&t.text().trim());
+              let continues_column = vertical_lines.get(&idx).copied();
+              let follows_column = idx
+                .checked_sub(1)
+                .is_some_and(|previous| vertical_lines.contains_key(&previous));
+              // vertical text column continues the line without spaces between objects
+              if last_top_pos == -1.0 || continues_column == Some(true) {
+                page_text_line.push_str(t.text().trim());
               }
-              // text is on the same line with small vertical position misalignment
-              else if top_pos > last_top_pos - SAME_LINE_RANGE_DIFF {
-                page_text_line.push_str(" ");
-                page_text_line.push_str(&t.text().trim());
+              // text is on the same line with small vertical position misalignment,
+              // next block continues the line only when it starts at the same height
+              else if continues_column.is_none()
+                && !follows_column
+                && top_pos > last_top_pos - same_line_range(o, same_line_tolerance)
+                && (!block_starts.contains(&idx)
+                  || top_pos < last_top_pos + same_line_range(o, same_line_tolerance))
+              {
+                // objects of single glyphs or word parts continue the word
+                let previous = idx
+                  .checked_sub(1)
+                  .and_then(|previous| texts_and_images.get(previous));
+                if previous.is_none_or(|previous| layout::is_word_gap(previous, o, &text_page)) {
+                  page_text_line.push(' ');
+                }
+                page_text_line.push_str(t.text().trim());
               } else {
                 if !page_text_line.is_empty() {
-                  page_text_lines_and_images
-                    .push(TextLineOrImage::TextLine(page_text_line.clone()));
+                  page_text_lines_and_images.push(TextLineOrImage::TextLine(
+                    page_text_line.clone(),
+                    page_text_line_bounds.take(),
+                    page_text_line_style.take().map(|(_, style)| style),
+                  ));
                   page_text_line = "".to_owned();
                 }
 
-                page_text_line.push_str(&t.text().trim());
+                page_text_line.push_str(t.text().trim());
+              }
+
+              if let Ok(b) = o.bounds() {
+                let (left, bottom, right, top) = (
+                  b.left().value,
+                  b.bottom().value,
+                  b.right().value,
+                  b.top().value,
+                );
+                page_text_line_bounds = Some(match page_text_line_bounds {
+                  Some((l, btm, r, t)) => (l.min(left), btm.min(bottom), r.max(right), t.max(top)),
+                  None => (left, bottom, right, top),
+                });
+              }
+
+              if include_text_line_bounds {
+                let chars_count = t.text().trim().chars().count();
+                if page_text_line_style
+                  .as_ref()
+                  .is_none_or(|(max_chars_count, _)| chars_count > *max_chars_count)
+                {
+                  page_text_line_style = Some((chars_count, text_object_style(&text_page, t)));
+                }
               }
             }
           }
           _ => {}
         };
 
-        if position == Position::Last {
+        if matches!(position, Position::Last | Position::Only) {
           // last text line of page
           if !page_text_line.is_empty() {
-            page_text_lines_and_images.push(TextLineOrImage::TextLine(page_text_line.clone()));
+            page_text_lines_and_images.push(TextLineOrImage::TextLine(
+              page_text_line.clone(),
+              page_text_line_bounds.take(),
+              page_text_line_style.take().map(|(_, style)| style),
+            ));
           }
         }
 
         last_top_pos = top_pos;
       });
+    transform_form_images(&page, false);
+    if let Some(err) = image_save_error {
+      return Err(err.into());
+    }
+
+    for item in page_text_lines_and_images.iter_mut() {
+      if let TextLineOrImage::TextLine(text, ..) = item {
+        // pdfium marks hyphens at line ends with control character
+        if let Some(stem) = text.strip_suffix('\u{2}') {
+          *text = format!("{}-", stem);
+        }
+        if bidi_visual_order {
+          *text = layout::visual_order(text);
+        }
+        *text = text::normalize_text(std::mem::take(text), options);
+      }
+    }
+    if dehyphenate {
+      layout::dehyphenate(&mut page_text_lines_and_images);
+    }
 
     // map result
     let mut page_text_lines: Vec<String> = vec![];
     let mut page_images: Vec<ExtractedImageMeta> = vec![];
 
     // map text lines
-    page_text_lines_and_images
-      .iter()
-      .for_each(|item| match item {
-        TextLineOrImage::TextLine(text) => page_text_lines.push(text.clone()),
-        _ => {}
-      });
+    page_text_lines_and_images.iter().for_each(|item| {
+      if let TextLineOrImage::TextLine(text, ..) = item {
+        page_text_lines.push(text.clone())
+      }
+    });
+
+    let mut page_text_line_bounds = include_text_line_bounds.then(|| {
+      page_text_lines_and_images
+        .iter()
+        .filter_map(|item| match item {
+          TextLineOrImage::TextLine(text, bounds, style) => {
+            let (left, bottom, right, top) = bounds.unwrap_or_default();
+            let bounds = (left as f64, top as f64, right as f64, bottom as f64);
+            let (left, top, right, bottom) = page_rotation.map_or(bounds, |r| r.apply(bounds));
+            let style = style.clone().unwrap_or_default();
+            Some(ExtractedTextLine {
+              text: text.clone(),
+              page_index: page_index as u32,
+              left,
+              top,
+              right,
+              bottom,
+              font_name: style.font_name,
+              font_size: style.font_size,
+              font_weight: style.font_weight,
+              is_italic: style.is_italic,
+              fill_color: style.fill_color,
+              kind: None,
+              confidence: None,
+            })
+          }
+          _ => None,
+        })
+        .collect()
+    });
+
+    let mut page_paragraphs =
+      detect_paragraphs.then(|| layout::page_paragraphs(&page_text_lines_and_images));
 
     // map images
     // remove artifacts and small text lines which will be hard to relate to image
-    let page_text_lines_filtered_and_images: Vec<TextLineOrImage> = page_text_lines_and_images
+    let page_text_lines_filtered_and_images: Vec<&TextLineOrImage> = page_text_lines_and_images
       .iter()
-      .cloned()
       .filter(|item| match item {
-        TextLineOrImage::TextLine(v) => v.chars().count() >= 2,
+        TextLineOrImage::TextLine(v, ..) => v.chars().count() >= 2,
         _ => true,
       })
       .collect();
 
-    page_text_lines_filtered_and_images
+    let page_images_related_text: Vec<Vec<String>> = page_text_lines_filtered_and_images
       .iter()
       .enumerate()
-      .with_position()
-      .for_each(|(position, (idx, item))| match item {
-        TextLineOrImage::Image(filename) => {
-          let related_text: Vec<String>;
-
-          if position == Position::First {
-            let next_two_text_lines: Vec<String> = page_text_lines_filtered_and_images
-              .iter()
-              .skip(idx)
-              .filter_map(|item| match item {
-                TextLineOrImage::TextLine(v) => Some(v.clone()),
-                _ => None,
-              })
-              .take(2)
-              .collect();
-
-            related_text = next_two_text_lines;
-          } else {
-            let mut previous_two_text_lines: Vec<String> = page_text_lines_filtered_and_images
-              .iter()
-              .skip(idx - 2)
-              .filter_map(|item| match item {
-                TextLineOrImage::TextLine(v) => Some(v.clone()),
-                _ => None,
-              })
-              .take(2)
-              .collect();
-            previous_two_text_lines.reverse();
-
-            related_text = previous_two_text_lines;
-          }
+      .filter_map(|(idx, item)| match item {
+        TextLineOrImage::Image(..) => Some(layout::related_text(
+          &page_text_lines_filtered_and_images,
+          idx,
+          options,
+        )),
+        _ => None,
+      })
+      .collect();
 
-          let file_path = images_folder_path.join(&filename);
-          let mut file_size_bytes: u32 = 0;
-          if let Ok(x) = std::fs::metadata(file_path) {
-            file_size_bytes = x.len() as u32;
-          };
-
-          let meta = ExtractedImageMeta {
-            filename: filename.clone(),
-            related_text,
-            file_size_bytes,
-          };
-          page_images.push(meta);
-        }
-        _ => {}
+    page_text_lines_and_images
+      .into_iter()
+      .filter_map(|item| match item {
+        TextLineOrImage::Image(meta, _) => Some(meta),
+        _ => None,
+      })
+      .zip(page_images_related_text)
+      .for_each(|(mut meta, related_text)| {
+        meta.related_text = related_text;
+        page_images.push(meta);
+      });
+
+    images_extracted += page_images.len() as u32;
+
+    // map page paths into single SVG
+    let page_vector_graphics = if export_vector_graphics {
+      let mut page_vector_graphics: Vec<ExtractedImageMeta> = vec![];
+      if let Some((svg, bounds)) = svg::page_paths_to_svg(&page) {
+        let filename = format!("vector-{}.svg", file_indices.vector_graphics);
+        file_indices.vector_graphics += 1;
+
+        let file_size_bytes = svg.len() as u32;
+        let data = if images_as_buffers {
+          Some(Buffer::from(svg.into_bytes()))
+        } else {
+          std::fs::write(images_folder_path.join(&filename), svg).map_err(|err| {
+            PdfError::new(
+              ErrorCode::ImageSaveFailed,
+              format!("Failed to save image {}", filename),
+            )
+            .on_page(page_index)
+            .caused_by(err)
+          })?;
+          None
+        };
+
+        let (left, top, right, bottom) = image_rect(Some(bounds), page_rotation);
+        page_vector_graphics.push(ExtractedImageMeta {
+          filename,
+          file_size_bytes,
+          related_text: vec![],
+          data,
+          duplicate_of: None,
+          page_index: page_index as u32,
+          left,
+          top,
+          right,
+          bottom,
+          width: None,
+          height: None,
+          dpi: None,
+        });
+      }
+      Some(page_vector_graphics)
+    } else {
+      None
+    };
+
+    if let Some(max_total_image_bytes) = options.max_total_image_bytes {
+      images_bytes += page_images
+        .iter()
+        .chain(page_vector_graphics.iter().flatten())
+        .filter(|image| image.duplicate_of.is_none())
+        .map(|image| image.file_size_bytes as u64)
+        .sum::<u64>();
+      if images_bytes > max_total_image_bytes as u64 {
+        return Err(limit_exceeded(format!(
+          "extracted images take {} bytes, more than {}",
+          images_bytes, max_total_image_bytes
+        )));
+      }
+    }
+
+    let is_scanned = ocr::is_scanned_page(&page);
+    let mut page_text_line_confidences = None;
+
+    // scanned pages without text objects are recognized from rendered page
+    if ocr && page_text_lines.is_empty() {
+      let ocr_start = Instant::now();
+      let mut lines = ocr::recognize_page(&page, options)?;
+      page_ocr = ocr_start.elapsed();
+      for line in lines.iter_mut() {
+        line.text = text::normalize_text(std::mem::take(&mut line.text), options);
+      }
+      page_text_lines = lines.iter().map(|line| line.text.clone()).collect();
+      page_text_line_confidences = Some(lines.iter().map(|line| line.confidence).collect());
+      page_text_line_bounds = include_text_line_bounds.then(|| {
+        lines
+          .iter()
+          .map(|line| {
+            let (left, top, right, bottom) = line.bounds;
+            ExtractedTextLine {
+              text: line.text.clone(),
+              page_index: page_index as u32,
+              left,
+              top,
+              right,
+              bottom,
+              font_name: String::new(),
+              font_size: top - bottom,
+              font_weight: 400,
+              is_italic: false,
+              fill_color: "#000000".to_owned(),
+              kind: None,
+              confidence: Some(line.confidence),
+            }
+          })
+          .collect()
+      });
+      page_paragraphs = detect_paragraphs.then(|| {
+        lines
+          .chunk_by(|a, b| a.paragraph == b.paragraph)
+          .map(|paragraph| paragraph.iter().map(|line| line.text.as_str()).join(" "))
+          .collect()
       });
+    }
+
+    let language = if detect_language {
+      text::detect_language(&page_text_lines.join("\n"))
+    } else {
+      None
+    };
 
     let page_result = ExtractedPage {
+      page_label: page.label().map(str::to_owned),
       page_images,
       page_text_lines,
+      page_vector_graphics,
+      page_text_line_bounds,
+      page_paragraphs,
+      page_text_line_confidences,
+      is_scanned,
+      language,
+      warnings: skip_bad_pages.then(Vec::new),
+      timings: include_timings.then(|| {
+        let page = page_start.elapsed();
+        PageTimings {
+          document_load_ms: document_load.take().map(millis),
+          page_ms: millis(page),
+          text_ms: millis(page.saturating_sub(image_decode + image_encode + page_ocr)),
+          image_decode_ms: millis(image_decode),
+          image_encode_ms: millis(image_encode),
+          ocr_ms: millis(page_ocr),
+        }
+      }),
     };
 
     result.push(page_result);
+
+    report_progress(options, page_index as u32, total_pages, images_extracted);
+  }
+
+  if detect_headings {
+    layout::classify_headings(&mut result);
   }
 
   Ok(result)
@@ -332,68 +1055,581 @@ pub async fn extract_text(
   pdfium_dir: String,
   pdf_path: String,
 ) -> napi::Result<Vec<String>> {
-  let pdfium = PDFIUM.get_or_try_init(|| init_pdfium(pdfium_dir))?;
+  run_blocking(move || {
+    extract_text_from_document(&pdfium_dir, &pdf_path, &ExtractOptions::default())
+  })
+  .await
+}
 
-  // Pdfium will only load the portions of the document it actually needs into memory. This is more efficient than loading the entire document into memory, especially when working with large documents, and allows for working with documents larger than the amount of available memory.
-  let reader =
-    File::open(pdf_path).map_err(|_| napi::Error::from_reason("Failed to open pdf document"))?;
-  let document: PdfDocument<'_> = pdfium
-    .load_pdf_from_reader(reader, None)
-    .map_err(|_| napi::Error::from_reason("Failed to read pdf document"))?;
+#[napi(catch_unwind)]
+/// Extract text from pdf files in lines, configured with options
+pub async fn extract_text_with_options(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+  options: Option<ExtractOptions>,
+) -> napi::Result<Vec<String>> {
+  run_blocking(move || {
+    let options = options.unwrap_or_default();
+    extract_text_from_document(&pdfium_dir, &pdf_path, &options)
+  })
+  .await
+}
 
-  let mut result: Vec<String> = vec![];
+fn extract_text_from_document(
+  pdfium_dir: &str,
+  pdf_path: &str,
+  options: &ExtractOptions,
+) -> napi::Result<Vec<String>> {
+  let pdfium = &get_pdfium(pdfium_dir)?;
 
-  for page in document.pages().iter() {
-    let text_page: PdfPageText<'_> = page
-      .text()
-      .map_err(|_| napi::Error::from_reason("Failed to read pdf document page"))?;
+  let document = load_document(pdfium, pdf_path)?;
+  check_page_limit(&document, options)?;
 
-    let texts = page
-      .objects()
-      .iter()
-      .filter_map(|o| {
-        o.as_text_object()
-          .map(|text| text_page.for_object(text).trim().to_string())
-      })
-      .collect::<Vec<String>>();
+  let mut result: Vec<String> = vec![];
+  let total_pages = document.pages().len() as u32;
 
-    let combined_text = texts.join("");
+  for (page_index, page) in document.pages().iter().enumerate() {
+    check_aborted(options)?;
 
-    if !combined_text.trim().is_empty() {
-      result.push(combined_text);
+    let text = page_text(&page, page_index, options)?;
+    if !text.trim().is_empty() {
+      result.push(text);
     }
+
+    report_progress(options, page_index as u32, total_pages, 0);
   }
 
   Ok(result)
 }
 
-fn init_pdfium(pdfium_dir: String) -> napi::Result<Pdfium> {
-  let pdfium_dir = PathBuf::from(pdfium_dir);
-  let pdfium_platform_library_folder = if env::consts::OS == "macos" {
-    if env::consts::ARCH == "aarch64" {
-      "pdfium-mac-arm64/lib"
-    } else {
-      "pdfium-mac-x64/lib"
+/// Page text normalized as configured, placed by its position on the page when layout is
+/// preserved
+fn page_text(page: &PdfPage, page_index: usize, options: &ExtractOptions) -> napi::Result<String> {
+  if options.preserve_layout.unwrap_or(false) {
+    let text = export::layout_text(page, page_index as u32, options)?;
+    return Ok(text::normalize_text(text, options));
+  }
+
+  let text_page: PdfPageText<'_> = page
+    .text()
+    .map_err(|err| PdfError::page(page_index, "Failed to read pdf document page").caused_by(err))?;
+
+  let mut objects = page.objects().iter().collect::<Vec<_>>();
+  layout::remove_overlapping_duplicates(&mut objects, &text_page);
+  let texts = objects
+    .iter()
+    .filter_map(|o| {
+      o.as_text_object()
+        .map(|text| text_page.for_object(text).trim().to_string())
+    })
+    .collect::<Vec<String>>();
+
+  Ok(text::normalize_text(texts.join(""), options))
+}
+
+/// Page object with images drawn in it when it is form XObject, inline or not, which pdfium
+/// keeps out of page objects
+fn with_form_images(object: PdfPageObject) -> Vec<PdfPageObject> {
+  if let PdfPageObject::XObjectForm(form) = &object {
+    return (0..form.len())
+      .filter_map(|idx| form.get(idx).ok())
+      .flat_map(with_form_images)
+      .filter(|o| o.object_type() == PdfPageObjectType::Image)
+      .collect();
+  }
+  vec![object]
+}
+
+/// Bounds of page images in page space, including images of form XObjects
+fn page_images_bounds(mut page: PdfPage) -> Vec<Bounds> {
+  // moving images must not regenerate page content
+  page.set_content_regeneration_strategy(PdfPageContentRegenerationStrategy::Manual);
+  transform_form_images(&page, true);
+  let bounds = page
+    .objects()
+    .iter()
+    .flat_map(with_form_images)
+    .filter(|o| o.object_type() == PdfPageObjectType::Image)
+    .filter_map(|o| layout::object_bounds(&o))
+    .collect();
+  transform_form_images(&page, false);
+  bounds
+}
+
+/// Move images drawn in form XObjects from form space into page space, or back
+fn transform_form_images(page: &PdfPage, into_page_space: bool) {
+  for object in page.objects().iter() {
+    if let (Some(form), Ok(matrix)) = (object.as_x_object_form_object(), object.matrix()) {
+      transform_images_of_form(form, matrix, into_page_space);
     }
-  } else {
-    if env::consts::ARCH == "aarch64" {
-      "pdfium-linux-arm64/lib"
+  }
+}
+
+fn transform_images_of_form(
+  form: &PdfPageXObjectFormObject,
+  matrix: PdfMatrix,
+  into_page_space: bool,
+) {
+  for mut object in (0..form.len()).filter_map(|idx| form.get(idx).ok()) {
+    if let (Some(nested), Ok(nested_matrix)) = (object.as_x_object_form_object(), object.matrix()) {
+      transform_images_of_form(nested, nested_matrix.multiply(matrix), into_page_space);
+    } else if object.object_type() == PdfPageObjectType::Image {
+      let transform = if into_page_space {
+        matrix
+      } else {
+        matrix.invert()
+      };
+      if let Ok(image_matrix) = object.matrix() {
+        let _ = object.reset_matrix(image_matrix.multiply(transform));
+      }
+    }
+  }
+}
+
+/// Image left, top, right and bottom in page space, displayed page space on normalized rotated
+/// pages
+fn image_rect(
+  bounds: Option<Bounds>,
+  page_rotation: Option<layout::PageRotation>,
+) -> (f64, f64, f64, f64) {
+  let (left, bottom, right, top) = bounds.unwrap_or_default();
+  let bounds = (left as f64, top as f64, right as f64, bottom as f64);
+  page_rotation.map_or(bounds, |r| r.apply(bounds))
+}
+
+/// Sort page objects in lines and vertical text in columns from right to left placed at their
+/// top, returns vertical text objects by index with whether they continue the column of the
+/// previous object
+fn sort_page_objects(
+  objects: &mut Vec<PdfPageObject>,
+  text_page: &PdfPageText,
+  same_line_tolerance: Option<f32>,
+) -> HashMap<usize, bool> {
+  let is_vertical = layout::vertical_text_objects(objects, text_page);
+  let (vertical, horizontal): (Vec<_>, Vec<_>) = std::mem::take(objects)
+    .into_iter()
+    .zip(is_vertical)
+    .partition(|(_, is_vertical)| *is_vertical);
+
+  let mut horizontal: Vec<PdfPageObject> = horizontal.into_iter().map(|(o, _)| o).collect();
+  sort_lines(&mut horizontal, text_page, same_line_tolerance);
+  let columns = layout::vertical_columns(vertical.into_iter().map(|(o, _)| o).collect());
+
+  // vertical text goes before the first line below its top
+  let columns_top = columns
+    .iter()
+    .filter_map(|column| column.first().and_then(layout::object_bounds))
+    .map(|(_, _, _, top)| top)
+    .fold(f32::NEG_INFINITY, f32::max);
+  let position = horizontal
+    .iter()
+    .position(|o| layout::object_bounds(o).is_some_and(|(_, _, _, top)| top < columns_top))
+    .unwrap_or(horizontal.len());
+  let below = horizontal.split_off(position);
+
+  *objects = horizontal;
+  let mut vertical_lines: HashMap<usize, bool> = HashMap::new();
+  for column in columns {
+    for (idx, object) in column.into_iter().enumerate() {
+      vertical_lines.insert(objects.len(), idx > 0);
+      objects.push(object);
+    }
+  }
+  objects.extend(below);
+
+  vertical_lines
+}
+
+/// Sort page objects from top to bottom and objects on the same line from left to right, or
+/// from right to left on right to left text lines
+fn sort_lines(
+  texts_and_images: &mut [PdfPageObject],
+  text_page: &PdfPageText,
+  same_line_tolerance: Option<f32>,
+) {
+  // Sort from top to bottom
+  texts_and_images.sort_by(|a, b| {
+    let a_bounds = a.bounds();
+    let b_bounds = b.bounds();
+
+    if a_bounds.is_err() || b_bounds.is_err() {
+      return std::cmp::Ordering::Equal;
+    }
+
+    let a_bounds = a_bounds.unwrap();
+    let b_bounds = b_bounds.unwrap();
+
+    b_bounds.top().cmp(&a_bounds.top())
+  });
+
+  // Sort items on the same line or close from left to right
+  let mut groups: Vec<(f32, usize, usize)> = vec![]; // same line items line top/start/end
+
+  // Combine items on the same line in groups
+  for i in 0..texts_and_images.len() {
+    let current = texts_and_images.get(i);
+    if current.is_none() {
+      break;
+    }
+
+    let current_bounds = current.unwrap().bounds();
+    if current_bounds.is_err() {
+      break;
+    }
+    let current_bounds = current_bounds.unwrap();
+
+    if groups.is_empty() {
+      let current_group: (f32, usize, usize) = (current_bounds.top().value, i, i);
+      groups.push(current_group);
+      continue;
+    }
+
+    let last_group = groups.last().unwrap();
+    let is_same_line = current_bounds.top().value == last_group.0
+      || (current_bounds.top().value - last_group.0).abs()
+        < same_line_range(current.unwrap(), same_line_tolerance);
+
+    if is_same_line {
+      let updated_last_group = (last_group.0, last_group.1, i);
+      groups.pop();
+      groups.push(updated_last_group);
+      continue;
     } else {
-      "pdfium-linux-x64/lib"
+      let current_group: (f32, usize, usize) = (current_bounds.top().value, i, i);
+      groups.push(current_group);
+      continue;
+    }
+  }
+
+  // Sort groups from left to right
+  for item in groups {
+    texts_and_images[item.1..item.2 + 1].sort_by(|a, b| {
+      let a_bounds = a.bounds();
+      let b_bounds = b.bounds();
+
+      if a_bounds.is_err() || b_bounds.is_err() {
+        return std::cmp::Ordering::Equal;
+      }
+
+      let a_bounds = a_bounds.unwrap();
+      let b_bounds = b_bounds.unwrap();
+
+      a_bounds.left().cmp(&b_bounds.left())
+    });
+    layout::bidi_logical_order(&mut texts_and_images[item.1..item.2 + 1], text_page);
+  }
+}
+
+/// Error of exceeded document size limit
+fn limit_exceeded(reason: String) -> napi::Error {
+  PdfError::new(ErrorCode::LimitExceeded, reason).into()
+}
+
+fn check_page_limit(document: &PdfDocument, options: &ExtractOptions) -> napi::Result<()> {
+  let pages_count = document.pages().len() as u32;
+  match options.max_pages {
+    Some(max_pages) if pages_count > max_pages => Err(limit_exceeded(format!(
+      "document has {} pages, more than {}",
+      pages_count, max_pages
+    ))),
+    _ => Ok(()),
+  }
+}
+
+/// Empty placeholder of page which failed to be read when bad pages are skipped, keeping indices
+/// of the following pages
+fn skipped_page(err: PdfError, options: &ExtractOptions) -> napi::Result<ExtractedPage> {
+  if !options.skip_bad_pages.unwrap_or(false) {
+    return Err(err.into());
+  }
+  log(LogLevel::Warn, err.to_string());
+  let include_text_line_bounds =
+    options.include_text_line_bounds.unwrap_or(false) || options.detect_headings.unwrap_or(false);
+  Ok(ExtractedPage {
+    page_label: None,
+    page_images: vec![],
+    page_text_lines: vec![],
+    page_vector_graphics: options
+      .export_vector_graphics
+      .unwrap_or(false)
+      .then(Vec::new),
+    page_text_line_bounds: include_text_line_bounds.then(Vec::new),
+    page_paragraphs: options.detect_paragraphs.unwrap_or(false).then(Vec::new),
+    page_text_line_confidences: None,
+    is_scanned: false,
+    language: None,
+    warnings: Some(vec![err.to_string()]),
+    timings: None,
+  })
+}
+
+fn millis(duration: Duration) -> f64 {
+  duration.as_secs_f64() * 1000.0
+}
+
+fn check_aborted(options: &ExtractOptions) -> napi::Result<()> {
+  match &options.signal {
+    Some(signal) => signal.check(),
+    None => Ok(()),
+  }
+}
+
+fn report_progress(
+  options: &ExtractOptions,
+  page_index: u32,
+  total_pages: u32,
+  images_extracted: u32,
+) {
+  if let Some(on_progress) = &options.on_progress {
+    on_progress.call(
+      ExtractProgress {
+        page_index,
+        total_pages,
+        images_extracted,
+      },
+      ThreadsafeFunctionCallMode::NonBlocking,
+    );
+  }
+}
+
+#[napi(catch_unwind)]
+/// Bind pdfium library from folder or library file used by all functions, replacing library
+/// bound from a different path before once running functions finish. Functions bind library from
+/// their pdfium folder when it is not initialized
+pub async fn init_pdfium(pdfium_dir: String) -> napi::Result<()> {
+  run_blocking(move || {
+    let _access = PDFIUM_ACCESS.lock().unwrap_or_else(|err| err.into_inner());
+    let mut pdfium = PDFIUM.lock().unwrap_or_else(|err| err.into_inner());
+    if pdfium.as_ref().is_some_and(|(dir, _)| *dir == pdfium_dir) {
+      return Ok(());
+    }
+    // pdfium can't be bound again before replaced library is released by open documents
+    if pdfium
+      .as_ref()
+      .is_some_and(|(_, bound)| Arc::strong_count(bound) > 1)
+    {
+      return Err(
+        PdfError::new(
+          ErrorCode::Unsupported,
+          "Pdfium library can't be replaced while documents are open",
+        )
+        .into(),
+      );
+    }
+    drop(pdfium.take());
+    *pdfium = Some((pdfium_dir.clone(), Arc::new(bind_pdfium(pdfium_dir)?)));
+    Ok(())
+  })
+  .await
+}
+
+/// Run function using pdfium on a blocking thread, runtime threads only resolve promises
+async fn run_blocking<T: Send + 'static>(
+  work: impl FnOnce() -> napi::Result<T> + Send + 'static,
+) -> napi::Result<T> {
+  napi::bindgen_prelude::spawn_blocking(work)
+    .await
+    .map_err(|err| {
+      napi::Error::from(
+        PdfError::new(ErrorCode::TaskFailed, "Failed to run pdfium task").caused_by(err),
+      )
+    })?
+}
+
+/// Pdfium library used exclusively by one function until dropped
+struct PdfiumAccess {
+  pdfium: Arc<Pdfium>,
+  _lock: MutexGuard<'static, ()>,
+}
+
+impl Deref for PdfiumAccess {
+  type Target = Pdfium;
+
+  fn deref(&self) -> &Pdfium {
+    &self.pdfium
+  }
+}
+
+/// Wait for other functions to finish using pdfium and take it, bound from pdfium folder when
+/// it is not initialized
+fn get_pdfium(pdfium_dir: &str) -> napi::Result<PdfiumAccess> {
+  let lock = PDFIUM_ACCESS.lock().unwrap_or_else(|err| err.into_inner());
+  let mut pdfium = PDFIUM.lock().unwrap_or_else(|err| err.into_inner());
+  let bound = match pdfium.as_ref() {
+    Some((_, bound)) => bound.clone(),
+    None => {
+      let bound = Arc::new(bind_pdfium(pdfium_dir.to_owned())?);
+      *pdfium = Some((pdfium_dir.to_owned(), bound.clone()));
+      bound
     }
   };
-  let pdfium_platform_library_path = pdfium_dir.join(pdfium_platform_library_folder);
-  let binary_path = Pdfium::pdfium_platform_library_name_at_path(&pdfium_platform_library_path);
-  let bindings = Pdfium::bind_to_library(binary_path.clone()).map_err(|err| {
-    eprintln!("{}", err);
-    napi::Error::from_reason(format!(
-      "Failed to bind to external Pdfium library bindings. ARCH: {}, OS: {}, binary_path: {:?}, path exists: {}",
-      env::consts::ARCH,
-      env::consts::OS,
-      binary_path.clone(),
-      binary_path.exists(),
-    ))
+  Ok(PdfiumAccess {
+    pdfium: bound,
+    _lock: lock,
+  })
+}
+
+fn load_document<'a>(pdfium: &'a Pdfium, pdf_path: &str) -> napi::Result<PdfDocument<'a>> {
+  // Pdfium will only load the portions of the document it actually needs into memory. This is more efficient than loading the entire document into memory, especially when working with large documents, and allows for working with documents larger than the amount of available memory.
+  let reader = File::open(pdf_path).map_err(|err| {
+    PdfError::new(ErrorCode::LoadFailed, "Failed to open pdf document").caused_by(err)
   })?;
+  let document = pdfium
+    .load_pdf_from_reader(reader, None)
+    .map_err(PdfError::load)?;
+  Ok(document)
+}
+
+/// Save document into output path, which may be the document source itself
+fn save_document(document: PdfDocument, output_path: &str) -> napi::Result<()> {
+  // document keeps its source file open, save into memory and close it before writing
+  let data = document.save_to_bytes().map_err(|err| {
+    PdfError::new(ErrorCode::SaveFailed, "Failed to save pdf document").caused_by(err)
+  })?;
+  drop(document);
+  std::fs::write(output_path, data).map_err(|err| {
+    PdfError::new(ErrorCode::SaveFailed, "Failed to write pdf document").caused_by(err)
+  })?;
+  Ok(())
+}
+
+/// Document opened through pdfium bindings for data pdfium-render does not expose, loaded once
+/// on first use and closed when dropped
+struct RawDocument<'a> {
+  bindings: &'a dyn PdfiumLibraryBindings,
+  pdf_path: String,
+  handle: OnceCell<*mut c_void>,
+}
+
+// SAFETY: pdfium is used by one function at a time holding its access lock
+unsafe impl Send for RawDocument<'_> {}
+
+impl<'a> RawDocument<'a> {
+  fn new(bindings: &'a dyn PdfiumLibraryBindings, pdf_path: &str) -> Self {
+    RawDocument {
+      bindings,
+      pdf_path: pdf_path.to_owned(),
+      handle: OnceCell::new(),
+    }
+  }
+
+  fn bindings(&self) -> &'a dyn PdfiumLibraryBindings {
+    self.bindings
+  }
+
+  fn pdf_path(&self) -> &str {
+    &self.pdf_path
+  }
+
+  /// Document handle cast into pdfium handle type, none when the document fails to load
+  fn handle<T>(&self) -> Option<*mut T> {
+    let handle = *self
+      .handle
+      .get_or_init(|| self.bindings.FPDF_LoadDocument(&self.pdf_path, None) as *mut c_void);
+    (!handle.is_null()).then_some(handle as *mut T)
+  }
+
+  /// Document handle like `handle`, failing with the reason pdfium reports when the document
+  /// fails to load
+  fn loaded_handle<T>(&self) -> napi::Result<*mut T> {
+    self.handle().ok_or_else(|| {
+      // pdfium FPDF_ERR_FILE, FPDF_ERR_FORMAT, FPDF_ERR_PASSWORD and FPDF_ERR_SECURITY codes
+      let error = match self.bindings.FPDF_GetLastError() {
+        2 => PdfiumInternalError::FileError,
+        3 => PdfiumInternalError::FormatError,
+        4 => PdfiumInternalError::PasswordError,
+        5 => PdfiumInternalError::SecurityError,
+        _ => PdfiumInternalError::Unknown,
+      };
+      PdfError::load(PdfiumError::PdfiumLibraryInternalError(error)).into()
+    })
+  }
+}
+
+impl Drop for RawDocument<'_> {
+  fn drop(&mut self) {
+    if let Some(handle) = self.handle.get().filter(|handle| !handle.is_null()) {
+      self.bindings.FPDF_CloseDocument(*handle as *mut _);
+    }
+  }
+}
+
+/// Next indices of files extracted across pages, kept between calls extracting pages of one
+/// document one at a time
+struct FileIndices {
+  image: u32,
+  vector_graphics: u32,
+}
+
+impl Default for FileIndices {
+  fn default() -> Self {
+    FileIndices {
+      image: 1,
+      vector_graphics: 1,
+    }
+  }
+}
+
+/// Bind to pdfium linked into the addon, pdfium folder is not used
+#[cfg(feature = "static")]
+fn bind_pdfium(_pdfium_dir: String) -> napi::Result<Pdfium> {
+  let bindings = Pdfium::bind_to_statically_linked_library().map_err(|err| {
+    napi::Error::from(
+      PdfError::new(
+        ErrorCode::BindFailed,
+        "Failed to bind to statically linked Pdfium library",
+      )
+      .caused_by(err),
+    )
+  })?;
+  Ok(Pdfium::new(bindings))
+}
+
+#[cfg(not(feature = "static"))]
+fn bind_pdfium(pdfium_dir: String) -> napi::Result<Pdfium> {
+  use std::env;
+  use std::path::PathBuf;
+
+  let pdfium_dir = PathBuf::from(pdfium_dir);
+  let pdfium_platform_library_folder = match (env::consts::OS, env::consts::ARCH) {
+    ("macos", "aarch64") => "pdfium-mac-arm64/lib",
+    ("macos", _) => "pdfium-mac-x64/lib",
+    // Windows builds keep pdfium.dll in bin, lib has only its import library
+    ("windows", "aarch64") => "pdfium-win-arm64/bin",
+    ("windows", _) => "pdfium-win-x64/bin",
+    (_, "aarch64") => "pdfium-linux-arm64/lib",
+    _ => "pdfium-linux-x64/lib",
+  };
+  // pdfium library file can be given directly instead of folder with platform folders
+  let binary_path = if pdfium_dir.is_file() {
+    pdfium_dir
+  } else {
+    let pdfium_platform_library_path = pdfium_dir.join(pdfium_platform_library_folder);
+    Pdfium::pdfium_platform_library_name_at_path(&pdfium_platform_library_path)
+  };
+  // library installed by system package manager is used when pdfium folder has none
+  let bindings = Pdfium::bind_to_library(binary_path.clone())
+    .or_else(|err| {
+      log(
+        LogLevel::Info,
+        format!("Failed to bind to Pdfium library at {:?}, binding to system library", binary_path),
+      );
+      Pdfium::bind_to_system_library().map_err(|_| err)
+    })
+    .map_err(|err| {
+      log(LogLevel::Error, format!("Failed to bind to Pdfium library - {:?}", err));
+      napi::Error::from(PdfError::new(
+        ErrorCode::BindFailed,
+        format!(
+          "Failed to bind to external Pdfium library bindings. ARCH: {}, OS: {}, binary_path: {:?}, path exists: {}",
+          env::consts::ARCH,
+          env::consts::OS,
+          binary_path.clone(),
+          binary_path.exists(),
+        ),
+      ))
+    })?;
   // Bind library to pdfium binary
   let pdfium: Pdfium = Pdfium::new(bindings);
   Ok(pdfium)
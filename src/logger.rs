@@ -0,0 +1,68 @@
+use napi::threadsafe_function::{ErrorStrategy, ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi::Env;
+use std::sync::Mutex;
+
+#[napi(string_enum = "lowercase")]
+#[derive(PartialEq, PartialOrd)]
+/// Diagnostic message level, from most to least severe
+pub enum LogLevel {
+  Error,
+  Warn,
+  Info,
+  Debug,
+}
+
+#[napi(object)]
+/// Diagnostic message passed to logging callback
+pub struct LogMessage {
+  pub level: LogLevel,
+  pub message: String,
+}
+
+// logging callback and least severe level passed to it
+static LOGGER: Mutex<
+  Option<(
+    ThreadsafeFunction<LogMessage, ErrorStrategy::Fatal>,
+    LogLevel,
+  )>,
+> = Mutex::new(None);
+
+#[napi]
+/// Pass diagnostics up to level, `warn` by default, to callback instead of printing errors and
+/// warnings to stderr, which is done again when callback is not set
+pub fn set_logger(
+  env: Env,
+  callback: Option<ThreadsafeFunction<LogMessage, ErrorStrategy::Fatal>>,
+  level: Option<LogLevel>,
+) -> napi::Result<()> {
+  let logger = match callback {
+    Some(mut callback) => {
+      // logger must not keep node process running
+      callback.unref(&env)?;
+      Some((callback, level.unwrap_or(LogLevel::Warn)))
+    }
+    None => None,
+  };
+  *LOGGER.lock().unwrap_or_else(|err| err.into_inner()) = logger;
+  Ok(())
+}
+
+/// Pass message to logging callback, errors and warnings are printed to stderr without it
+pub fn log(level: LogLevel, message: String) {
+  let logger = LOGGER.lock().unwrap_or_else(|err| err.into_inner());
+  match logger.as_ref() {
+    Some((callback, max_level)) => {
+      if level <= *max_level {
+        callback.call(
+          LogMessage { level, message },
+          ThreadsafeFunctionCallMode::NonBlocking,
+        );
+      }
+    }
+    None => {
+      if level <= LogLevel::Warn {
+        eprintln!("{}", message);
+      }
+    }
+  }
+}
@@ -0,0 +1,459 @@
+use flate2::read::ZlibDecoder;
+use pdfium_render::prelude::*;
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::os::raw::{c_int, c_ulong, c_void};
+
+use crate::RawDocument;
+
+// pdfium save flag writing document without its encryption
+static SAVE_REMOVE_SECURITY: c_ulong = 3;
+
+/// Objects of document data located through its cross reference tables and streams, objects
+/// compressed in object streams are not located
+pub struct PdfObjects<'a> {
+  data: &'a [u8],
+  /// Offsets of objects by number, from the latest section defining them
+  offsets: BTreeMap<u32, usize>,
+  /// Trailer or cross reference stream dictionary of the last section
+  trailer: String,
+}
+
+impl<'a> PdfObjects<'a> {
+  /// Read cross reference sections from the last one through their previous sections, none when
+  /// the last section cannot be read
+  pub fn parse(data: &'a [u8]) -> Option<Self> {
+    let startxref = find_last(data, b"startxref")?;
+    let mut next = String::from_utf8_lossy(&data[startxref + b"startxref".len()..])
+      .split_whitespace()
+      .next()?
+      .parse::<usize>()
+      .ok();
+
+    let mut objects = PdfObjects {
+      data,
+      offsets: BTreeMap::new(),
+      trailer: String::new(),
+    };
+    let mut visited = vec![];
+    while let Some(offset) = next.filter(|offset| *offset < data.len()) {
+      // malformed documents may point back to sections already read
+      if visited.contains(&offset) {
+        break;
+      }
+      visited.push(offset);
+
+      let trailer = match data[offset..].starts_with(b"xref") {
+        true => objects.read_table(offset)?,
+        false => objects.read_stream(offset)?,
+      };
+      next = number_value(&trailer, "/Prev").map(|prev| prev as usize);
+      if objects.trailer.is_empty() {
+        objects.trailer = trailer;
+      }
+    }
+    (!objects.trailer.is_empty()).then_some(objects)
+  }
+
+  /// Entries of cross reference table, keeping offsets of later sections
+  fn read_table(&mut self, offset: usize) -> Option<String> {
+    let trailer_start = find(&self.data[offset..], b"trailer")? + offset;
+    let table = String::from_utf8_lossy(&self.data[offset + b"xref".len()..trailer_start]);
+    let mut lines = table.lines().map(str::trim).filter(|line| !line.is_empty());
+    while let Some(subsection) = lines.next() {
+      let mut header = subsection.split_whitespace();
+      let first = header.next()?.parse::<u32>().ok()?;
+      let count = header.next()?.parse::<u32>().ok()?;
+      // object numbers past the largest number make the section unreadable
+      for number in first..first.checked_add(count)? {
+        let mut entry = lines.next()?.split_whitespace();
+        let entry_offset = entry.next()?.parse::<usize>().ok()?;
+        if entry.nth(1) == Some("n") {
+          self.offsets.entry(number).or_insert(entry_offset);
+        }
+      }
+    }
+
+    let trailer = &self.data[trailer_start..];
+    let end = find(trailer, b"startxref").unwrap_or(trailer.len());
+    Some(String::from_utf8_lossy(&trailer[..end]).into_owned())
+  }
+
+  /// Entries of cross reference stream with offsets of uncompressed objects
+  fn read_stream(&mut self, offset: usize) -> Option<String> {
+    let dictionary = self.dictionary_at(offset)?;
+    let widths: Vec<usize> = array_value(&dictionary, "/W")?
+      .split_whitespace()
+      .map(|width| width.parse::<usize>().ok())
+      .collect::<Option<_>>()?;
+    let [type_width, offset_width, _] = widths[..] else {
+      return None;
+    };
+    let size = number_value(&dictionary, "/Size")?;
+    let index: Vec<u32> = array_value(&dictionary, "/Index")
+      .map_or(format!("0 {}", size), str::to_owned)
+      .split_whitespace()
+      .map(|value| value.parse::<u32>().ok())
+      .collect::<Option<_>>()?;
+
+    let data = decode(&dictionary, self.stream_at(offset, &dictionary)?)?;
+    let mut entries = data.chunks_exact(widths.iter().sum::<usize>().max(1));
+    for subsection in index.chunks_exact(2) {
+      for number in subsection[0]..subsection[0].checked_add(subsection[1])? {
+        let entry = entries.next()?;
+        // entry type defaults to uncompressed object when its field is omitted
+        let entry_type = match type_width {
+          0 => 1,
+          _ => field(&entry[..type_width]),
+        };
+        if entry_type == 1 {
+          let entry_offset = field(&entry[type_width..type_width + offset_width]);
+          self.offsets.entry(number).or_insert(entry_offset);
+        }
+      }
+    }
+    Some(dictionary)
+  }
+
+  /// Numbers of located objects in ascending order
+  pub fn numbers(&self) -> impl Iterator<Item = u32> + '_ {
+    self.offsets.keys().copied()
+  }
+
+  /// Generation number written in the object header
+  pub fn generation(&self, number: u32) -> Option<u32> {
+    let offset = *self.offsets.get(&number)?;
+    let header = &self.data[offset..];
+    let header_end = find(header, b"obj")?;
+    String::from_utf8_lossy(&header[..header_end])
+      .split_whitespace()
+      .nth(1)?
+      .parse::<u32>()
+      .ok()
+  }
+
+  /// Dictionary of the object or of its stream, or other value of the object
+  pub fn dictionary(&self, number: u32) -> Option<String> {
+    self.dictionary_at(*self.offsets.get(&number)?)
+  }
+
+  /// Undecoded stream data of the object
+  pub fn stream(&self, number: u32) -> Option<&'a [u8]> {
+    let offset = *self.offsets.get(&number)?;
+    self.stream_at(offset, &self.dictionary_at(offset)?)
+  }
+
+  /// Stream data of the object decoded with its filter, none for filters other than Flate
+  pub fn decoded_stream(&self, number: u32) -> Option<Vec<u8>> {
+    let offset = *self.offsets.get(&number)?;
+    let dictionary = self.dictionary_at(offset)?;
+    decode(&dictionary, self.stream_at(offset, &dictionary)?)
+  }
+
+  /// Dictionary of the document catalog
+  pub fn catalog(&self) -> Option<String> {
+    self.dictionary(reference_value(&self.trailer, "/Root")?)
+  }
+
+  /// Dictionary value of the key, written inline or as reference to its object
+  pub fn dictionary_value(&self, dictionary: &str, key: &str) -> Option<String> {
+    if let Some(number) = reference_value(dictionary, key) {
+      return self.dictionary(number);
+    }
+    values(dictionary, key).find_map(|rest| {
+      let rest = rest.trim_start();
+      if !rest.starts_with("<<") {
+        return None;
+      }
+      Some(rest[..value_length(rest)?].to_owned())
+    })
+  }
+
+  fn dictionary_at(&self, offset: usize) -> Option<String> {
+    let object = self.object_at(offset);
+    let start = find(object, b"obj")? + b"obj".len();
+    let end = find(object, b"stream").unwrap_or(object.len());
+    (start <= end).then(|| {
+      String::from_utf8_lossy(&object[start..end])
+        .trim()
+        .to_owned()
+    })
+  }
+
+  /// Object data up to its end keyword
+  fn object_at(&self, offset: usize) -> &'a [u8] {
+    let object = &self.data[offset..];
+    &object[..find(object, b"endobj").unwrap_or(object.len())]
+  }
+
+  fn stream_at(&self, offset: usize, dictionary: &str) -> Option<&'a [u8]> {
+    let object = self.object_at(offset);
+    let keyword = find(object, b"stream")? + b"stream".len();
+    // stream keyword is followed by CRLF or LF line end
+    let start = offset
+      + match object[keyword..].starts_with(b"\r\n") {
+        true => keyword + 2,
+        false => keyword + 1,
+      };
+    // length may be written as reference to another object
+    let length = match reference_value(dictionary, "/Length") {
+      Some(number) => self.dictionary(number)?.parse::<u32>().ok(),
+      None => number_value(dictionary, "/Length"),
+    }? as usize;
+    self.data.get(start..start + length)
+  }
+}
+
+/// Document data saved through pdfium without encryption, objects of compressed object streams
+/// are written uncompressed with a cross reference table
+pub fn decrypted_data(raw_document: &RawDocument) -> Option<Vec<u8>> {
+  let document = raw_document.handle::<c_void>()?;
+  saved_data(raw_document.bindings(), document, SAVE_REMOVE_SECURITY)
+}
+
+/// Complete document data written by pdfium with save flags
+pub fn saved_data<T>(
+  bindings: &dyn PdfiumLibraryBindings,
+  document: *mut T,
+  flags: c_ulong,
+) -> Option<Vec<u8>> {
+  let mut file_write = FileWrite {
+    version: 1,
+    write_block: Some(write_block),
+    data: vec![],
+  };
+  bindings
+    .is_true(bindings.FPDF_SaveAsCopy(
+      document as *mut _,
+      &mut file_write as *mut FileWrite as *mut _,
+      flags,
+    ))
+    .then_some(file_write.data)
+}
+
+/// Data writer layout of pdfium `FPDF_FILEWRITE` followed by written data
+#[repr(C)]
+struct FileWrite {
+  version: c_int,
+  write_block: Option<unsafe extern "C" fn(*mut FileWrite, *const c_void, c_ulong) -> c_int>,
+  data: Vec<u8>,
+}
+
+unsafe extern "C" fn write_block(
+  file_write: *mut FileWrite,
+  data: *const c_void,
+  size: c_ulong,
+) -> c_int {
+  // SAFETY: pdfium passes back the writer given to it with a block of written data
+  let file_write = &mut *file_write;
+  file_write
+    .data
+    .extend_from_slice(std::slice::from_raw_parts(data as *const u8, size as usize));
+  1
+}
+
+/// Stream data decoded with its filter, none for filters other than Flate
+fn decode(dictionary: &str, data: &[u8]) -> Option<Vec<u8>> {
+  let filters: Vec<&str> = match array_value(dictionary, "/Filter") {
+    Some(filters) => filters.split_whitespace().collect(),
+    None => values(dictionary, "/Filter")
+      .map(|rest| rest.split_whitespace().next().unwrap_or_default())
+      .collect(),
+  };
+  match filters[..] {
+    [] => Some(data.to_vec()),
+    [filter] if filter.starts_with("/FlateDecode") => {
+      let decoded = inflate(data)?;
+      match number_value(dictionary, "/Predictor") {
+        Some(predictor) if predictor > 1 => unpredict(dictionary, predictor, decoded),
+        _ => Some(decoded),
+      }
+    }
+    _ => None,
+  }
+}
+
+/// Data decompressed from zlib format, none when it is not compressed
+pub fn inflate(data: &[u8]) -> Option<Vec<u8>> {
+  let mut decoded = vec![];
+  ZlibDecoder::new(data).read_to_end(&mut decoded).ok()?;
+  Some(decoded)
+}
+
+/// Undo PNG predictors cross reference streams are usually written with
+fn unpredict(dictionary: &str, predictor: u32, data: Vec<u8>) -> Option<Vec<u8>> {
+  if predictor < 10 {
+    return None;
+  }
+  let columns = number_value(dictionary, "/Columns").unwrap_or(1) as usize;
+  let mut result: Vec<u8> = Vec::with_capacity(data.len());
+  let mut previous = vec![0u8; columns];
+  for row in data.chunks(columns + 1) {
+    let (predictor, row) = row.split_first()?;
+    let mut current = row.to_vec();
+    for i in 0..current.len() {
+      let left = if i > 0 { current[i - 1] } else { 0 };
+      let above = previous[i];
+      let above_left = if i > 0 { previous[i - 1] } else { 0 };
+      current[i] = current[i].wrapping_add(match predictor {
+        1 => left,
+        2 => above,
+        3 => ((left as u16 + above as u16) / 2) as u8,
+        4 => paeth(left, above, above_left),
+        _ => 0,
+      });
+    }
+    result.extend_from_slice(&current);
+    previous[..current.len()].copy_from_slice(&current);
+  }
+  Some(result)
+}
+
+fn paeth(left: u8, above: u8, above_left: u8) -> u8 {
+  let estimate = left as i16 + above as i16 - above_left as i16;
+  let distances = [
+    (estimate - left as i16).abs(),
+    (estimate - above as i16).abs(),
+    (estimate - above_left as i16).abs(),
+  ];
+  if distances[0] <= distances[1] && distances[0] <= distances[2] {
+    left
+  } else if distances[1] <= distances[2] {
+    above
+  } else {
+    above_left
+  }
+}
+
+/// Big-endian number of cross reference stream entry field
+fn field(bytes: &[u8]) -> usize {
+  bytes
+    .iter()
+    .fold(0, |value, byte| (value << 8) | *byte as usize)
+}
+
+pub fn find(data: &[u8], needle: &[u8]) -> Option<usize> {
+  data
+    .windows(needle.len())
+    .position(|window| window == needle)
+}
+
+pub fn find_last(data: &[u8], needle: &[u8]) -> Option<usize> {
+  data
+    .windows(needle.len())
+    .rposition(|window| window == needle)
+}
+
+/// Data following each occurrence of the key, skipping other keys sharing its prefix, nested
+/// values may contain the key again
+fn values<'a>(dictionary: &'a str, key: &'a str) -> impl Iterator<Item = &'a str> {
+  dictionary
+    .match_indices(key)
+    .map(move |(index, _)| &dictionary[index + key.len()..])
+    .filter(|rest| !rest.starts_with(|c: char| c.is_ascii_alphanumeric() || c == '#'))
+}
+
+/// Number value of dictionary key, which may be directly followed by the next key
+pub fn number_value(dictionary: &str, key: &str) -> Option<u32> {
+  values(dictionary, key).find_map(|rest| {
+    let rest = rest.trim_start();
+    let end = rest
+      .find(|c: char| !c.is_ascii_digit())
+      .unwrap_or(rest.len());
+    rest[..end].parse::<u32>().ok()
+  })
+}
+
+/// Number of the object referenced by dictionary key
+pub fn reference_value(dictionary: &str, key: &str) -> Option<u32> {
+  values(dictionary, key).find_map(|rest| {
+    let mut tokens = rest.split_whitespace();
+    let number = tokens.next()?.parse::<u32>().ok()?;
+    tokens.next()?.parse::<u32>().ok()?;
+    tokens.next()?.starts_with('R').then_some(number)
+  })
+}
+
+/// Name value of the key, other value types are skipped
+pub fn name_value(dictionary: &str, key: &str) -> Option<String> {
+  values(dictionary, key).find_map(|rest| {
+    let value = rest.trim_start().strip_prefix('/')?;
+    // names end at whitespace or delimiters
+    let end = value
+      .find(|c: char| c.is_ascii_whitespace() || "/<>[]()".contains(c))
+      .unwrap_or(value.len());
+    Some(value[..end].to_owned()).filter(|name| !name.is_empty())
+  })
+}
+
+/// Values of array value of the key, references are split into their number, generation and R
+pub fn array_items<'a>(dictionary: &'a str, key: &'a str) -> Option<Vec<&'a str>> {
+  values(dictionary, key).find_map(|rest| {
+    let mut rest = rest.trim_start().strip_prefix('[')?.trim_start();
+    let mut items = vec![];
+    while !rest.starts_with(']') {
+      let length = value_length(rest)?;
+      items.push(&rest[..length]);
+      rest = rest[length..].trim_start();
+    }
+    Some(items)
+  })
+}
+
+/// Value of the key as written in the dictionary, references are read with `reference_value`
+pub fn written_value<'a>(dictionary: &'a str, key: &'a str) -> Option<&'a str> {
+  values(dictionary, key).find_map(|rest| {
+    let rest = rest.trim_start();
+    Some(&rest[..value_length(rest)?])
+  })
+}
+
+/// Length of the value the data starts with, strings and containers may include delimiters
+fn value_length(value: &str) -> Option<usize> {
+  let bytes = value.as_bytes();
+  match bytes.first()? {
+    b'(' => {
+      let mut depth = 0;
+      let mut i = 0;
+      while i < bytes.len() {
+        match bytes[i] {
+          b'\\' => i += 1,
+          b'(' => depth += 1,
+          b')' if depth == 1 => return Some(i + 1),
+          b')' => depth -= 1,
+          _ => {}
+        }
+        i += 1;
+      }
+      None
+    }
+    b'<' if bytes.get(1) != Some(&b'<') => value.find('>').map(|end| end + 1),
+    b'[' | b'<' => {
+      let close = if bytes[0] == b'[' { "]" } else { ">>" };
+      // opening delimiter is as long as the closing one
+      let mut i = close.len();
+      loop {
+        i += value[i..].len() - value[i..].trim_start().len();
+        if value[i..].starts_with(close) {
+          return Some(i + close.len());
+        }
+        i += value_length(&value[i..])?;
+      }
+    }
+    b']' | b'>' | b')' => None,
+    // names, numbers and keywords end at whitespace or delimiters
+    _ => Some(
+      value[1..]
+        .find(|c: char| c.is_ascii_whitespace() || "/<>[]()".contains(c))
+        .map_or(value.len(), |end| end + 1),
+    ),
+  }
+}
+
+/// Content of array value between its brackets
+fn array_value<'a>(dictionary: &'a str, key: &'a str) -> Option<&'a str> {
+  values(dictionary, key).find_map(|rest| {
+    let value = rest.trim_start().strip_prefix('[')?;
+    value.find(']').map(|end| &value[..end])
+  })
+}
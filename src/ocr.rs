@@ -0,0 +1,386 @@
+use pdfium_render::prelude::*;
+
+use crate::document::rotation_degrees;
+use crate::errors::{ErrorCode, PdfError};
+use crate::layout::PageRotation;
+use crate::options::{ExtractOptions, SearchableOptions};
+use crate::{get_pdfium, load_document, run_blocking, save_document};
+
+/// Text line recognized on rendered page, bounds in page space points as the page is displayed
+pub struct RecognizedLine {
+  pub text: String,
+  /// Tesseract block and paragraph numbers
+  pub paragraph: (u32, u32),
+  /// Left, top, right and bottom bounds
+  pub bounds: (f64, f64, f64, f64),
+  /// Mean recognition confidence of line words from 0 to 100
+  pub confidence: f64,
+}
+
+// page is scanned when images cover at least this part of its area
+static SCANNED_IMAGES_COVERAGE: f32 = 0.8;
+// scans can have few text objects like page numbers or stamps added after scanning
+static SCANNED_MAX_TEXT_OBJECTS: usize = 2;
+
+/// Page mostly covered by images with almost no visible text, invisible text layer of already
+/// recognized scans is not counted
+pub fn is_scanned_page(page: &PdfPage) -> bool {
+  let page_area = page.width().value * page.height().value;
+  if page_area <= 0.0 {
+    return false;
+  }
+
+  let mut text_objects_count = 0;
+  let mut images_area = 0.0;
+  for object in page.objects().iter() {
+    if let Some(text) = object.as_text_object() {
+      if text.render_mode() != PdfPageTextRenderMode::Invisible && !text.text().trim().is_empty() {
+        text_objects_count += 1;
+      }
+    } else if object.object_type() == PdfPageObjectType::Image {
+      if let Ok(bounds) = object.bounds() {
+        images_area += bounds.width().value * bounds.height().value;
+      }
+    }
+  }
+
+  text_objects_count <= SCANNED_MAX_TEXT_OBJECTS
+    && images_area / page_area >= SCANNED_IMAGES_COVERAGE
+}
+
+#[napi(object)]
+/// Page given recognized text layer
+pub struct SearchablePage {
+  pub page_index: u32,
+  /// Number of recognized text lines added to the page
+  pub line_count: u32,
+  /// Mean recognition confidence of page lines from 0 to 100
+  pub confidence: f64,
+}
+
+#[napi(catch_unwind)]
+/// Recognize text of scanned pages and save document with it added as invisible text over page
+/// images, so the text can be searched and selected, requires build with `ocr` feature and
+/// tesseract installed
+pub async fn make_searchable(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+  output_path: String,
+  options: Option<SearchableOptions>,
+) -> napi::Result<Vec<SearchablePage>> {
+  run_blocking(move || {
+    let options = options.unwrap_or_default();
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let mut document = load_document(pdfium, &pdf_path)?;
+    let font = match &options.font_path {
+      Some(font_path) => document
+        .fonts_mut()
+        .load_true_type_from_file(font_path, true)
+        .map_err(|err| {
+          PdfError::new(ErrorCode::LoadFailed, "Failed to load font").caused_by(err)
+        })?,
+      None => document.fonts_mut().helvetica(),
+    };
+    // recognized bounds are as the page is displayed
+    let extract_options = ExtractOptions {
+      ocr_language: options.ocr_language.clone(),
+      normalize_rotation: Some(true),
+      ..Default::default()
+    };
+
+    let total_pages = document.pages().len() as u32;
+    if let Some(page_index) = options
+      .pages
+      .iter()
+      .flatten()
+      .find(|index| **index >= total_pages)
+    {
+      return Err(PdfError::page_out_of_range(*page_index as usize).into());
+    }
+
+    let mut result = vec![];
+    for page_index in 0..total_pages {
+      let mut page = document
+        .pages()
+        .get(page_index as PdfPageIndex)
+        .map_err(|err| {
+          PdfError::page(page_index as usize, "Failed to load pdf page").caused_by(err)
+        })?;
+      let is_selected = match &options.pages {
+        Some(pages) => pages.contains(&page_index),
+        None => is_scanned_page(&page) && !has_invisible_text(&page),
+      };
+      if !is_selected {
+        continue;
+      }
+
+      let lines = recognize_page(&page, &extract_options)?;
+      let rotation = PageRotation::of(&page, &extract_options);
+      let degrees = rotation_degrees(&page);
+      for line in &lines {
+        let (left, top, right, bottom) =
+          rotation.map_or(line.bounds, |rotation| rotation.revert(line.bounds));
+        let (width, height) = ((right - left) as f32, (top - bottom) as f32);
+        if width <= 0.0 || height <= 0.0 {
+          continue;
+        }
+        // standard fonts write text in WinAnsi encoding, other characters would be garbled
+        if options.font_path.is_none() && !line.text.chars().all(is_win_ansi) {
+          return Err(
+            PdfError::new(
+              ErrorCode::Unsupported,
+              format!(
+                "Recognized text \"{}\" has characters missing in standard font, set font path",
+                line.text
+              ),
+            )
+            .on_page(page_index as usize)
+            .into(),
+          );
+        }
+        let mut object =
+          PdfPageTextObject::new(&document, &line.text, font, PdfPoints::new(height)).map_err(
+            |err| {
+              PdfError::edit("Failed to create recognized text")
+                .on_page(page_index as usize)
+                .caused_by(err)
+            },
+          )?;
+        object
+          .set_render_mode(PdfPageTextRenderMode::Invisible)
+          .map_err(|err| {
+            PdfError::edit("Failed to hide recognized text")
+              .on_page(page_index as usize)
+              .caused_by(err)
+          })?;
+
+        // stretch text to recognized line size and turn it upright as the page is displayed
+        let (text_width, text_height) = match degrees {
+          90 | 270 => (height, width),
+          _ => (width, height),
+        };
+        let bounds = object.bounds().map_err(|err| {
+          PdfError::edit("Failed to measure recognized text")
+            .on_page(page_index as usize)
+            .caused_by(err)
+        })?;
+        if bounds.width().value > 0.0 && bounds.height().value > 0.0 {
+          object
+            .scale(
+              text_width / bounds.width().value,
+              text_height / bounds.height().value,
+            )
+            .map_err(|err| {
+              PdfError::edit("Failed to place recognized text")
+                .on_page(page_index as usize)
+                .caused_by(err)
+            })?;
+        }
+        object
+          .rotate_counter_clockwise_degrees(degrees as f32)
+          .map_err(|err| {
+            PdfError::edit("Failed to place recognized text")
+              .on_page(page_index as usize)
+              .caused_by(err)
+          })?;
+        let bounds = object.bounds().map_err(|err| {
+          PdfError::edit("Failed to measure recognized text")
+            .on_page(page_index as usize)
+            .caused_by(err)
+        })?;
+        object
+          .translate(
+            PdfPoints::new(left as f32 - bounds.left().value),
+            PdfPoints::new(bottom as f32 - bounds.bottom().value),
+          )
+          .map_err(|err| {
+            PdfError::edit("Failed to place recognized text")
+              .on_page(page_index as usize)
+              .caused_by(err)
+          })?;
+
+        page.objects_mut().add_text_object(object).map_err(|err| {
+          PdfError::edit("Failed to add recognized text to page")
+            .on_page(page_index as usize)
+            .caused_by(err)
+        })?;
+      }
+
+      let confidence = if lines.is_empty() {
+        0.0
+      } else {
+        lines.iter().map(|line| line.confidence).sum::<f64>() / lines.len() as f64
+      };
+      result.push(SearchablePage {
+        page_index,
+        line_count: lines.len() as u32,
+        confidence,
+      });
+    }
+
+    save_document(document, &output_path)?;
+    Ok(result)
+  })
+  .await
+}
+
+/// Character of Windows-1252 code page standard fonts are written in
+fn is_win_ansi(c: char) -> bool {
+  matches!(c, ' '..='~' | '\u{a0}'..='\u{ff}') || "€‚ƒ„…†‡ˆ‰Š‹ŒŽ‘’“”•–—˜™š›œžŸ".contains(c)
+}
+
+/// Page already has invisible text layer, like scans recognized before
+fn has_invisible_text(page: &PdfPage) -> bool {
+  page.objects().iter().any(|object| {
+    object.as_text_object().is_some_and(|text| {
+      text.render_mode() == PdfPageTextRenderMode::Invisible && !text.text().trim().is_empty()
+    })
+  })
+}
+
+#[cfg(not(feature = "ocr"))]
+pub fn recognize_page(
+  _page: &PdfPage,
+  _options: &ExtractOptions,
+) -> napi::Result<Vec<RecognizedLine>> {
+  Err(
+    PdfError::new(
+      ErrorCode::Unsupported,
+      "OCR is not supported by this build, rebuild with `ocr` feature",
+    )
+    .into(),
+  )
+}
+
+#[cfg(feature = "ocr")]
+// pages are rendered in this resolution for recognition
+static OCR_DPI: f32 = 300.0;
+
+#[cfg(feature = "ocr")]
+static RENDERED_PAGES_COUNT: std::sync::atomic::AtomicUsize =
+  std::sync::atomic::AtomicUsize::new(0);
+
+/// Render page and recognize its text lines with tesseract command line tool
+#[cfg(feature = "ocr")]
+pub fn recognize_page(
+  page: &PdfPage,
+  options: &ExtractOptions,
+) -> napi::Result<Vec<RecognizedLine>> {
+  use std::process::Command;
+  use std::sync::atomic::Ordering;
+
+  let image = page
+    .render_with_config(&PdfRenderConfig::new().scale_page_by_factor(OCR_DPI / 72.0))
+    .map_err(|err| PdfError::new(ErrorCode::OcrFailed, "Failed to render pdf page").caused_by(err))?
+    .as_image();
+
+  let image_path = std::env::temp_dir().join(format!(
+    "pdf-lib-ocr-{}-{}.png",
+    std::process::id(),
+    RENDERED_PAGES_COUNT.fetch_add(1, Ordering::Relaxed)
+  ));
+  image
+    .save(&image_path)
+    .map_err(|err| PdfError::new(ErrorCode::OcrFailed, "Failed to save pdf page").caused_by(err))?;
+
+  let output = Command::new("tesseract")
+    .arg(&image_path)
+    .arg("stdout")
+    .args(["-l", options.ocr_language.as_deref().unwrap_or("eng")])
+    .args(["--dpi", &OCR_DPI.to_string()])
+    .arg("tsv")
+    .output();
+  let _ = std::fs::remove_file(&image_path);
+
+  let output = output
+    .map_err(|err| PdfError::new(ErrorCode::OcrFailed, "Failed to run tesseract").caused_by(err))?;
+  if !output.status.success() {
+    return Err(
+      PdfError::new(
+        ErrorCode::OcrFailed,
+        format!(
+          "Tesseract failed - {}",
+          String::from_utf8_lossy(&output.stderr).trim()
+        ),
+      )
+      .into(),
+    );
+  }
+
+  let scale = 72.0 / OCR_DPI as f64;
+  let page_height = page.height().value as f64;
+  Ok(
+    tsv_lines(&String::from_utf8_lossy(&output.stdout))
+      .into_iter()
+      .map(|line| {
+        // pixels from the top left corner into points from the bottom left corner
+        let (left, top, right, bottom) = line.bounds;
+        RecognizedLine {
+          bounds: (
+            left * scale,
+            page_height - top * scale,
+            right * scale,
+            page_height - bottom * scale,
+          ),
+          ..line
+        }
+      })
+      .collect(),
+  )
+}
+
+/// Words of tesseract TSV output joined into lines, bounds in pixels from the top left corner
+#[cfg(feature = "ocr")]
+fn tsv_lines(tsv: &str) -> Vec<RecognizedLine> {
+  let mut lines: Vec<RecognizedLine> = vec![];
+  let mut last_line_id: Option<(u32, u32, u32)> = None;
+  // words count of the last line
+  let mut line_words = 0.0;
+
+  // level, page, block, paragraph, line and word numbers, left, top, width, height,
+  // confidence and text
+  for row in tsv.lines().skip(1) {
+    let fields: Vec<&str> = row.split('\t').collect();
+    if fields.len() < 12 || fields[0] != "5" || fields[11].trim().is_empty() {
+      continue;
+    }
+    let number = |i: usize| fields[i].parse::<u32>().unwrap_or(0);
+    let (left, top, width, height) = (
+      number(6) as f64,
+      number(7) as f64,
+      number(8) as f64,
+      number(9) as f64,
+    );
+    let line_id = (number(2), number(3), number(4));
+    let confidence = fields[10].parse::<f64>().unwrap_or(0.0).max(0.0);
+
+    match lines.last_mut() {
+      Some(line) if last_line_id == Some(line_id) => {
+        let (l, t, r, b) = line.bounds;
+        line.text.push(' ');
+        line.text.push_str(fields[11]);
+        line.bounds = (
+          l.min(left),
+          t.min(top),
+          r.max(left + width),
+          b.max(top + height),
+        );
+        line_words += 1.0;
+        line.confidence += (confidence - line.confidence) / line_words;
+      }
+      _ => {
+        lines.push(RecognizedLine {
+          text: fields[11].to_owned(),
+          paragraph: (line_id.0, line_id.1),
+          bounds: (left, top, left + width, top + height),
+          confidence,
+        });
+        line_words = 1.0;
+      }
+    }
+    last_line_id = Some(line_id);
+  }
+
+  lines
+}
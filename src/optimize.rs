@@ -0,0 +1,450 @@
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, GrayImage, ImageResult, RgbImage};
+use pdfium_render::prelude::*;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::os::raw::{c_int, c_uint, c_void};
+
+use crate::errors::{ErrorCode, PdfError};
+use crate::images::{encode_image, DEFAULT_JPEG_QUALITY, PAGE_OBJECT_IMAGE};
+use crate::objects::{find_last, name_value, number_value, written_value, PdfObjects};
+use crate::options::{ImageOutputFormat, OptimizeOptions};
+use crate::{get_pdfium, load_document, run_blocking, save_document};
+
+// pdfium bitmap formats
+static BITMAP_GRAY: c_int = 1;
+static BITMAP_BGR: c_int = 2;
+// pdfium image color spaces of single gray channel
+static COLORSPACE_DEVICE_GRAY: c_int = 1;
+static COLORSPACE_CAL_GRAY: c_int = 4;
+// image dictionary entries kept in its JPEG replacement
+static KEPT_IMAGE_ENTRIES: [&str; 4] = ["/Decode", "/Interpolate", "/Intent", "/Metadata"];
+
+#[napi(object)]
+/// Sizes and changed images of optimized document
+pub struct OptimizedDocument {
+  pub input_size_bytes: u32,
+  pub output_size_bytes: u32,
+  /// Number of images replaced by smaller JPEG
+  pub recompressed_images: u32,
+  /// Number of recompressed images scaled down to the maximum resolution
+  pub downsampled_images: u32,
+}
+
+/// Image object metadata layout of pdfium `FPDF_IMAGEOBJ_METADATA`
+#[repr(C)]
+#[derive(Default)]
+struct ImageMetadata {
+  width: c_uint,
+  height: c_uint,
+  horizontal_dpi: f32,
+  vertical_dpi: f32,
+  bits_per_pixel: c_uint,
+  colorspace: c_int,
+  marked_content_id: c_int,
+}
+
+/// JPEG replacing data of image stream object
+struct ReplacedImage {
+  object_number: u32,
+  generation: u32,
+  width: u32,
+  height: u32,
+  /// JPEG has single gray channel instead of RGB
+  is_gray: bool,
+  /// Entries of the original image dictionary written into the replacement as they are
+  kept_entries: String,
+  data: Vec<u8>,
+  is_downsampled: bool,
+}
+
+#[napi(catch_unwind)]
+/// Shrink document by recompressing images into JPEG where it is smaller, optionally scaling
+/// down images shown in higher resolution than needed, and save it into output path without
+/// objects no longer used
+pub async fn optimize_pdf(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+  output_path: String,
+  options: Option<OptimizeOptions>,
+) -> napi::Result<OptimizedDocument> {
+  run_blocking(move || {
+    let options = options.unwrap_or_default();
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    // fail with the reason reported by pdfium-render before reading the document directly
+    drop(load_document(pdfium, &pdf_path)?);
+    let data = std::fs::read(&pdf_path).map_err(|err| {
+      PdfError::new(ErrorCode::LoadFailed, "Failed to open pdf document").caused_by(err)
+    })?;
+
+    let quality = options.quality.map(|quality| quality.min(100) as u8);
+    let max_image_dpi = options.max_image_dpi.filter(|dpi| *dpi > 0.0);
+    let images = recompressed_images(pdfium.bindings(), &pdf_path, &data, quality, max_image_dpi)?;
+
+    // pdfium keeps replaced images referenced from page resources, so image streams are rewritten
+    // in an incremental update and the complete save writes only their latest versions
+    let updated = match images.is_empty() {
+      true => None,
+      false => with_replaced_images(&data, &images),
+    };
+    let (document, images) =
+      match updated.and_then(|data| pdfium.load_pdf_from_byte_vec(data, None).ok()) {
+        Some(document) => (document, images),
+        None => (load_document(pdfium, &pdf_path)?, vec![]),
+      };
+    save_document(document, &output_path)?;
+    let output_size_bytes = std::fs::metadata(&output_path)
+      .map_err(|err| {
+        PdfError::new(ErrorCode::SaveFailed, "Failed to read saved pdf document").caused_by(err)
+      })?
+      .len();
+
+    Ok(OptimizedDocument {
+      input_size_bytes: data.len() as u32,
+      output_size_bytes: output_size_bytes as u32,
+      recompressed_images: images.len() as u32,
+      downsampled_images: images.iter().filter(|image| image.is_downsampled).count() as u32,
+    })
+  })
+  .await
+}
+
+/// Images of the document data encoded into JPEG smaller than their streams, read through pdfium
+/// bindings as pdfium-render exposes decoded images only
+fn recompressed_images(
+  bindings: &dyn PdfiumLibraryBindings,
+  pdf_path: &str,
+  data: &[u8],
+  quality: Option<u8>,
+  max_image_dpi: Option<f64>,
+) -> napi::Result<Vec<ReplacedImage>> {
+  let document = bindings.FPDF_LoadDocument(pdf_path, None);
+  if document.is_null() {
+    return Err(PdfError::new(ErrorCode::LoadFailed, "Failed to read pdf document").into());
+  }
+
+  // lowest resolution each image is shown in by hash of its stream, so shared images are scaled
+  // down for their largest use
+  let mut shown_dpis: HashMap<u64, f64> = HashMap::new();
+  let mut visited_images: HashSet<u64> = HashSet::new();
+  let objects = PdfObjects::parse(data);
+  let image_objects = objects
+    .as_ref()
+    .map(image_stream_objects)
+    .unwrap_or_default();
+  let mut images = vec![];
+
+  let passes: &[bool] = match max_image_dpi {
+    Some(_) => &[true, false],
+    None => &[false],
+  };
+  for is_measuring in passes {
+    for page_index in 0..bindings.FPDF_GetPageCount(document) {
+      let page = bindings.FPDF_LoadPage(document, page_index);
+      if page.is_null() {
+        continue;
+      }
+
+      for idx in 0..bindings.FPDFPage_CountObjects(page) {
+        let object = bindings.FPDFPage_GetObject(page, idx);
+        if object.is_null() || bindings.FPDFPageObj_GetType(object) != PAGE_OBJECT_IMAGE {
+          continue;
+        }
+
+        // bilevel scans and stencil masks are smaller in their lossless encodings
+        let mut metadata = ImageMetadata::default();
+        if !bindings.is_true(bindings.FPDFImageObj_GetImageMetadata(
+          object,
+          page,
+          &mut metadata as *mut ImageMetadata as *mut _,
+        )) || metadata.bits_per_pixel < 8
+        {
+          continue;
+        }
+
+        let length = bindings.FPDFImageObj_GetImageDataRaw(object, std::ptr::null_mut(), 0);
+        let mut stream = vec![0u8; length as usize];
+        bindings.FPDFImageObj_GetImageDataRaw(object, stream.as_mut_ptr() as *mut c_void, length);
+        if stream.is_empty() {
+          continue;
+        }
+        let stream_hash = stream_hash(&stream);
+
+        if *is_measuring {
+          // quarter turns of the image keep its bounds area
+          let (mut left, mut bottom, mut right, mut top) = (0.0, 0.0, 0.0, 0.0);
+          if bindings.is_true(bindings.FPDFPageObj_GetBounds(
+            object,
+            &mut left,
+            &mut bottom,
+            &mut right,
+            &mut top,
+          )) && right > left
+            && top > bottom
+          {
+            let pixels = metadata.width as f64 * metadata.height as f64;
+            let dpi = (pixels / ((right - left) * (top - bottom)) as f64).sqrt() * 72.0;
+            let shown_dpi = shown_dpis.entry(stream_hash).or_insert(dpi);
+            *shown_dpi = shown_dpi.min(dpi);
+          }
+          continue;
+        }
+
+        if !visited_images.insert(stream_hash) {
+          continue;
+        }
+        // objects are compared as their stream hashes may collide
+        let image_object = image_objects.get(&stream_hash).and_then(|number| {
+          let objects = objects.as_ref()?;
+          if objects.stream(*number)? != stream.as_slice() {
+            return None;
+          }
+          Some((
+            *number,
+            objects.generation(*number)?,
+            objects.dictionary(*number)?,
+          ))
+        });
+        let Some((object_number, generation, dictionary)) = image_object else {
+          continue;
+        };
+
+        let bitmap = bindings.FPDFImageObj_GetBitmap(object);
+        if bitmap.is_null() {
+          continue;
+        }
+        let width = bindings.FPDFBitmap_GetWidth(bitmap).max(0) as u32;
+        let height = bindings.FPDFBitmap_GetHeight(bitmap).max(0) as u32;
+        let stride = bindings.FPDFBitmap_GetStride(bitmap).max(1) as usize;
+        let format = bindings.FPDFBitmap_GetFormat(bitmap);
+        let rows = bindings
+          .FPDFBitmap_GetBuffer_as_slice(bitmap)
+          .chunks_exact(stride)
+          .take(height as usize);
+        let image = if format == BITMAP_GRAY {
+          let pixels = rows.flat_map(|row| &row[..width as usize]).copied();
+          GrayImage::from_raw(width, height, pixels.collect()).map(DynamicImage::ImageLuma8)
+        } else {
+          // BGR or BGR with unused or alpha byte
+          let channels = if format == BITMAP_BGR { 3 } else { 4 };
+          let pixels = rows.flat_map(|row| {
+            row[..width as usize * channels]
+              .chunks_exact(channels)
+              .flat_map(|pixel| [pixel[2], pixel[1], pixel[0]])
+          });
+          RgbImage::from_raw(width, height, pixels.collect()).map(DynamicImage::ImageRgb8)
+        };
+        bindings.FPDFBitmap_Destroy(bitmap);
+        let Some(mut image) = image else {
+          continue;
+        };
+
+        let scale = match (max_image_dpi, shown_dpis.get(&stream_hash)) {
+          (Some(max_image_dpi), Some(shown_dpi)) if *shown_dpi > max_image_dpi => {
+            Some(max_image_dpi / shown_dpi)
+          }
+          _ => None,
+        };
+        if let Some(scale) = scale {
+          image = image.resize_exact(
+            ((width as f64 * scale).round() as u32).max(1),
+            ((height as f64 * scale).round() as u32).max(1),
+            FilterType::Triangle,
+          );
+        }
+
+        // pdfium renders gray images into color bitmaps
+        let is_gray = matches!(image, DynamicImage::ImageLuma8(_))
+          || [COLORSPACE_DEVICE_GRAY, COLORSPACE_CAL_GRAY].contains(&metadata.colorspace);
+        if is_gray {
+          image = DynamicImage::ImageLuma8(image.to_luma8());
+        }
+        // pdfium applies decode array to bitmap samples, the replacement keeps it
+        if let Some(decode) = written_value(&dictionary, "/Decode") {
+          let Some(undecoded) = undecoded(image, decode) else {
+            continue;
+          };
+          image = undecoded;
+        }
+        let jpeg = match is_gray {
+          true => encode_gray_jpeg(&image, quality),
+          false => encode_image(&image, ImageOutputFormat::Jpeg, quality),
+        };
+        match jpeg {
+          Ok(jpeg) if jpeg.len() < stream.len() => images.push(ReplacedImage {
+            object_number,
+            generation,
+            width: image.width(),
+            height: image.height(),
+            is_gray,
+            kept_entries: kept_entries(&dictionary),
+            data: jpeg,
+            is_downsampled: scale.is_some(),
+          }),
+          _ => {}
+        }
+      }
+
+      bindings.FPDF_ClosePage(page);
+    }
+  }
+
+  bindings.FPDF_CloseDocument(document);
+  Ok(images)
+}
+
+/// Numbers of image stream objects by hash of their data, images with masks are skipped as JPEG
+/// keeps no transparency
+fn image_stream_objects(objects: &PdfObjects) -> HashMap<u64, u32> {
+  objects
+    .numbers()
+    .filter_map(|number| {
+      let dictionary = objects.dictionary(number)?;
+      if name_value(&dictionary, "/Subtype").as_deref() != Some("Image")
+        || dictionary.contains("Mask")
+      {
+        return None;
+      }
+      Some((stream_hash(objects.stream(number)?), number))
+    })
+    .collect()
+}
+
+/// Entries of image dictionary to keep, written like ` /Interpolate true`
+fn kept_entries(dictionary: &str) -> String {
+  KEPT_IMAGE_ENTRIES
+    .iter()
+    .filter_map(|key| {
+      let value = reference_value(dictionary, key)
+        .or_else(|| written_value(dictionary, key).map(str::to_owned))?;
+      Some(format!(" {} {}", key, value))
+    })
+    .collect()
+}
+
+/// Image samples before decode array like `[1 0]` mapped them, none when the array does not
+/// map each image channel
+fn undecoded(mut image: DynamicImage, decode: &str) -> Option<DynamicImage> {
+  let ranges: Vec<f32> = decode
+    .trim_start_matches('[')
+    .trim_end_matches(']')
+    .split_whitespace()
+    .map(|value| value.parse().ok())
+    .collect::<Option<_>>()?;
+  let channels = image.color().channel_count() as usize;
+  if ranges.len() != channels * 2 || ranges.chunks_exact(2).any(|range| range[0] == range[1]) {
+    return None;
+  }
+  let samples: &mut [u8] = match &mut image {
+    DynamicImage::ImageLuma8(image) => image,
+    DynamicImage::ImageRgb8(image) => image,
+    _ => return None,
+  };
+  for (index, sample) in samples.iter_mut().enumerate() {
+    let range = &ranges[index % channels * 2..][..2];
+    let value = (*sample as f32 / 255.0 - range[0]) / (range[1] - range[0]);
+    *sample = (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+  }
+  Some(image)
+}
+
+/// Encode gray image into JPEG with its single channel, which other image encoding converts to
+/// RGB
+fn encode_gray_jpeg(image: &DynamicImage, quality: Option<u8>) -> ImageResult<Vec<u8>> {
+  let mut data = vec![];
+  let encoder = JpegEncoder::new_with_quality(
+    &mut data,
+    quality.unwrap_or(DEFAULT_JPEG_QUALITY).clamp(1, 100),
+  );
+  image.write_with_encoder(encoder)?;
+  Ok(data)
+}
+
+fn stream_hash(stream: &[u8]) -> u64 {
+  let mut hasher = DefaultHasher::new();
+  stream.hash(&mut hasher);
+  hasher.finish()
+}
+
+/// Document data with appended incremental update rewriting image streams, none for encrypted
+/// documents or trailer which cannot be read
+fn with_replaced_images(data: &[u8], images: &[ReplacedImage]) -> Option<Vec<u8>> {
+  let startxref = find_last(data, b"startxref")?;
+  let previous = String::from_utf8_lossy(&data[startxref + b"startxref".len()..])
+    .split_whitespace()
+    .next()?
+    .parse::<usize>()
+    .ok()?;
+  // trailer or cross reference stream dictionary of the last section
+  let section = String::from_utf8_lossy(data.get(previous..startxref)?);
+  if section.contains("/Encrypt") {
+    return None;
+  }
+  let size = number_value(&section, "/Size")?;
+  let root = reference_value(&section, "/Root")?;
+  let info = reference_value(&section, "/Info");
+
+  let mut result = data.to_vec();
+  result.push(b'\n');
+  let mut offsets = vec![];
+  for image in images {
+    offsets.push(result.len());
+    result.extend_from_slice(
+      format!(
+        "{} {} obj\n<< /Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace {} \
+         /BitsPerComponent 8 /Filter /DCTDecode /Length {}{} >>\nstream\n",
+        image.object_number,
+        image.generation,
+        image.width,
+        image.height,
+        match image.is_gray {
+          true => "/DeviceGray",
+          false => "/DeviceRGB",
+        },
+        image.data.len(),
+        image.kept_entries
+      )
+      .as_bytes(),
+    );
+    result.extend_from_slice(&image.data);
+    result.extend_from_slice(b"\nendstream\nendobj\n");
+  }
+
+  let xref = result.len();
+  result.extend_from_slice(b"xref\n");
+  for (image, offset) in images.iter().zip(offsets) {
+    // entries are 20 bytes long with their line end
+    result.extend_from_slice(
+      format!(
+        "{} 1\n{:010} {:05} n\r\n",
+        image.object_number, offset, image.generation
+      )
+      .as_bytes(),
+    );
+  }
+  let info = info.map_or(String::new(), |info| format!(" /Info {}", info));
+  result.extend_from_slice(
+    format!(
+      "trailer\n<< /Size {} /Root {}{} /Prev {} >>\nstartxref\n{}\n%%EOF\n",
+      size, root, info, previous, xref
+    )
+    .as_bytes(),
+  );
+  Some(result)
+}
+
+/// Indirect reference like `12 0 R` of dictionary key
+fn reference_value(dictionary: &str, key: &str) -> Option<String> {
+  dictionary.split(key).skip(1).find_map(|rest| {
+    let mut parts = rest.split_whitespace();
+    let number = parts.next()?.parse::<u32>().ok()?;
+    let generation = parts.next()?.parse::<u32>().ok()?;
+    parts
+      .next()?
+      .starts_with('R')
+      .then(|| format!("{} {} R", number, generation))
+  })
+}
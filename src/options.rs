@@ -0,0 +1,289 @@
+use napi::threadsafe_function::{ErrorStrategy, ThreadsafeFunction};
+
+use crate::abort::AbortFlag;
+use crate::ExtractProgress;
+
+#[napi(object, object_to_js = false)]
+#[derive(Clone, Default)]
+/// Extraction options
+pub struct ExtractOptions {
+  /// Folder to save extracted images into, created if not exist
+  pub images_folder_path: Option<String>,
+  /// Extracted images format, PNG by default
+  pub image_format: Option<ImageOutputFormat>,
+  /// Lossy images encoding quality from 1 to 100, used for JPEG
+  pub image_quality: Option<u32>,
+  /// Extracted image filename with `{pdfStem}` pdf filename without extension, `{page}` page
+  /// number, `{index}` image number on the page, `{n}` image number in the document and `{ext}`
+  /// extension placeholders, `image-{n}.{ext}` by default
+  pub image_filename_template: Option<String>,
+  /// Save JPEG images with their original data as `.jpg` files instead of decoding and encoding
+  /// them in image format, ignored when images are rendered in DPI
+  pub keep_original_jpeg: Option<bool>,
+  /// Turn and mirror extracted images as they are displayed on the page by their transformation
+  /// and page rotation, ignored when images are rendered in DPI
+  pub apply_image_transform: Option<bool>,
+  /// Render images at the size they appear on the page in this DPI instead of extracting embedded resolution
+  pub image_dpi: Option<f64>,
+  /// Save page vector graphics drawn with paths as SVG files alongside images
+  pub export_vector_graphics: Option<bool>,
+  /// Return page text lines with their bounding rectangles, font style and color in
+  /// `pageTextLineBounds`
+  pub include_text_line_bounds: Option<bool>,
+  /// Classify text lines in `pageTextLineBounds` as headings or body text by their font size
+  /// and weight relative to the document body text
+  pub detect_headings: Option<bool>,
+  /// Exclude text repeating at the same position in page top or bottom margin on most pages,
+  /// like running headers and page numbers
+  pub strip_headers_and_footers: Option<bool>,
+  /// Merge consecutive text lines into paragraphs returned in `pageParagraphs`
+  pub detect_paragraphs: Option<bool>,
+  /// Join words hyphenated at line end with their rest on the next line, like "instal-" and
+  /// "lation" into "installation", when the next line starts lowercase
+  pub dehyphenate: Option<bool>,
+  /// Return right to left text like Arabic or Hebrew in visual order as displayed from left
+  /// to right instead of logical reading order
+  pub bidi_visual_order: Option<bool>,
+  /// Unicode normalization form of extracted text, not normalized by default
+  pub unicode_normalization: Option<UnicodeNormalization>,
+  /// Replace typographic ligatures like "ﬁ" and "ﬂ" with their letters
+  pub expand_ligatures: Option<bool>,
+  /// Detect page text language returned in `language`
+  pub detect_language: Option<bool>,
+  /// Allowed vertical position difference of objects on the same line relative to their font
+  /// size, like 0.4, 5 points regardless of font size by default
+  pub same_line_tolerance: Option<f64>,
+  /// Emit text and images column by column using XY-cut page segmentation instead of
+  /// sorting the whole page from top to bottom
+  pub column_reading_order: Option<bool>,
+  /// Emit text lines of tagged pages in the order their structure tree lists them instead of by
+  /// their position, images and untagged text like page artifacts keep their place
+  pub structure_reading_order: Option<bool>,
+  /// Place text in `extractTextWithOptions` output by its position on the page, padding
+  /// words with spaces and lines with empty lines like `pdftotext -layout`
+  pub preserve_layout: Option<bool>,
+  /// Return bounds on rotated pages in page space as displayed, origin at the bottom left
+  /// corner of the rotated page
+  pub normalize_rotation: Option<bool>,
+  /// Recognize text of pages without extractable text with tesseract, requires build with
+  /// `ocr` feature and tesseract installed
+  pub ocr: Option<bool>,
+  /// Tesseract languages like `eng+deu`, `eng` by default
+  pub ocr_language: Option<String>,
+  /// Number of closest text lines related to each image, 2 by default
+  pub related_text_lines: Option<u32>,
+  /// Take image related text lines above, below or on both sides of it, above or below images
+  /// at page top by default
+  pub related_text_position: Option<RelatedTextPosition>,
+  /// Maximum vertical distance in points between image and its related text lines, unlimited
+  /// by default
+  pub related_text_max_distance: Option<f64>,
+  /// Return encoded images in `data` of image metadata instead of saving them into images folder
+  pub images_as_buffers: Option<bool>,
+  /// Scale down extracted images which width or height is larger than this in pixels keeping
+  /// their aspect ratio, unlimited by default
+  pub max_image_dimension: Option<u32>,
+  /// Skip images narrower than this in pixels
+  pub min_image_width: Option<u32>,
+  /// Skip images lower than this in pixels
+  pub min_image_height: Option<u32>,
+  /// Skip images which encoded size is smaller than this
+  pub min_file_size_bytes: Option<u32>,
+  /// Save identical images once, repeated images refer to the first extracted file
+  pub deduplicate_images: Option<bool>,
+  /// Exclude images repeating at the same position on most pages, like logos and border art
+  pub exclude_recurring_images: Option<bool>,
+  /// Fail with `LIMIT_EXCEEDED` error for documents with more pages
+  pub max_pages: Option<u32>,
+  /// Fail with `LIMIT_EXCEEDED` error once extracted images take more bytes in total
+  pub max_total_image_bytes: Option<u32>,
+  /// Fail with `LIMIT_EXCEEDED` error before decoding an image with more pixels
+  pub max_image_pixels: Option<u32>,
+  /// Keep extracting when a page fails to be read, the page is left empty with the `PAGE_FAILED`
+  /// error in its warnings, text and images extraction only
+  pub skip_bad_pages: Option<bool>,
+  /// Measure extraction stages of each page, text and images extraction only
+  pub include_timings: Option<bool>,
+  /// Called after each processed page
+  pub on_progress: Option<ThreadsafeFunction<ExtractProgress, ErrorStrategy::Fatal>>,
+  /// Stops extraction before the next page once aborted
+  #[napi(ts_type = "AbortSignal")]
+  pub signal: Option<AbortFlag>,
+}
+
+#[napi(string_enum = "lowercase")]
+/// Extracted images format
+pub enum ImageOutputFormat {
+  Png,
+  Jpeg,
+  Webp,
+}
+
+#[napi(string_enum = "lowercase")]
+/// Side of image to take its related text lines from
+pub enum RelatedTextPosition {
+  Above,
+  Below,
+  Both,
+}
+
+#[napi(string_enum = "lowercase")]
+/// Unicode normalization form, compatibility form also replaces ligatures, full width and
+/// other compatibility characters
+pub enum UnicodeNormalization {
+  Nfc,
+  Nfkc,
+}
+
+#[napi(object)]
+#[derive(Default)]
+/// Pages rendering options
+pub struct RenderOptions {
+  /// Indices of pages to render, all pages by default
+  pub pages: Option<Vec<u32>>,
+  /// Rendering resolution, 150 by default
+  pub dpi: Option<f64>,
+  /// Rendered images format, PNG by default
+  pub format: Option<ImageOutputFormat>,
+  /// Lossy images encoding quality from 1 to 100, used for JPEG
+  pub quality: Option<u32>,
+  /// Folder to save rendered images into, created if not exist
+  pub output_folder_path: Option<String>,
+  /// Return encoded images in `data` instead of saving them into output folder
+  pub as_buffers: Option<bool>,
+  /// Number of page images encoded at the same time by `renderDocument`, available CPUs by
+  /// default
+  pub concurrency: Option<u32>,
+  /// Square tile side in pixels used by `renderTiles`, 256 by default
+  pub tile_size: Option<u32>,
+}
+
+#[napi(object)]
+#[derive(Default)]
+/// Visual documents comparison options
+pub struct CompareOptions {
+  /// Rendering resolution, 72 by default
+  pub dpi: Option<f64>,
+  /// Largest color channel difference from 0 to 255 of pixels considered same, 16 by default
+  pub threshold: Option<u32>,
+  /// Create images highlighting differing pixels of pages
+  pub diff_images: Option<bool>,
+  /// Diff images format, PNG by default
+  pub format: Option<ImageOutputFormat>,
+  /// Folder to save diff images into, created if not exist
+  pub output_folder_path: Option<String>,
+  /// Return diff images in `data` instead of saving them into output folder
+  pub as_buffers: Option<bool>,
+}
+
+#[napi(object)]
+#[derive(Default)]
+/// Attachments extraction options
+pub struct AttachmentOptions {
+  /// Folder to save attachments into, created if not exist
+  pub output_folder_path: Option<String>,
+  /// Return attachments content in `data` instead of saving them into output folder
+  pub as_buffers: Option<bool>,
+}
+
+#[napi(object)]
+#[derive(Default)]
+/// Embedded fonts extraction options
+pub struct FontOptions {
+  /// Folder to save font programs into, created if not exist
+  pub output_folder_path: Option<String>,
+  /// Return font programs in `data` instead of saving them into output folder
+  pub as_buffers: Option<bool>,
+}
+
+#[napi(object)]
+#[derive(Default)]
+/// Document splitting options
+pub struct SplitOptions {
+  /// Number of pages in each output document, 1 by default
+  pub pages_per_file: Option<u32>,
+}
+
+#[napi(object)]
+#[derive(Default)]
+/// Images to pdf conversion options
+pub struct ImagesToPdfOptions {
+  /// Resolution of images to size pages by, 72 by default
+  pub dpi: Option<f64>,
+  /// Page width in points, image width with margins by default
+  pub page_width: Option<f64>,
+  /// Page height in points, image height with margins by default
+  pub page_height: Option<f64>,
+  /// Space around images in points, 0 by default
+  pub margin: Option<f64>,
+}
+
+#[napi(object)]
+#[derive(Default)]
+/// Document optimization options
+pub struct OptimizeOptions {
+  /// JPEG quality of recompressed images from 1 to 100, 80 by default
+  pub quality: Option<u32>,
+  /// Scale down images shown in higher resolution than this, images keep their resolution by
+  /// default
+  pub max_image_dpi: Option<f64>,
+}
+
+#[napi(object)]
+#[derive(Default)]
+/// Options of adding recognized text layer to scanned pages
+pub struct SearchableOptions {
+  /// Indices of pages to recognize, scanned pages without recognized text layer by default
+  pub pages: Option<Vec<u32>>,
+  /// Tesseract languages like `eng+deu`, `eng` by default
+  pub ocr_language: Option<String>,
+  /// Path to TrueType font embedded for recognized text, standard Helvetica by default which
+  /// writes WinAnsi characters only
+  pub font_path: Option<String>,
+}
+
+#[napi(object)]
+#[derive(Default)]
+/// Text watermark or image stamp options, either text or image path is required
+pub struct StampOptions {
+  /// Watermark text
+  pub text: Option<String>,
+  /// Path to stamp image
+  pub image_path: Option<String>,
+  /// Indices of pages to stamp, all pages by default
+  pub pages: Option<Vec<u32>>,
+  /// Watermark font size in points, 48 by default
+  pub font_size: Option<f64>,
+  /// Stamp width in points, image size at 72 DPI by default
+  pub image_width: Option<f64>,
+  /// Counterclockwise angle in degrees as page is displayed, 0 by default
+  pub angle: Option<f64>,
+  /// Opacity from 0 to 1, 0.5 by default
+  pub opacity: Option<f64>,
+  /// Horizontal position of stamp center in page space points, page center by default
+  pub x: Option<f64>,
+  /// Vertical position of stamp center in page space points, page center by default
+  pub y: Option<f64>,
+}
+
+#[napi(object)]
+#[derive(Default)]
+/// Text search options
+pub struct SearchOptions {
+  /// Match letter case
+  pub match_case: Option<bool>,
+  /// Match whole words only
+  pub match_whole_word: Option<bool>,
+}
+
+#[napi(object)]
+#[derive(Default)]
+/// Redaction options, matches of all terms and patterns are removed
+pub struct RedactOptions {
+  /// Exact text to redact, case sensitive
+  pub terms: Option<Vec<String>>,
+  /// Regular expressions to redact like `[\w.]+@\w+\.\w+`
+  pub patterns: Option<Vec<String>>,
+  /// Indices of pages to redact, all pages by default
+  pub pages: Option<Vec<u32>>,
+}
@@ -0,0 +1,447 @@
+use image::metadata::Orientation;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader};
+use pdfium_render::prelude::*;
+use std::collections::BTreeSet;
+use std::fs::create_dir_all;
+use std::io::{BufRead, Cursor, Seek};
+use std::os::raw::{c_int, c_ulong, c_void};
+use std::path::Path;
+
+use crate::document::rotation_degrees;
+use crate::errors::{ErrorCode, PdfError};
+use crate::objects::saved_data;
+use crate::options::{ImagesToPdfOptions, SplitOptions};
+use crate::{get_pdfium, load_document, run_blocking, save_document, RawDocument};
+
+#[napi(object)]
+/// Document saved from a range of source pages
+pub struct SplitDocument {
+  /// Document filename in output folder
+  pub filename: String,
+  pub first_page_index: u32,
+  pub page_count: u32,
+}
+
+#[napi(catch_unwind)]
+/// Split document into files with one or configured number of pages each
+pub async fn split_pdf(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+  output_folder_path: String,
+  options: Option<SplitOptions>,
+) -> napi::Result<Vec<SplitDocument>> {
+  run_blocking(move || {
+    let options = options.unwrap_or_default();
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let document = load_document(pdfium, &pdf_path)?;
+    create_dir_all(&output_folder_path).map_err(|err| {
+      PdfError::new(ErrorCode::SaveFailed, "Failed to create output folder").caused_by(err)
+    })?;
+
+    let pages_per_file = options.pages_per_file.unwrap_or(1).max(1) as PdfPageIndex;
+    let total_pages = document.pages().len();
+
+    let mut result: Vec<SplitDocument> = vec![];
+
+    for first_page_index in (0..total_pages).step_by(pages_per_file as usize) {
+      let last_page_index = (first_page_index + pages_per_file).min(total_pages) - 1;
+
+      let mut part = pdfium
+        .create_new_pdf()
+        .map_err(|err| PdfError::edit("Failed to create pdf document").caused_by(err))?;
+      part
+        .pages_mut()
+        .copy_page_range_from_document(&document, first_page_index..=last_page_index, 0)
+        .map_err(|err| PdfError::edit("Failed to copy pdf pages").caused_by(err))?;
+
+      let filename = if first_page_index == last_page_index {
+        format!("page-{}.pdf", first_page_index + 1)
+      } else {
+        format!("pages-{}-{}.pdf", first_page_index + 1, last_page_index + 1)
+      };
+      let output_path = Path::new(&output_folder_path).join(&filename);
+      save_document(part, &output_path.to_string_lossy())?;
+
+      result.push(SplitDocument {
+        filename,
+        first_page_index: first_page_index as u32,
+        page_count: (last_page_index - first_page_index + 1) as u32,
+      });
+    }
+
+    Ok(result)
+  })
+  .await
+}
+
+#[napi(catch_unwind)]
+/// Concatenate documents pages in the given order and save them into output path
+pub async fn merge_pdfs(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_paths: Vec<String>,
+  output_path: String,
+) -> napi::Result<()> {
+  run_blocking(move || {
+    if pdf_paths.is_empty() {
+      return Err(PdfError::new(ErrorCode::InvalidArgument, "No pdf documents to merge").into());
+    }
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+
+    let mut merged = pdfium
+      .create_new_pdf()
+      .map_err(|err| PdfError::edit("Failed to create pdf document").caused_by(err))?;
+    for pdf_path in &pdf_paths {
+      let document = load_document(pdfium, pdf_path)?;
+      merged.pages_mut().append(&document).map_err(|err| {
+        PdfError::edit(format!("Failed to copy {} pages", pdf_path)).caused_by(err)
+      })?;
+    }
+
+    save_document(merged, &output_path)
+  })
+  .await
+}
+
+#[napi(catch_unwind)]
+/// Create document with a page for each image in the given order and save it into output path
+pub async fn images_to_pdf(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  image_paths: Vec<String>,
+  output_path: String,
+  options: Option<ImagesToPdfOptions>,
+) -> napi::Result<()> {
+  run_blocking(move || {
+    if image_paths.is_empty() {
+      return Err(PdfError::new(ErrorCode::InvalidArgument, "No images to convert").into());
+    }
+    let options = options.unwrap_or_default();
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+
+    // page space is measured in points, 72 per inch
+    let scale = 72.0 / options.dpi.filter(|dpi| *dpi > 0.0).unwrap_or(72.0) as f32;
+    let margin = options.margin.unwrap_or(0.0).max(0.0) as f32;
+
+    let mut document = pdfium
+      .create_new_pdf()
+      .map_err(|err| PdfError::edit("Failed to create pdf document").caused_by(err))?;
+    for image_path in &image_paths {
+      let open_failed = || {
+        PdfError::new(
+          ErrorCode::LoadFailed,
+          format!("Failed to open image {}", image_path),
+        )
+      };
+      let data = std::fs::read(image_path).map_err(|err| open_failed().caused_by(err))?;
+      // JPEG data is embedded as is, other images and turned photos are decoded
+      let (image, (pixel_width, pixel_height)) = match upright_jpeg_size(&data) {
+        Some(size) => (None, size),
+        None => {
+          let image = oriented_image(&data).map_err(|err| open_failed().caused_by(err))?;
+          let size = (image.width(), image.height());
+          (Some(image), size)
+        }
+      };
+
+      let image_width = pixel_width as f32 * scale;
+      let image_height = pixel_height as f32 * scale;
+      let page_width = options
+        .page_width
+        .map_or(image_width + 2.0 * margin, |width| width as f32);
+      let page_height = options
+        .page_height
+        .map_or(image_height + 2.0 * margin, |height| height as f32);
+
+      // fit image into page inside margins keeping its aspect ratio, centered
+      let fit = ((page_width - 2.0 * margin) / image_width)
+        .min((page_height - 2.0 * margin) / image_height)
+        .min(1.0);
+      if fit <= 0.0 {
+        return Err(
+          PdfError::new(
+            ErrorCode::InvalidArgument,
+            "Margins leave no space for image",
+          )
+          .into(),
+        );
+      }
+      let (width, height) = (image_width * fit, image_height * fit);
+
+      let (x, y) = ((page_width - width) / 2.0, (page_height - height) / 2.0);
+      let mut page = document
+        .pages_mut()
+        .create_page_at_end(PdfPagePaperSize::new_custom(
+          PdfPoints::new(page_width),
+          PdfPoints::new(page_height),
+        ))
+        .map_err(|err| PdfError::edit("Failed to add image page").caused_by(err))?;
+      let added = match image {
+        Some(image) => PdfPageImageObject::new_with_size(
+          &document,
+          &image,
+          PdfPoints::new(width),
+          PdfPoints::new(height),
+        )
+        .and_then(|mut object| {
+          object
+            .translate(PdfPoints::new(x), PdfPoints::new(y))
+            .map(|_| object)
+        })
+        .and_then(|object| page.objects_mut().add_image_object(object))
+        .is_ok(),
+        None => add_jpeg_object(&document, &page, &data, (width, height, x, y)),
+      };
+      if !added {
+        return Err(PdfError::edit(format!("Failed to create image {}", image_path)).into());
+      }
+    }
+
+    save_document(document, &output_path)
+  })
+  .await
+}
+
+#[napi(catch_unwind)]
+/// Rotate selected pages clockwise by 90, 180 or 270 degrees on top of their current
+/// rotation and save document into output path
+pub async fn rotate_pages(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+  page_indices: Vec<u32>,
+  degrees: u32,
+  output_path: String,
+) -> napi::Result<()> {
+  run_blocking(move || {
+    if !degrees.is_multiple_of(90) {
+      return Err(
+        PdfError::new(
+          ErrorCode::InvalidArgument,
+          format!("Rotation {} is not a multiple of 90 degrees", degrees),
+        )
+        .into(),
+      );
+    }
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let document = load_document(pdfium, &pdf_path)?;
+    check_page_indices(&document, &page_indices)?;
+
+    for page_index in page_indices.iter().copied().collect::<BTreeSet<u32>>() {
+      let mut page = document
+        .pages()
+        .get(page_index as PdfPageIndex)
+        .map_err(|err| {
+          PdfError::page(page_index as usize, "Failed to load pdf page").caused_by(err)
+        })?;
+      let rotation = match (rotation_degrees(&page) + degrees) % 360 {
+        90 => PdfPageRenderRotation::Degrees90,
+        180 => PdfPageRenderRotation::Degrees180,
+        270 => PdfPageRenderRotation::Degrees270,
+        _ => PdfPageRenderRotation::None,
+      };
+      page.set_rotation(rotation);
+    }
+
+    save_document(document, &output_path)
+  })
+  .await
+}
+
+fn check_page_indices(document: &PdfDocument, page_indices: &[u32]) -> napi::Result<()> {
+  let total_pages = document.pages().len() as u32;
+  match page_indices.iter().find(|index| **index >= total_pages) {
+    Some(index) => Err(PdfError::page_out_of_range(*index as usize).into()),
+    None => Ok(()),
+  }
+}
+
+#[napi(catch_unwind)]
+/// Remove selected pages and save document into output path
+pub async fn remove_pages(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+  page_indices: Vec<u32>,
+  output_path: String,
+) -> napi::Result<()> {
+  run_blocking(move || {
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let document = load_document(pdfium, &pdf_path)?;
+    check_page_indices(&document, &page_indices)?;
+
+    let page_indices: BTreeSet<u32> = page_indices.into_iter().collect();
+    if page_indices.len() == document.pages().len() as usize {
+      return Err(
+        PdfError::new(
+          ErrorCode::InvalidArgument,
+          "Can not remove all document pages",
+        )
+        .into(),
+      );
+    }
+    // remove from the end so remaining indices stay valid
+    for page_index in page_indices.into_iter().rev() {
+      document
+        .pages()
+        .get(page_index as PdfPageIndex)
+        .and_then(|page| page.delete())
+        .map_err(|err| {
+          PdfError::edit("Failed to remove pdf page")
+            .on_page(page_index as usize)
+            .caused_by(err)
+        })?;
+    }
+
+    save_document(document, &output_path)
+  })
+  .await
+}
+
+#[napi(catch_unwind)]
+/// Save document with pages in given order into output path, the order lists every source
+/// page index once
+pub async fn reorder_pages(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+  page_order: Vec<u32>,
+  output_path: String,
+) -> napi::Result<()> {
+  run_blocking(move || {
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let bindings = pdfium.bindings();
+    // pages are moved in the document itself which keeps its outline, forms and attachments
+    let raw_document = RawDocument::new(bindings, &pdf_path);
+    let document = raw_document.loaded_handle()?;
+    let total_pages = bindings.FPDF_GetPageCount(document) as u32;
+    if let Some(index) = page_order.iter().find(|index| **index >= total_pages) {
+      return Err(PdfError::page_out_of_range(*index as usize).into());
+    }
+
+    let unique_pages: BTreeSet<u32> = page_order.iter().copied().collect();
+    if page_order.len() != total_pages as usize || unique_pages.len() != page_order.len() {
+      return Err(
+        PdfError::new(
+          ErrorCode::InvalidArgument,
+          "Page order must list every page once",
+        )
+        .into(),
+      );
+    }
+
+    let page_indices: Vec<c_int> = page_order.iter().map(|index| *index as c_int).collect();
+    if !bindings.is_true(bindings.FPDF_MovePages(
+      document,
+      page_indices.as_ptr(),
+      page_indices.len() as c_ulong,
+      0,
+    )) {
+      return Err(PdfError::edit("Failed to move pdf pages").into());
+    }
+    let data = saved_data(bindings, document, 0)
+      .ok_or_else(|| PdfError::new(ErrorCode::SaveFailed, "Failed to save pdf document"))?;
+    // source is closed before writing, output path may be the source itself
+    drop(raw_document);
+    std::fs::write(&output_path, data).map_err(|err| {
+      PdfError::new(ErrorCode::SaveFailed, "Failed to write pdf document").caused_by(err)
+    })?;
+    Ok(())
+  })
+  .await
+}
+
+/// Decoded image turned upright by its EXIF orientation, as photos from cameras are stored
+pub fn open_oriented_image(image_path: &str) -> image::ImageResult<DynamicImage> {
+  decode_oriented(ImageReader::open(image_path)?.with_guessed_format()?)
+}
+
+/// Decoded image data turned upright by its EXIF orientation
+fn oriented_image(data: &[u8]) -> image::ImageResult<DynamicImage> {
+  decode_oriented(ImageReader::new(Cursor::new(data)).with_guessed_format()?)
+}
+
+fn decode_oriented<R: BufRead + Seek>(reader: ImageReader<R>) -> image::ImageResult<DynamicImage> {
+  let mut decoder = reader.into_decoder()?;
+  let orientation = decoder.orientation()?;
+  let mut image = DynamicImage::from_decoder(decoder)?;
+  image.apply_orientation(orientation);
+  Ok(image)
+}
+
+/// Width and height of JPEG image data displayed as stored, none for other formats and photos
+/// turned by their EXIF orientation
+fn upright_jpeg_size(data: &[u8]) -> Option<(u32, u32)> {
+  let reader = ImageReader::new(Cursor::new(data))
+    .with_guessed_format()
+    .ok()?;
+  if reader.format() != Some(ImageFormat::Jpeg) {
+    return None;
+  }
+  let mut decoder = reader.into_decoder().ok()?;
+  let orientation = decoder.orientation().ok()?;
+  (orientation == Orientation::NoTransforms).then(|| decoder.dimensions())
+}
+
+/// Add image object with JPEG data embedded as DCT encoded image to page, placed in page
+/// space at left and bottom with width and height
+fn add_jpeg_object(
+  document: &PdfDocument,
+  page: &PdfPage,
+  data: &[u8],
+  (width, height, left, bottom): (f32, f32, f32, f32),
+) -> bool {
+  let bindings = document.bindings();
+  let object = bindings.FPDFPageObj_NewImageObj(bindings.get_handle_from_document(document));
+  if object.is_null() {
+    return false;
+  }
+  let mut page_handle = bindings.get_handle_from_page(page);
+  let mut file_access = FileAccess {
+    length: data.len() as c_ulong,
+    get_block: Some(get_block),
+    data: data.as_ptr(),
+  };
+  if !bindings.is_true(bindings.FPDFImageObj_LoadJpegFileInline(
+    &mut page_handle,
+    1,
+    object,
+    &mut file_access as *mut FileAccess as *mut _,
+  )) {
+    bindings.FPDFPageObj_Destroy(object);
+    return false;
+  }
+  // image is drawn into unit square scaled by its matrix
+  bindings.FPDFPageObj_Transform(
+    object,
+    width as f64,
+    0.0,
+    0.0,
+    height as f64,
+    left as f64,
+    bottom as f64,
+  );
+  bindings.FPDFPage_InsertObject(page_handle, object);
+  bindings.is_true(bindings.FPDFPage_GenerateContent(page_handle))
+}
+
+/// Data reader layout of pdfium `FPDF_FILEACCESS` over data in memory
+#[repr(C)]
+struct FileAccess {
+  length: c_ulong,
+  get_block: Option<unsafe extern "C" fn(*mut c_void, c_ulong, *mut u8, c_ulong) -> c_int>,
+  data: *const u8,
+}
+
+unsafe extern "C" fn get_block(
+  file_access: *mut c_void,
+  position: c_ulong,
+  buffer: *mut u8,
+  size: c_ulong,
+) -> c_int {
+  // SAFETY: pdfium passes back the data pointer given to it with a range inside the data length
+  let data = file_access as *const u8;
+  std::ptr::copy_nonoverlapping(data.add(position as usize), buffer, size as usize);
+  1
+}
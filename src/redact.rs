@@ -0,0 +1,267 @@
+use pdfium_render::prelude::*;
+use regex::Regex;
+
+use crate::errors::{ErrorCode, PdfError};
+use crate::options::RedactOptions;
+use crate::{get_pdfium, load_document, run_blocking, save_document};
+
+#[napi(object)]
+/// Redacted text with its bounding rectangle in page space points
+pub struct RedactedMatch {
+  pub page_index: u32,
+  pub text: String,
+  pub left: f64,
+  pub top: f64,
+  pub right: f64,
+  pub bottom: f64,
+}
+
+// page character with the text object it is drawn by
+struct PageChar {
+  text: String,
+  origin: Option<(PdfPoints, PdfPoints)>,
+  bounds: Option<PdfRect>,
+  // pdfium-render does not expose object handles, objects are told apart by matrix and text
+  object: Option<(PdfMatrix, String)>,
+  is_redacted: bool,
+}
+
+#[napi(catch_unwind)]
+/// Remove text matching terms or patterns from page content, cover it with black rectangles
+/// and save document into output path
+pub async fn redact_text(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+  options: RedactOptions,
+  output_path: String,
+) -> napi::Result<Vec<RedactedMatch>> {
+  run_blocking(move || {
+    let mut patterns: Vec<Regex> = vec![];
+    let terms = options
+      .terms
+      .iter()
+      .flatten()
+      .map(|term| regex::escape(term));
+    for pattern in terms.chain(options.patterns.iter().flatten().cloned()) {
+      patterns.push(Regex::new(&pattern).map_err(|_| {
+        PdfError::new(
+          ErrorCode::InvalidArgument,
+          format!("Invalid redaction pattern {}", pattern),
+        )
+      })?);
+    }
+
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let document = load_document(pdfium, &pdf_path)?;
+
+    let total_pages = document.pages().len() as u32;
+    let pages = options
+      .pages
+      .clone()
+      .unwrap_or_else(|| (0..total_pages).collect());
+    if let Some(page_index) = pages.iter().find(|index| **index >= total_pages) {
+      return Err(PdfError::page_out_of_range(*page_index as usize).into());
+    }
+
+    let mut result: Vec<RedactedMatch> = vec![];
+
+    for page_index in pages {
+      let mut page = document
+        .pages()
+        .get(page_index as PdfPageIndex)
+        .map_err(|err| {
+          PdfError::page(page_index as usize, "Failed to load pdf page").caused_by(err)
+        })?;
+
+      let (mut chars, matches) = {
+        let text = page.text().map_err(|err| {
+          PdfError::page(page_index as usize, "Failed to load page text").caused_by(err)
+        })?;
+        let chars: Vec<PageChar> = text
+          .chars()
+          .iter()
+          .map(|char| PageChar {
+            text: char.unicode_string().unwrap_or_default(),
+            origin: char.origin().ok(),
+            bounds: char.loose_bounds().ok(),
+            object: char
+              .text_object()
+              .ok()
+              .and_then(|object| Some((object.matrix().ok()?, object.text()))),
+            is_redacted: false,
+          })
+          .collect();
+        let matches = char_matches(&chars, &patterns);
+        (chars, matches)
+      };
+      if matches.is_empty() {
+        continue;
+      }
+
+      let mut rects: Vec<PdfRect> = vec![];
+      for (start, end) in matches {
+        let matched = &mut chars[start..end];
+        matched.iter_mut().for_each(|char| char.is_redacted = true);
+
+        // one rectangle for each text object the match is drawn by
+        for chunk in matched.chunk_by(|a, b| a.object == b.object) {
+          if let Some(rect) = union(chunk.iter().filter_map(|char| char.bounds)) {
+            rects.push(rect);
+          }
+        }
+        if let Some(rect) = union(matched.iter().filter_map(|char| char.bounds)) {
+          result.push(RedactedMatch {
+            page_index,
+            text: matched.iter().map(|char| char.text.as_str()).collect(),
+            left: rect.left().value as f64,
+            top: rect.top().value as f64,
+            right: rect.right().value as f64,
+            bottom: rect.bottom().value as f64,
+          });
+        }
+      }
+
+      // replace text objects with redacted characters by objects drawing the remaining runs
+      let mut redacted_objects: Vec<PdfPageObjectIndex> = vec![];
+      let mut removed_keys: Vec<Option<(PdfMatrix, String)>> = vec![];
+      let mut replacements: Vec<PdfPageTextObject> = vec![];
+      for (object_index, object) in page.objects().iter().enumerate() {
+        let object = match object.as_text_object() {
+          Some(object) => object,
+          None => continue,
+        };
+        let key = match object.matrix() {
+          Ok(matrix) => Some((matrix, object.text())),
+          Err(_) => continue,
+        };
+        let object_chars: Vec<&PageChar> = chars.iter().filter(|char| char.object == key).collect();
+        if !object_chars.iter().any(|char| char.is_redacted) {
+          continue;
+        }
+        redacted_objects.push(object_index);
+        removed_keys.push(key.clone());
+
+        let matrix = key.map(|(matrix, _)| matrix).unwrap_or(PdfMatrix::IDENTITY);
+        for run in object_chars
+          .chunk_by(|a, b| a.is_redacted == b.is_redacted)
+          .filter(|run| !run[0].is_redacted)
+        {
+          let (x, y) = match run[0].origin {
+            Some(origin) => origin,
+            None => continue,
+          };
+          let text: String = run.iter().map(|char| char.text.as_str()).collect();
+          let mut replacement =
+            PdfPageTextObject::new(&document, text, object.font(), object.unscaled_font_size())
+              .map_err(|err| {
+                PdfError::edit("Failed to create text object")
+                  .on_page(page_index as usize)
+                  .caused_by(err)
+              })?;
+          replacement
+            .set_render_mode(object.render_mode())
+            .and_then(|_| replacement.set_fill_color(object.fill_color()?))
+            .and_then(|_| {
+              replacement.apply_matrix(PdfMatrix::new(
+                matrix.a(),
+                matrix.b(),
+                matrix.c(),
+                matrix.d(),
+                x.value,
+                y.value,
+              ))
+            })
+            .map_err(|err| {
+              PdfError::edit("Failed to create text object")
+                .on_page(page_index as usize)
+                .caused_by(err)
+            })?;
+          replacements.push(replacement);
+        }
+      }
+
+      // text drawn by form XObjects can not be removed through pdfium-render, covering it would
+      // leave it extractable
+      if chars
+        .iter()
+        .any(|char| char.is_redacted && !removed_keys.contains(&char.object))
+      {
+        return Err(
+          PdfError::page(
+            page_index as usize,
+            "Matched text drawn inside form XObject can not be removed",
+          )
+          .into(),
+        );
+      }
+
+      let objects = page.objects_mut();
+      for object_index in redacted_objects.into_iter().rev() {
+        objects
+          .remove_object_at_index(object_index)
+          .map_err(|err| {
+            PdfError::edit("Failed to remove text object")
+              .on_page(page_index as usize)
+              .caused_by(err)
+          })?;
+      }
+      for replacement in replacements {
+        objects.add_text_object(replacement).map_err(|err| {
+          PdfError::edit("Failed to add text object")
+            .on_page(page_index as usize)
+            .caused_by(err)
+        })?;
+      }
+      for rect in rects {
+        let cover = PdfPagePathObject::new_rect(&document, rect, None, None, Some(PdfColor::BLACK))
+          .map_err(|err| {
+            PdfError::edit("Failed to create redaction rectangle")
+              .on_page(page_index as usize)
+              .caused_by(err)
+          })?;
+        objects.add_path_object(cover).map_err(|err| {
+          PdfError::edit("Failed to add redaction rectangle")
+            .on_page(page_index as usize)
+            .caused_by(err)
+        })?;
+      }
+    }
+
+    save_document(document, &output_path)?;
+
+    Ok(result)
+  })
+  .await
+}
+
+/// Ranges of matched characters in page text
+fn char_matches(chars: &[PageChar], patterns: &[Regex]) -> Vec<(usize, usize)> {
+  let mut text = String::new();
+  let mut char_starts: Vec<usize> = vec![];
+  for char in chars {
+    char_starts.push(text.len());
+    text.push_str(&char.text);
+  }
+  let char_at = |offset: usize| char_starts.partition_point(|start| *start < offset);
+
+  let mut result: Vec<(usize, usize)> = patterns
+    .iter()
+    .flat_map(|pattern| pattern.find_iter(&text))
+    .filter(|found| !found.is_empty())
+    .map(|found| (char_at(found.start()), char_at(found.end())))
+    .collect();
+  result.sort();
+  result
+}
+
+fn union(rects: impl Iterator<Item = PdfRect>) -> Option<PdfRect> {
+  rects.reduce(|a, b| {
+    PdfRect::new(
+      a.bottom().min(b.bottom()),
+      a.left().min(b.left()),
+      a.top().max(b.top()),
+      a.right().max(b.right()),
+    )
+  })
+}
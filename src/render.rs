@@ -0,0 +1,568 @@
+use image::{DynamicImage, Rgba, RgbaImage};
+use napi::bindgen_prelude::Buffer;
+use pdfium_render::prelude::*;
+use std::fs::create_dir_all;
+use std::num::NonZeroUsize;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+
+use crate::errors::{ErrorCode, PdfError};
+use crate::images::encode_image;
+use crate::options::{CompareOptions, ImageOutputFormat, RenderOptions};
+use crate::{get_pdfium, load_document, run_blocking, PageRect};
+
+// default pages rendering resolution
+static DEFAULT_RENDER_DPI: f32 = 150.0;
+// default documents comparison rendering resolution
+static DEFAULT_COMPARE_DPI: f64 = 72.0;
+// default largest color channel difference of pixels considered same, hides antialiasing noise
+static DEFAULT_COMPARE_THRESHOLD: u32 = 16;
+// differing pixels color in diff images
+static DIFF_COLOR: Rgba<u8> = Rgba([255, 0, 0, 255]);
+// default side of square tiles in pixels, common for deep zoom viewers
+static DEFAULT_TILE_SIZE: u32 = 256;
+
+#[napi(object)]
+/// Rendered page image
+pub struct RenderedPage {
+  pub page_index: u32,
+  /// Image filename
+  pub filename: String,
+  /// Image width in pixels
+  pub width: u32,
+  /// Image height in pixels
+  pub height: u32,
+  pub file_size_bytes: u32,
+  /// Encoded image, set when pages are returned as buffers instead of saved
+  pub data: Option<Buffer>,
+}
+
+#[napi(object)]
+/// Rendered page tile
+pub struct RenderedTile {
+  pub page_index: u32,
+  /// Tile row counted from the page top
+  pub row: u32,
+  /// Tile column counted from the page left
+  pub column: u32,
+  /// Tile left offset in the whole page image pixels
+  pub x: u32,
+  /// Tile top offset in the whole page image pixels
+  pub y: u32,
+  /// Image filename
+  pub filename: String,
+  /// Tile width in pixels, smaller than tile size at the right page edge
+  pub width: u32,
+  /// Tile height in pixels, smaller than tile size at the bottom page edge
+  pub height: u32,
+  pub file_size_bytes: u32,
+  /// Encoded image, set when tiles are returned as buffers instead of saved
+  pub data: Option<Buffer>,
+}
+
+#[napi(object)]
+/// Visual difference of document pages
+pub struct PageDifference {
+  pub page_index: u32,
+  /// Percentage of differing pixels from 0 to 100, 100 for pages missing in one of documents
+  pub difference_percent: f64,
+  /// Page image with differing pixels highlighted, set when diff images are requested for pages
+  /// present in both documents
+  pub diff_image: Option<RenderedPage>,
+}
+
+#[napi(catch_unwind)]
+/// Render pdf pages into images
+pub async fn render_pages(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+  options: Option<RenderOptions>,
+) -> napi::Result<Vec<RenderedPage>> {
+  run_blocking(move || {
+    let options = options.unwrap_or_default();
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let document = load_document(pdfium, &pdf_path)?;
+    prepare_output_folder(&options)?;
+
+    let config = PdfRenderConfig::new().scale_page_by_factor(render_scale(&options));
+
+    let page_indices: Vec<u32> = match &options.pages {
+      Some(pages) => pages.clone(),
+      None => (0..document.pages().len() as u32).collect(),
+    };
+
+    let mut result: Vec<RenderedPage> = vec![];
+
+    for page_index in page_indices {
+      let page = get_page(&document, page_index)?;
+
+      let image = page
+        .render_with_config(&config)
+        .map_err(|err| {
+          PdfError::page(page_index as usize, "Failed to render pdf page").caused_by(err)
+        })?
+        .as_image();
+
+      let filename = format!("page-{}", page_index + 1);
+      result.push(save_rendered_image(image, page_index, filename, &options)?);
+    }
+
+    Ok(result)
+  })
+  .await
+}
+
+#[napi(catch_unwind)]
+/// Render all pdf pages into images, pages are rendered one by one while their images are
+/// encoded and saved in parallel
+pub async fn render_document(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+  options: Option<RenderOptions>,
+) -> napi::Result<Vec<RenderedPage>> {
+  run_blocking(move || {
+    let options = options.unwrap_or_default();
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let document = load_document(pdfium, &pdf_path)?;
+    prepare_output_folder(&options)?;
+
+    let config = PdfRenderConfig::new().scale_page_by_factor(render_scale(&options));
+
+    let page_indices: Vec<u32> = match &options.pages {
+      Some(pages) => pages.clone(),
+      None => (0..document.pages().len() as u32).collect(),
+    };
+    let concurrency = options
+      .concurrency
+      .filter(|concurrency| *concurrency > 0)
+      .map(|concurrency| concurrency as usize)
+      .unwrap_or_else(|| thread::available_parallelism().map_or(1, NonZeroUsize::get));
+
+    // rendered images wait for encoding in queue bounded by workers count, the receiver is dropped
+    // with the last worker so rendering stops when all of them exit
+    let (sender, receiver) = mpsc::sync_channel::<(usize, u32, DynamicImage)>(concurrency);
+    let receiver = Arc::new(Mutex::new(receiver));
+    let is_failed = AtomicBool::new(false);
+
+    thread::scope(|scope| {
+      let workers: Vec<_> = (0..concurrency)
+        .map(|_| {
+          let receiver = Arc::clone(&receiver);
+          let (options, is_failed) = (&options, &is_failed);
+          scope.spawn(move || -> napi::Result<Vec<(usize, RenderedPage)>> {
+            let mut pages = vec![];
+            loop {
+              let next = receiver
+                .lock()
+                .unwrap_or_else(|err| err.into_inner())
+                .recv();
+              let (order, page_index, image) = match next {
+                Ok(next) => next,
+                // all pages are rendered
+                Err(_) => return Ok(pages),
+              };
+              let filename = format!("page-{}", page_index + 1);
+              match save_rendered_image(image, page_index, filename, options) {
+                Ok(page) => pages.push((order, page)),
+                Err(err) => {
+                  is_failed.store(true, Ordering::Relaxed);
+                  return Err(err);
+                }
+              }
+            }
+          })
+        })
+        .collect();
+      drop(receiver);
+
+      // pdfium is used by this thread only
+      let mut rendered = Ok(());
+      for (order, page_index) in page_indices.iter().enumerate() {
+        // remaining pages are not rendered after the first failed one
+        if is_failed.load(Ordering::Relaxed) {
+          break;
+        }
+        let image = get_page(&document, *page_index).and_then(|page| {
+          page
+            .render_with_config(&config)
+            .map(|bitmap| bitmap.as_image())
+            .map_err(|err| {
+              PdfError::page(*page_index as usize, "Failed to render pdf page")
+                .caused_by(err)
+                .into()
+            })
+        });
+        match image {
+          Ok(image) => {
+            // sending fails only when all workers have exited on errors
+            if sender.send((order, *page_index, image)).is_err() {
+              break;
+            }
+          }
+          Err(err) => {
+            rendered = Err(err);
+            break;
+          }
+        }
+      }
+      drop(sender);
+
+      let mut result = vec![];
+      let mut error = None;
+      for worker in workers {
+        match worker.join() {
+          Ok(Ok(pages)) => result.extend(pages),
+          Ok(Err(err)) => {
+            error.get_or_insert(err);
+          }
+          Err(_) => {
+            error.get_or_insert(
+              PdfError::new(ErrorCode::ImageSaveFailed, "Failed to encode page image").into(),
+            );
+          }
+        }
+      }
+      if let Some(err) = error {
+        return Err(err);
+      }
+      rendered?;
+
+      result.sort_by_key(|(order, _)| *order);
+      Ok(result.into_iter().map(|(_, page)| page).collect())
+    })
+  })
+  .await
+}
+
+#[napi(catch_unwind)]
+/// Render rectangular region of pdf page into image
+pub async fn render_region(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+  page_index: u32,
+  // Region in page space points, origin at the bottom left page corner
+  region: PageRect,
+  options: Option<RenderOptions>,
+) -> napi::Result<RenderedPage> {
+  run_blocking(move || {
+    let options = options.unwrap_or_default();
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let document = load_document(pdfium, &pdf_path)?;
+    prepare_output_folder(&options)?;
+
+    let page = get_page(&document, page_index)?;
+
+    let scale = render_scale(&options);
+    let width = ((region.right - region.left) as f32 * scale).round() as Pixels;
+    let height = ((region.top - region.bottom) as f32 * scale).round() as Pixels;
+    if width <= 0 || height <= 0 {
+      return Err(PdfError::new(ErrorCode::InvalidArgument, "Region is empty").into());
+    }
+
+    let image = render_clip(
+      pdfium,
+      &page,
+      page_index,
+      // Move region to the bitmap origin before scaling, rendering y axis points down
+      (region.left as f32, page.height().value - region.top as f32),
+      scale,
+      (width, height),
+    )?;
+
+    let filename = format!(
+      "page-{}-region-{}-{}-{}-{}",
+      page_index + 1,
+      region.left.round(),
+      region.bottom.round(),
+      region.right.round(),
+      region.top.round()
+    );
+    save_rendered_image(image, page_index, filename, &options)
+  })
+  .await
+}
+
+#[napi(catch_unwind)]
+/// Render pdf page into grid of square tiles, only one tile bitmap is kept in memory at a time
+pub async fn render_tiles(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+  page_index: u32,
+  options: Option<RenderOptions>,
+) -> napi::Result<Vec<RenderedTile>> {
+  run_blocking(move || {
+    let options = options.unwrap_or_default();
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let document = load_document(pdfium, &pdf_path)?;
+    prepare_output_folder(&options)?;
+
+    let page = get_page(&document, page_index)?;
+
+    let tile_size = options.tile_size.unwrap_or(DEFAULT_TILE_SIZE);
+    if tile_size == 0 {
+      return Err(PdfError::new(ErrorCode::InvalidArgument, "Tile size must be positive").into());
+    }
+    let scale = render_scale(&options);
+    let page_width = (page.width().value * scale).round() as u32;
+    let page_height = (page.height().value * scale).round() as u32;
+
+    let mut result = vec![];
+    for row in 0..page_height.div_ceil(tile_size) {
+      for column in 0..page_width.div_ceil(tile_size) {
+        let x = column * tile_size;
+        let y = row * tile_size;
+        let width = tile_size.min(page_width - x);
+        let height = tile_size.min(page_height - y);
+
+        let image = render_clip(
+          pdfium,
+          &page,
+          page_index,
+          (x as f32 / scale, y as f32 / scale),
+          scale,
+          (width as Pixels, height as Pixels),
+        )?;
+
+        let filename = format!("page-{}-tile-{}-{}", page_index + 1, row, column);
+        let tile = save_rendered_image(image, page_index, filename, &options)?;
+        result.push(RenderedTile {
+          page_index,
+          row,
+          column,
+          x,
+          y,
+          filename: tile.filename,
+          width: tile.width,
+          height: tile.height,
+          file_size_bytes: tile.file_size_bytes,
+          data: tile.data,
+        });
+      }
+    }
+
+    Ok(result)
+  })
+  .await
+}
+
+#[napi(catch_unwind)]
+/// Compare rendered pages of two pdf documents pixel by pixel, catching layout and graphics
+/// changes not visible in text
+pub async fn compare_pages(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+  other_pdf_path: String,
+  options: Option<CompareOptions>,
+) -> napi::Result<Vec<PageDifference>> {
+  run_blocking(move || {
+    let options = options.unwrap_or_default();
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let document = load_document(pdfium, &pdf_path)?;
+    let other_document = load_document(pdfium, &other_pdf_path)?;
+
+    let diff_images = options.diff_images.unwrap_or(false);
+    let image_options = RenderOptions {
+      format: options.format,
+      output_folder_path: options.output_folder_path.clone(),
+      as_buffers: options.as_buffers,
+      ..Default::default()
+    };
+    if diff_images {
+      prepare_output_folder(&image_options)?;
+    }
+
+    let scale = (options.dpi.unwrap_or(DEFAULT_COMPARE_DPI) / 72.0) as f32;
+    let config = PdfRenderConfig::new().scale_page_by_factor(scale);
+    let threshold = options.threshold.unwrap_or(DEFAULT_COMPARE_THRESHOLD);
+
+    let page_count = document.pages().len().max(other_document.pages().len()) as u32;
+    let mut result = vec![];
+    for page_index in 0..page_count {
+      let render = |document: &PdfDocument| -> napi::Result<Option<RgbaImage>> {
+        if page_index >= document.pages().len() as u32 {
+          return Ok(None);
+        }
+        let image = get_page(document, page_index)?
+          .render_with_config(&config)
+          .map_err(|err| {
+            PdfError::page(page_index as usize, "Failed to render pdf page").caused_by(err)
+          })?
+          .as_image();
+        Ok(Some(image.to_rgba8()))
+      };
+
+      let (image, other_image) = match (render(&document)?, render(&other_document)?) {
+        (Some(image), Some(other_image)) => (image, other_image),
+        _ => {
+          result.push(PageDifference {
+            page_index,
+            difference_percent: 100.0,
+            diff_image: None,
+          });
+          continue;
+        }
+      };
+
+      // pages of different sizes are compared over both of them
+      let width = image.width().max(other_image.width());
+      let height = image.height().max(other_image.height());
+      let mut differing_count = 0u64;
+      let diff = RgbaImage::from_fn(width, height, |x, y| {
+        let pixel = image.get_pixel_checked(x, y);
+        let is_same = match (pixel, other_image.get_pixel_checked(x, y)) {
+          (Some(pixel), Some(other_pixel)) => pixel
+            .0
+            .iter()
+            .zip(other_pixel.0)
+            .all(|(channel, other_channel)| channel.abs_diff(other_channel) as u32 <= threshold),
+          _ => false,
+        };
+        if !is_same {
+          differing_count += 1;
+          return DIFF_COLOR;
+        }
+        // faded page keeps same content recognizable behind highlighted pixels
+        let [red, green, blue, _] = pixel.map_or([255; 4], |pixel| pixel.0);
+        let luma = (red as u32 * 299 + green as u32 * 587 + blue as u32 * 114) / 1000;
+        let faded = (255 - (255 - luma) / 3) as u8;
+        Rgba([faded, faded, faded, 255])
+      });
+
+      let difference_percent = differing_count as f64 * 100.0 / (width as f64 * height as f64);
+      let diff_image = if diff_images {
+        let filename = format!("page-{}-diff", page_index + 1);
+        Some(save_rendered_image(
+          DynamicImage::ImageRgba8(diff),
+          page_index,
+          filename,
+          &image_options,
+        )?)
+      } else {
+        None
+      };
+
+      result.push(PageDifference {
+        page_index,
+        difference_percent,
+        diff_image,
+      });
+    }
+
+    Ok(result)
+  })
+  .await
+}
+
+fn render_scale(options: &RenderOptions) -> f32 {
+  let dpi = options
+    .dpi
+    .map(|dpi| dpi as f32)
+    .unwrap_or(DEFAULT_RENDER_DPI);
+  // page space is measured in points, 72 per inch
+  dpi / 72.0
+}
+
+fn prepare_output_folder(options: &RenderOptions) -> napi::Result<()> {
+  if options.as_buffers.unwrap_or(false) {
+    return Ok(());
+  }
+  match options.output_folder_path.as_deref() {
+    // Create output folder if not exist
+    Some(output_folder_path) if !output_folder_path.is_empty() => {
+      create_dir_all(output_folder_path).map_err(|err| {
+        PdfError::new(ErrorCode::ImageSaveFailed, "Failed to create output folder").caused_by(err)
+      })?;
+      Ok(())
+    }
+    _ => Err(PdfError::new(ErrorCode::InvalidArgument, "Output folder path is required").into()),
+  }
+}
+
+/// Render part of page into bitmap of given size, offset is in page points from the top left
+/// page corner
+fn render_clip(
+  pdfium: &Pdfium,
+  page: &PdfPage,
+  page_index: u32,
+  (left, top): (f32, f32),
+  scale: f32,
+  (width, height): (Pixels, Pixels),
+) -> napi::Result<DynamicImage> {
+  let config = PdfRenderConfig::new()
+    .translate(PdfPoints::new(-left), PdfPoints::new(-top))
+    .map_err(|err| PdfError::new(ErrorCode::InvalidArgument, "Invalid region").caused_by(err))?
+    .scale_page_by_factor(scale)
+    .clip(0, 0, width, height);
+
+  let mut bitmap = PdfBitmap::empty(width, height, PdfBitmapFormat::default(), pdfium.bindings())
+    .map_err(|err| {
+    PdfError::page(page_index as usize, "Failed to allocate region bitmap").caused_by(err)
+  })?;
+  page
+    .render_into_bitmap_with_config(&mut bitmap, &config)
+    .map_err(|err| {
+      PdfError::page(page_index as usize, "Failed to render pdf page").caused_by(err)
+    })?;
+  Ok(bitmap.as_image())
+}
+
+fn get_page<'a>(document: &'a PdfDocument, page_index: u32) -> napi::Result<PdfPage<'a>> {
+  document
+    .pages()
+    .get(page_index as PdfPageIndex)
+    .map_err(|err| {
+      PdfError::page(page_index as usize, "Failed to read pdf page")
+        .caused_by(err)
+        .into()
+    })
+}
+
+/// Encode rendered image and save it into output folder or keep it to return as buffer
+fn save_rendered_image(
+  image: DynamicImage,
+  page_index: u32,
+  filename: String,
+  options: &RenderOptions,
+) -> napi::Result<RenderedPage> {
+  let format = options.format.unwrap_or(ImageOutputFormat::Png);
+  let quality = options.quality.map(|q| q.min(100) as u8);
+
+  let data = encode_image(&image, format, quality).map_err(|err| {
+    PdfError::new(ErrorCode::ImageSaveFailed, "Failed to encode page image")
+      .on_page(page_index as usize)
+      .caused_by(err)
+  })?;
+
+  let filename = format!("{}.{}", filename, format.extension());
+  let file_size_bytes = data.len() as u32;
+  let data = if options.as_buffers.unwrap_or(false) {
+    Some(Buffer::from(data))
+  } else {
+    let output_folder_path = Path::new(options.output_folder_path.as_deref().unwrap_or_default());
+    std::fs::write(output_folder_path.join(&filename), data).map_err(|err| {
+      PdfError::new(
+        ErrorCode::ImageSaveFailed,
+        format!("Failed to save image {}", filename),
+      )
+      .on_page(page_index as usize)
+      .caused_by(err)
+    })?;
+    None
+  };
+
+  Ok(RenderedPage {
+    page_index,
+    filename,
+    width: image.width(),
+    height: image.height(),
+    file_size_bytes,
+    data,
+  })
+}
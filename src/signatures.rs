@@ -0,0 +1,208 @@
+use pdfium_render::prelude::*;
+use std::fs;
+
+use crate::errors::{ErrorCode, PdfError};
+use crate::objects::{find, find_last};
+use crate::{get_pdfium, load_document, run_blocking};
+
+#[napi(object)]
+/// Document signature fields and applied signatures
+pub struct DocumentSignatures {
+  /// Document has signature form fields, signed or not
+  pub has_signature_fields: bool,
+  pub signatures: Vec<ExtractedSignature>,
+}
+
+#[napi(object)]
+/// Applied digital signature, not verified
+pub struct ExtractedSignature {
+  /// Signer name written into signature dictionary, certificate subject is not read
+  pub signer_name: Option<String>,
+  /// Signing time in PDF date format like "D:20240102030405Z"
+  pub signing_date: Option<String>,
+  pub reason: Option<String>,
+  /// Signed byte ranges as offset and length pairs
+  pub byte_range: Vec<u32>,
+  /// Signed bytes reach the end of file, false when document was changed after signing
+  pub covers_whole_document: bool,
+}
+
+// signature dictionary parsed from raw document bytes
+struct SignatureDictionary {
+  contents: Vec<u8>,
+  byte_range: Vec<u32>,
+  signer_name: Option<String>,
+}
+
+#[napi(catch_unwind)]
+/// Detect signature fields and read applied signatures details
+pub async fn extract_signatures(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+) -> napi::Result<DocumentSignatures> {
+  run_blocking(move || {
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let document = load_document(pdfium, &pdf_path)?;
+
+    let has_signature_fields = document.pages().iter().any(|page| {
+      page.annotations().iter().any(|annotation| {
+        annotation
+          .as_form_field()
+          .is_some_and(|field| field.field_type() == PdfFormFieldType::Signature)
+      })
+    });
+
+    // pdfium does not expose byte range and signer name, signature dictionaries are never
+    // compressed as their contents are excluded from signed bytes by offsets in file
+    let data = fs::read(&pdf_path).map_err(|err| {
+      PdfError::new(ErrorCode::LoadFailed, "Failed to open pdf document").caused_by(err)
+    })?;
+    let dictionaries = signature_dictionaries(&data);
+
+    let signatures = document
+      .signatures()
+      .iter()
+      .filter_map(|signature| {
+        // pdfium lists signature fields not signed yet
+        let contents = Some(signature.bytes()).filter(|contents| !contents.is_empty())?;
+        let dictionary = dictionaries.iter().find(|d| d.contents == contents);
+        let byte_range = dictionary.map(|d| d.byte_range.clone()).unwrap_or_default();
+        let covers_whole_document = byte_range
+          .chunks_exact(2)
+          .last()
+          .is_some_and(|range| range[0] as usize + range[1] as usize == data.len());
+
+        Some(ExtractedSignature {
+          signer_name: dictionary.and_then(|d| d.signer_name.clone()),
+          signing_date: signature.signing_date().filter(|date| !date.is_empty()),
+          reason: signature.reason().filter(|reason| !reason.is_empty()),
+          byte_range,
+          covers_whole_document,
+        })
+      })
+      .collect();
+
+    Ok(DocumentSignatures {
+      has_signature_fields,
+      signatures,
+    })
+  })
+  .await
+}
+
+fn signature_dictionaries(data: &[u8]) -> Vec<SignatureDictionary> {
+  let mut result: Vec<SignatureDictionary> = vec![];
+
+  let mut search_from = 0;
+  while let Some(position) = find(&data[search_from..], b"/ByteRange").map(|i| search_from + i) {
+    search_from = position + 1;
+
+    let byte_range = match parse_byte_range(&data[position..]) {
+      Some(byte_range) => byte_range,
+      None => continue,
+    };
+    // contents hex string fills the gap between the first two ranges
+    let (gap_start, gap_end) = (byte_range[1] as usize, byte_range[2] as usize);
+    if gap_start >= gap_end || gap_end > data.len() {
+      continue;
+    }
+    let contents = decode_hex(&data[gap_start..gap_end]);
+
+    // signature object around contents
+    let object_start = find_last(&data[..gap_start], b" obj").unwrap_or(0);
+    let object_end = find(&data[gap_end..], b"endobj").map_or(data.len(), |i| gap_end + i);
+    let signer_name = string_value(&data[object_start..object_end], b"/Name");
+
+    result.push(SignatureDictionary {
+      contents,
+      byte_range,
+      signer_name,
+    });
+  }
+
+  result
+}
+
+/// Numbers of array following the key
+fn parse_byte_range(data: &[u8]) -> Option<Vec<u32>> {
+  let start = data.iter().position(|b| *b == b'[')?;
+  let end = data.iter().position(|b| *b == b']')?;
+  if end < start {
+    return None;
+  }
+  let numbers: Option<Vec<u32>> = String::from_utf8_lossy(&data[start + 1..end])
+    .split_whitespace()
+    .map(|number| number.parse().ok())
+    .collect();
+  numbers.filter(|numbers| numbers.len() >= 4 && numbers.len() % 2 == 0)
+}
+
+fn decode_hex(data: &[u8]) -> Vec<u8> {
+  let digits: Vec<u8> = data
+    .iter()
+    .filter_map(|b| (*b as char).to_digit(16).map(|d| d as u8))
+    .collect();
+  digits
+    .chunks(2)
+    .map(|pair| (pair[0] << 4) | pair.get(1).copied().unwrap_or(0))
+    .collect()
+}
+
+/// Decoded literal or hexadecimal string value of the key, name values are skipped
+pub fn string_value(data: &[u8], key: &[u8]) -> Option<String> {
+  let mut search_from = 0;
+  while let Some(position) = find(&data[search_from..], key).map(|i| search_from + i) {
+    search_from = position + key.len();
+    let rest = &data[search_from..];
+    let value_start = rest.iter().position(|b| !b.is_ascii_whitespace())?;
+    let bytes = match (rest[value_start], rest.get(value_start + 1)) {
+      (b'(', _) => literal_string_bytes(&rest[value_start + 1..])?,
+      (b'<', Some(next)) if *next != b'<' => {
+        let hex = &rest[value_start + 1..];
+        decode_hex(&hex[..hex.iter().position(|b| *b == b'>')?])
+      }
+      _ => continue,
+    };
+
+    // text strings are UTF-16 with byte order mark or single byte encoded
+    return Some(match bytes.strip_prefix(&[0xFE, 0xFF]) {
+      Some(utf16) => String::from_utf16_lossy(
+        &utf16
+          .chunks_exact(2)
+          .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+          .collect::<Vec<u16>>(),
+      ),
+      None => bytes.iter().map(|b| *b as char).collect(),
+    });
+  }
+  None
+}
+
+/// Unescaped bytes of literal string data following its opening parenthesis
+fn literal_string_bytes(data: &[u8]) -> Option<Vec<u8>> {
+  let mut bytes: Vec<u8> = vec![];
+  let mut depth = 0;
+  let mut iter = data.iter().copied();
+  while let Some(b) = iter.next() {
+    match b {
+      b'\\' => match iter.next()? {
+        b'n' => bytes.push(b'\n'),
+        b'r' => bytes.push(b'\r'),
+        b't' => bytes.push(b'\t'),
+        escaped => bytes.push(escaped),
+      },
+      b'(' => {
+        depth += 1;
+        bytes.push(b);
+      }
+      b')' if depth == 0 => break,
+      b')' => {
+        depth -= 1;
+        bytes.push(b);
+      }
+      _ => bytes.push(b),
+    }
+  }
+  Some(bytes)
+}
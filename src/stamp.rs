@@ -0,0 +1,138 @@
+use image::DynamicImage;
+use pdfium_render::prelude::*;
+
+use crate::document::rotation_degrees;
+use crate::errors::{ErrorCode, PdfError};
+use crate::options::StampOptions;
+use crate::{get_pdfium, load_document, run_blocking, save_document};
+
+#[napi(catch_unwind)]
+/// Draw text watermark or image stamp on selected pages and save document into output path
+pub async fn stamp_pages(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+  options: StampOptions,
+  output_path: String,
+) -> napi::Result<()> {
+  run_blocking(move || {
+    let image = match (&options.text, &options.image_path) {
+      (Some(_), None) => None,
+      (None, Some(image_path)) => Some(image::open(image_path).map_err(|err| {
+        PdfError::new(ErrorCode::LoadFailed, "Failed to open stamp image").caused_by(err)
+      })?),
+      _ => {
+        return Err(
+          PdfError::new(
+            ErrorCode::InvalidArgument,
+            "Either stamp text or image path must be set",
+          )
+          .into(),
+        )
+      }
+    };
+    let opacity = options.opacity.unwrap_or(0.5).clamp(0.0, 1.0);
+    let image = image.map(|image| with_opacity(image, opacity));
+
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let mut document = load_document(pdfium, &pdf_path)?;
+    let font = document.fonts_mut().helvetica();
+
+    let total_pages = document.pages().len() as u32;
+    let pages = options
+      .pages
+      .clone()
+      .unwrap_or_else(|| (0..total_pages).collect());
+    if let Some(page_index) = pages.iter().find(|index| **index >= total_pages) {
+      return Err(PdfError::page_out_of_range(*page_index as usize).into());
+    }
+
+    for page_index in pages {
+      let mut page = document
+        .pages()
+        .get(page_index as PdfPageIndex)
+        .map_err(|err| {
+          PdfError::page(page_index as usize, "Failed to load pdf page").caused_by(err)
+        })?;
+
+      let mut object: PdfPageObject = match &image {
+        Some(image) => {
+          let width = options.image_width.unwrap_or(image.width() as f64) as f32;
+          PdfPageImageObject::new_with_width(&document, image, PdfPoints::new(width))
+            .map_err(|err| {
+              PdfError::edit("Failed to create stamp image")
+                .on_page(page_index as usize)
+                .caused_by(err)
+            })?
+            .into()
+        }
+        None => {
+          let text = options.text.as_deref().unwrap_or_default();
+          let font_size = options.font_size.unwrap_or(48.0) as f32;
+          let mut object = PdfPageTextObject::new(&document, text, font, PdfPoints::new(font_size))
+            .map_err(|err| {
+              PdfError::edit("Failed to create watermark text")
+                .on_page(page_index as usize)
+                .caused_by(err)
+            })?;
+          object
+            .set_fill_color(PdfColor::new(128, 128, 128, (opacity * 255.0) as u8))
+            .map_err(|err| {
+              PdfError::edit("Failed to set watermark color")
+                .on_page(page_index as usize)
+                .caused_by(err)
+            })?;
+          object.into()
+        }
+      };
+
+      // pdfium reports page size with rotation applied
+      let rotation = rotation_degrees(&page);
+      let (page_width, page_height) = match rotation {
+        90 | 270 => (page.height().value, page.width().value),
+        _ => (page.width().value, page.height().value),
+      };
+      let x = options.x.map_or(page_width / 2.0, |x| x as f32);
+      let y = options.y.map_or(page_height / 2.0, |y| y as f32);
+
+      // center the object on origin, rotate it around its center and move to position
+      let bounds = object.bounds().map_err(|err| {
+        PdfError::edit("Failed to measure stamp")
+          .on_page(page_index as usize)
+          .caused_by(err)
+      })?;
+      let (width, height) = (bounds.width().value, bounds.height().value);
+      let angle = options.angle.unwrap_or(0.0) as f32 + rotation as f32;
+      object
+        .translate(
+          PdfPoints::new(-bounds.left().value - width / 2.0),
+          PdfPoints::new(-bounds.bottom().value - height / 2.0),
+        )
+        .and_then(|_| object.rotate_counter_clockwise_degrees(angle))
+        .and_then(|_| object.translate(PdfPoints::new(x), PdfPoints::new(y)))
+        .map_err(|err| {
+          PdfError::edit("Failed to place stamp")
+            .on_page(page_index as usize)
+            .caused_by(err)
+        })?;
+
+      page.objects_mut().add_object(object).map_err(|err| {
+        PdfError::edit("Failed to add stamp to page")
+          .on_page(page_index as usize)
+          .caused_by(err)
+      })?;
+    }
+
+    save_document(document, &output_path)
+  })
+  .await
+}
+
+/// Image with alpha channel scaled by opacity
+fn with_opacity(image: DynamicImage, opacity: f64) -> DynamicImage {
+  let mut image = image.to_rgba8();
+  for pixel in image.pixels_mut() {
+    pixel[3] = (pixel[3] as f64 * opacity).round() as u8;
+  }
+  DynamicImage::ImageRgba8(image)
+}
@@ -0,0 +1,435 @@
+use pdfium_render::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::iter::Peekable;
+use std::os::raw::{c_int, c_ulong, c_void};
+
+use crate::document::document_metadata;
+use crate::errors::{ErrorCode, PdfError};
+use crate::layout::{self, Bounds};
+use crate::objects::{decrypted_data, PdfObjects};
+use crate::signatures::string_value;
+use crate::{get_pdfium, load_document, run_blocking, RawDocument};
+
+#[napi(object)]
+/// Element of tagged document structure tree
+pub struct StructureElement {
+  /// Structure type like "P", "H1", "Table" or "Figure"
+  pub role: String,
+  pub page_index: u32,
+  pub title: Option<String>,
+  /// Alternate description, usually of figures
+  pub alt_text: Option<String>,
+  /// Replacement text of element content, like of hyphenated words
+  pub actual_text: Option<String>,
+  /// Language like "en-US"
+  pub lang: Option<String>,
+  /// Text of element own content, without text of its children
+  pub text: Option<String>,
+  pub children: Vec<StructureElement>,
+}
+
+/// Structure element of page structure tree in depth first order
+struct PageElement {
+  depth: usize,
+  element: StructureElement,
+  marked_content_ids: Vec<c_int>,
+}
+
+/// Tagged page structure read through pdfium bindings as pdfium-render does not expose it
+struct PageStructure {
+  page_index: usize,
+  elements: Vec<PageElement>,
+  /// Page text by marked content
+  texts: HashMap<c_int, String>,
+  /// Marked content of page objects by their bounds
+  object_ids: Vec<(Bounds, c_int)>,
+}
+
+#[napi(catch_unwind)]
+/// Extract structure tree of tagged document, listed page by page as pdfium reads it, empty for
+/// untagged documents
+pub async fn extract_structure_tree(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+) -> napi::Result<Vec<StructureElement>> {
+  run_blocking(move || {
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    // loaded for failure reason
+    drop(load_document(pdfium, &pdf_path)?);
+    let raw_document = RawDocument::new(pdfium.bindings(), &pdf_path);
+    let pages = page_structures(&raw_document, None, true).ok_or_else(|| {
+      napi::Error::from(PdfError::new(
+        ErrorCode::LoadFailed,
+        "Failed to read pdf document",
+      ))
+    })?;
+
+    let mut result = vec![];
+    for page in pages {
+      let mut elements = page.elements;
+      for page_element in elements.iter_mut() {
+        let text = page_element
+          .marked_content_ids
+          .iter()
+          .filter_map(|id| page.texts.get(id))
+          .map(String::as_str)
+          .collect::<Vec<_>>()
+          .join(" ");
+        page_element.element.text = Some(text).filter(|text| !text.is_empty());
+      }
+      result.append(&mut nest_elements(&mut elements.into_iter().peekable(), 0));
+    }
+
+    Ok(result)
+  })
+  .await
+}
+
+#[napi(object)]
+/// Accessibility screening of the document
+pub struct AccessibilityReport {
+  /// Document is tagged with structure tree
+  pub is_tagged: bool,
+  pub title: Option<String>,
+  /// Document language like "en-US", read from document catalog stored uncompressed
+  pub language: Option<String>,
+  pub figure_count: u32,
+  /// Page index of each figure without alternate description
+  pub figures_without_alt_text: Vec<u32>,
+  /// Failed checks, empty when all of them pass
+  pub issues: Vec<String>,
+}
+
+#[napi(catch_unwind)]
+/// Check the document is tagged, its figures have alternate descriptions and it declares title
+/// and language
+pub async fn check_accessibility(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+) -> napi::Result<AccessibilityReport> {
+  run_blocking(move || {
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let bindings = pdfium.bindings();
+    let raw_document = RawDocument::new(bindings, &pdf_path);
+    let title = document_metadata(&load_document(pdfium, &pdf_path)?, &raw_document).title;
+
+    let document = raw_document.handle().ok_or_else(|| {
+      napi::Error::from(PdfError::new(
+        ErrorCode::LoadFailed,
+        "Failed to read pdf document",
+      ))
+    })?;
+    let is_tagged = bindings.is_true(bindings.FPDFCatalog_IsTagged(document));
+
+    let figures: Vec<StructureElement> = page_structures(&raw_document, None, false)
+      .unwrap_or_default()
+      .into_iter()
+      .flat_map(|page| page.elements)
+      .map(|page_element| page_element.element)
+      .filter(|element| element.role == "Figure")
+      .collect();
+    let figures_without_alt_text: Vec<u32> = figures
+      .iter()
+      .filter(|figure| figure.alt_text.is_none())
+      .map(|figure| figure.page_index)
+      .collect();
+    let language = decrypted_data(&raw_document)
+      .and_then(|data| PdfObjects::parse(&data)?.catalog())
+      .and_then(|catalog| catalog_language(&catalog));
+
+    let mut issues = vec![];
+    if !is_tagged {
+      issues.push("Document is not tagged".to_owned());
+    }
+    if title.is_none() {
+      issues.push("Document title is missing".to_owned());
+    }
+    if language.is_none() {
+      issues.push("Document language is not declared".to_owned());
+    }
+    if !figures_without_alt_text.is_empty() {
+      issues.push(format!(
+        "{} of {} figures have no alternate text",
+        figures_without_alt_text.len(),
+        figures.len()
+      ));
+    }
+
+    Ok(AccessibilityReport {
+      is_tagged,
+      title,
+      language,
+      figure_count: figures.len() as u32,
+      figures_without_alt_text,
+      issues,
+    })
+  })
+  .await
+}
+
+/// Language declared in the document catalog dictionary
+fn catalog_language(catalog: &str) -> Option<String> {
+  string_value(catalog.as_bytes(), b"/Lang")
+    .map(|language| language.trim().to_owned())
+    .filter(|language| !language.is_empty())
+}
+
+/// Reading order position of page objects by their bounds for each tagged page, all or the one
+/// with index, the order structure tree of the page lists their content in
+pub fn reading_orders(
+  raw_document: &RawDocument,
+  only_page_index: Option<usize>,
+) -> HashMap<usize, Vec<(Bounds, usize)>> {
+  let pages = page_structures(raw_document, only_page_index, false).unwrap_or_default();
+
+  let mut result = HashMap::new();
+  for page in pages {
+    let mut positions: HashMap<c_int, usize> = HashMap::new();
+    for id in page
+      .elements
+      .into_iter()
+      .flat_map(|page_element| page_element.marked_content_ids)
+    {
+      let position = positions.len();
+      positions.entry(id).or_insert(position);
+    }
+    let reading_order: Vec<(Bounds, usize)> = page
+      .object_ids
+      .into_iter()
+      .filter_map(|(bounds, id)| Some((bounds, *positions.get(&id)?)))
+      .collect();
+    if !reading_order.is_empty() {
+      result.insert(page.page_index, reading_order);
+    }
+  }
+  result
+}
+
+/// Reorder page text objects found in reading order as it lists them, in places of these objects
+/// so images and untagged text like page artifacts keep their place, with block starts and
+/// vertical lines of the objects moved along and each content starting a block
+pub fn apply_reading_order(
+  objects: &mut Vec<PdfPageObject>,
+  reading_order: &[(Bounds, usize)],
+  block_starts: &mut HashSet<usize>,
+  vertical_lines: &mut HashMap<usize, bool>,
+) {
+  let positions: Vec<Option<usize>> = objects
+    .iter()
+    .map(|object| {
+      object.as_text_object()?;
+      let bounds = layout::object_bounds(object)?;
+      reading_order
+        .iter()
+        .find(|(object_bounds, _)| *object_bounds == bounds)
+        .map(|(_, position)| *position)
+    })
+    .collect();
+
+  let slots: Vec<usize> = (0..objects.len())
+    .filter(|idx| positions[*idx].is_some())
+    .collect();
+  let mut ordered = slots.clone();
+  // objects of the same content keep their order
+  ordered.sort_by_key(|idx| positions[*idx]);
+
+  let mut order: Vec<usize> = (0..objects.len()).collect();
+  for (slot, idx) in slots.into_iter().zip(ordered) {
+    order[slot] = idx;
+  }
+  let mut new_indices = vec![0; objects.len()];
+  for (new_idx, idx) in order.iter().enumerate() {
+    new_indices[*idx] = new_idx;
+  }
+
+  // content continues the line of the previous one only at the same height
+  *block_starts = block_starts
+    .iter()
+    .map(|idx| new_indices[*idx])
+    .chain(
+      (1..order.len())
+        .filter(|new_idx| positions[order[*new_idx]] != positions[order[new_idx - 1]]),
+    )
+    .collect();
+  *vertical_lines = vertical_lines
+    .drain()
+    .map(|(idx, continues_column)| (new_indices[idx], continues_column))
+    .collect();
+
+  let mut taken: Vec<Option<PdfPageObject>> =
+    std::mem::take(objects).into_iter().map(Some).collect();
+  *objects = order
+    .into_iter()
+    .filter_map(|idx| taken[idx].take())
+    .collect();
+}
+
+/// Structure of tagged pages, all or the one with index, with text of marked content when
+/// requested, none when the document fails to load
+fn page_structures(
+  raw_document: &RawDocument,
+  only_page_index: Option<usize>,
+  with_texts: bool,
+) -> Option<Vec<PageStructure>> {
+  let bindings = raw_document.bindings();
+  let document = raw_document.handle()?;
+  let page_indices = match only_page_index {
+    Some(page_index) => page_index as c_int..page_index as c_int + 1,
+    None => 0..bindings.FPDF_GetPageCount(document),
+  };
+
+  let mut result = vec![];
+  for page_index in page_indices {
+    let page = bindings.FPDF_LoadPage(document, page_index);
+    if page.is_null() {
+      continue;
+    }
+    let tree = bindings.FPDF_StructTree_GetForPage(page);
+    if tree.is_null() {
+      bindings.FPDF_ClosePage(page);
+      continue;
+    }
+
+    // elements in depth first order, marked content kids are no elements
+    let mut elements = vec![];
+    let mut stack: Vec<_> = (0..bindings.FPDF_StructTree_CountChildren(tree))
+      .rev()
+      .map(|idx| (bindings.FPDF_StructTree_GetChildAtIndex(tree, idx), 0))
+      .collect();
+    while let Some((element, depth)) = stack.pop() {
+      if element.is_null() {
+        continue;
+      }
+      stack.extend(
+        (0..bindings.FPDF_StructElement_CountChildren(element))
+          .rev()
+          .map(|idx| {
+            (
+              bindings.FPDF_StructElement_GetChildAtIndex(element, idx),
+              depth + 1,
+            )
+          }),
+      );
+
+      elements.push(PageElement {
+        depth,
+        element: StructureElement {
+          role: pdfium_string(|buffer, length| {
+            bindings.FPDF_StructElement_GetType(element, buffer, length)
+          })
+          .unwrap_or_default(),
+          page_index: page_index as u32,
+          title: pdfium_string(|buffer, length| {
+            bindings.FPDF_StructElement_GetTitle(element, buffer, length)
+          }),
+          alt_text: pdfium_string(|buffer, length| {
+            bindings.FPDF_StructElement_GetAltText(element, buffer, length)
+          }),
+          actual_text: pdfium_string(|buffer, length| {
+            bindings.FPDF_StructElement_GetActualText(element, buffer, length)
+          }),
+          lang: pdfium_string(|buffer, length| {
+            bindings.FPDF_StructElement_GetLang(element, buffer, length)
+          }),
+          text: None,
+          children: vec![],
+        },
+        marked_content_ids: (0..bindings.FPDF_StructElement_GetMarkedContentIdCount(element))
+          .map(|idx| bindings.FPDF_StructElement_GetMarkedContentIdAtIndex(element, idx))
+          .filter(|id| *id >= 0)
+          .collect(),
+      });
+    }
+    bindings.FPDF_StructTree_Close(tree);
+
+    let mut object_ids = vec![];
+    for idx in 0..bindings.FPDFPage_CountObjects(page) {
+      let object = bindings.FPDFPage_GetObject(page, idx);
+      if object.is_null() {
+        continue;
+      }
+      let (mut left, mut bottom, mut right, mut top) = (0.0, 0.0, 0.0, 0.0);
+      let id = bindings.FPDFPageObj_GetMarkedContentID(object);
+      if id >= 0
+        && bindings.is_true(bindings.FPDFPageObj_GetBounds(
+          object,
+          &mut left,
+          &mut bottom,
+          &mut right,
+          &mut top,
+        ))
+      {
+        object_ids.push(((left, bottom, right, top), id));
+      }
+    }
+
+    // text by marked content, with whitespace pdfium generates between words and lines
+    let mut texts: HashMap<c_int, String> = HashMap::new();
+    let text_page = if with_texts {
+      bindings.FPDFText_LoadPage(page)
+    } else {
+      std::ptr::null_mut()
+    };
+    if !text_page.is_null() {
+      let mut current = None;
+      for idx in 0..bindings.FPDFText_CountChars(text_page) {
+        let object = bindings.FPDFText_GetTextObject(text_page, idx);
+        let c = char::from_u32(bindings.FPDFText_GetUnicode(text_page, idx)).unwrap_or(' ');
+        // generated characters have no text object
+        if !object.is_null() {
+          let id = bindings.FPDFPageObj_GetMarkedContentID(object);
+          current = (id >= 0).then_some(id);
+        }
+        if let Some(id) = current {
+          texts.entry(id).or_default().push(c);
+        }
+      }
+      bindings.FPDFText_ClosePage(text_page);
+    }
+    for text in texts.values_mut() {
+      *text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    }
+
+    result.push(PageStructure {
+      page_index: page_index as usize,
+      elements,
+      texts,
+      object_ids,
+    });
+    bindings.FPDF_ClosePage(page);
+  }
+
+  Some(result)
+}
+
+/// Elements at the depth with their descendants nested as children
+fn nest_elements(
+  elements: &mut Peekable<impl Iterator<Item = PageElement>>,
+  depth: usize,
+) -> Vec<StructureElement> {
+  let mut result = vec![];
+  while let Some(page_element) = elements.next_if(|page_element| page_element.depth >= depth) {
+    let mut element = page_element.element;
+    element.children = nest_elements(elements, page_element.depth + 1);
+    result.push(element);
+  }
+  result
+}
+
+/// UTF-16LE string with NUL terminator read by pdfium getter returning its length in bytes
+pub fn pdfium_string(get: impl Fn(*mut c_void, c_ulong) -> c_ulong) -> Option<String> {
+  let length = get(std::ptr::null_mut(), 0);
+  if length <= 2 {
+    return None;
+  }
+  let mut buffer = vec![0u8; length as usize];
+  get(buffer.as_mut_ptr() as *mut c_void, length);
+  let units: Vec<u16> = buffer
+    .chunks_exact(2)
+    .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+    .take_while(|unit| *unit != 0)
+    .collect();
+  Some(String::from_utf16_lossy(&units)).filter(|text| !text.is_empty())
+}
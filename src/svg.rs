@@ -0,0 +1,150 @@
+use pdfium_render::prelude::*;
+use std::fmt::Write;
+
+use crate::layout::Bounds;
+
+/// Serialize page path objects into standalone SVG cropped to the paths bounds, returned with
+/// the SVG
+pub fn page_paths_to_svg(page: &PdfPage) -> Option<(String, Bounds)> {
+  let page_height = page.height().value;
+
+  let mut paths = String::new();
+  // paths bounds left/bottom/right/top
+  let mut bounds: Option<Bounds> = None;
+
+  for object in page.objects().iter() {
+    let path = match object.as_path_object() {
+      Some(path) => path,
+      None => continue,
+    };
+    let d = match path_data(path, page_height) {
+      Some(d) => d,
+      None => continue,
+    };
+
+    if let Ok(b) = path.bounds() {
+      let (left, bottom, right, top) = (
+        b.left().value,
+        b.bottom().value,
+        b.right().value,
+        b.top().value,
+      );
+      bounds = Some(match bounds {
+        Some((l, btm, r, t)) => (l.min(left), btm.min(bottom), r.max(right), t.max(top)),
+        None => (left, bottom, right, top),
+      });
+    }
+
+    let fill = match path.fill_mode() {
+      Ok(PdfPathFillMode::None) | Err(_) => "fill=\"none\"".to_owned(),
+      Ok(fill_mode) => {
+        let rule = if fill_mode == PdfPathFillMode::EvenOdd {
+          "evenodd"
+        } else {
+          "nonzero"
+        };
+        let color = path.fill_color().unwrap_or(PdfColor::BLACK);
+        format!("{} fill-rule=\"{}\"", svg_color("fill", &color), rule)
+      }
+    };
+
+    let stroke = match path.is_stroked() {
+      Ok(true) => {
+        let color = path.stroke_color().unwrap_or(PdfColor::BLACK);
+        // stroke width is in object space, scale it into page space
+        let scale = path
+          .matrix()
+          .map(|m| m.determinant().abs().sqrt())
+          .unwrap_or(1.0);
+        let width = path.stroke_width().map(|w| w.value).unwrap_or(1.0) * scale;
+        format!(
+          " {} stroke-width=\"{}\"",
+          svg_color("stroke", &color),
+          width
+        )
+      }
+      _ => String::new(),
+    };
+
+    let _ = writeln!(paths, "  <path d=\"{}\" {}{}/>", d, fill, stroke);
+  }
+
+  let (left, bottom, right, top) = bounds?;
+  let (width, height) = (right - left, top - bottom);
+
+  let svg = format!(
+    "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}pt\" height=\"{height}pt\" viewBox=\"{} {} {width} {height}\">\n{paths}</svg>\n",
+    left,
+    page_height - top,
+  );
+  Some((svg, (left, bottom, right, top)))
+}
+
+/// SVG path data in page space with y axis pointing down
+fn path_data(path: &PdfPagePathObject, page_height: f32) -> Option<String> {
+  let matrix = path.matrix().ok()?;
+  let segments = path.segments().transform(matrix);
+
+  let mut d = String::new();
+  // Bezier curves come as three segments, two control points and the end point
+  let mut bezier_points: Vec<(f32, f32)> = vec![];
+
+  for segment in segments.iter() {
+    let (x, y) = (segment.x().value, page_height - segment.y().value);
+
+    match segment.segment_type() {
+      PdfPathSegmentType::MoveTo => {
+        let _ = write!(d, "M{} {} ", x, y);
+      }
+      PdfPathSegmentType::LineTo => {
+        let _ = write!(d, "L{} {} ", x, y);
+      }
+      PdfPathSegmentType::BezierTo => {
+        bezier_points.push((x, y));
+        if bezier_points.len() == 3 {
+          let _ = write!(
+            d,
+            "C{} {} {} {} {} {} ",
+            bezier_points[0].0,
+            bezier_points[0].1,
+            bezier_points[1].0,
+            bezier_points[1].1,
+            bezier_points[2].0,
+            bezier_points[2].1
+          );
+          bezier_points.clear();
+        }
+      }
+      PdfPathSegmentType::Unknown => {}
+    }
+
+    if segment.is_close() {
+      d.push_str("Z ");
+    }
+  }
+
+  let d = d.trim_end();
+  if d.is_empty() {
+    return None;
+  }
+  Some(d.to_owned())
+}
+
+fn svg_color(attribute: &str, color: &PdfColor) -> String {
+  let mut result = format!(
+    "{}=\"rgb({},{},{})\"",
+    attribute,
+    color.red(),
+    color.green(),
+    color.blue()
+  );
+  if color.alpha() < 255 {
+    let _ = write!(
+      result,
+      " {}-opacity=\"{:.3}\"",
+      attribute,
+      color.alpha() as f32 / 255.0
+    );
+  }
+  result
+}
@@ -0,0 +1,338 @@
+use pdfium_render::prelude::*;
+
+use crate::errors::PdfError;
+use crate::layout::{Bounds, PageRotation};
+use crate::options::ExtractOptions;
+use crate::text::page_words;
+use crate::{
+  check_aborted, check_page_limit, get_pdfium, load_document, report_progress, run_blocking,
+  ExtractedWord,
+};
+
+// maximal thickness of path to be considered a ruling line
+static MAX_RULING_WIDTH: f32 = 3.0;
+// allowed distance between ruling lines to consider them connected
+static RULING_TOLERANCE: f32 = 2.0;
+// allowed vertical words position difference to consider them same line
+static SAME_LINE_RANGE_DIFF: f64 = 5.0;
+
+#[napi(object)]
+/// Table detected by its ruling lines, bounds in page space points
+pub struct ExtractedTable {
+  pub page_index: u32,
+  pub left: f64,
+  pub top: f64,
+  pub right: f64,
+  pub bottom: f64,
+  /// Table rows from top to bottom with cells from left to right
+  pub rows: Vec<Vec<ExtractedTableCell>>,
+}
+
+#[napi(object)]
+/// Table cell, cells spanning several columns are merged
+pub struct ExtractedTableCell {
+  /// Cell text lines joined with new lines
+  pub text: String,
+  pub left: f64,
+  pub top: f64,
+  pub right: f64,
+  pub bottom: f64,
+}
+
+// horizontal ruling is y and x range, vertical ruling is x and y range
+#[derive(Clone, Copy)]
+enum Ruling {
+  Horizontal(f32, f32, f32),
+  Vertical(f32, f32, f32),
+}
+
+#[napi(catch_unwind)]
+/// Extract tables drawn with ruling lines with their cells text
+pub async fn extract_tables(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+  options: Option<ExtractOptions>,
+) -> napi::Result<Vec<ExtractedTable>> {
+  run_blocking(move || {
+    let options = options.unwrap_or_default();
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let document = load_document(pdfium, &pdf_path)?;
+    check_page_limit(&document, &options)?;
+
+    let mut result: Vec<ExtractedTable> = vec![];
+    let total_pages = document.pages().len() as u32;
+
+    for (page_index, page) in document.pages().iter().enumerate() {
+      check_aborted(&options)?;
+      result.append(&mut page_tables(&page, page_index as u32, &options)?);
+      report_progress(&options, page_index as u32, total_pages, 0);
+    }
+
+    Ok(result)
+  })
+  .await
+}
+
+/// Tables drawn with ruling lines on the page
+pub fn page_tables(
+  page: &PdfPage,
+  page_index: u32,
+  options: &ExtractOptions,
+) -> napi::Result<Vec<ExtractedTable>> {
+  let rulings = page_rulings(page);
+  if rulings.is_empty() {
+    return Ok(vec![]);
+  }
+  let text_page: PdfPageText<'_> = page.text().map_err(|err| {
+    PdfError::page(page_index as usize, "Failed to read pdf document page").caused_by(err)
+  })?;
+  let words = page_words(&text_page, page_index);
+
+  let mut result: Vec<ExtractedTable> = vec![];
+  let rotation = PageRotation::of(page, options);
+  for table_rulings in connected_rulings(rulings) {
+    if let Some(mut table) = build_table(&table_rulings, &words, page_index) {
+      if let Some(rotation) = rotation {
+        rotate_table(&mut table, rotation);
+      }
+      result.push(table);
+    }
+  }
+
+  Ok(result)
+}
+
+/// Thin filled paths and axis aligned stroked path segments
+fn page_rulings(page: &PdfPage) -> Vec<Ruling> {
+  let mut rulings: Vec<Ruling> = vec![];
+
+  for object in page.objects().iter() {
+    let path = match object.as_path_object() {
+      Some(path) => path,
+      None => continue,
+    };
+
+    if let Ok(b) = path.bounds() {
+      let (left, bottom, right, top) = (
+        b.left().value,
+        b.bottom().value,
+        b.right().value,
+        b.top().value,
+      );
+      let (width, height) = (right - left, top - bottom);
+      if height <= MAX_RULING_WIDTH && width > MAX_RULING_WIDTH {
+        rulings.push(Ruling::Horizontal((top + bottom) / 2.0, left, right));
+        continue;
+      }
+      if width <= MAX_RULING_WIDTH && height > MAX_RULING_WIDTH {
+        rulings.push(Ruling::Vertical((left + right) / 2.0, bottom, top));
+        continue;
+      }
+    }
+
+    if !path.is_stroked().unwrap_or(false) {
+      continue;
+    }
+    let matrix = match path.matrix() {
+      Ok(matrix) => matrix,
+      Err(_) => continue,
+    };
+    let mut last_point: Option<(f32, f32)> = None;
+    for segment in path.segments().transform(matrix).iter() {
+      let point = (segment.x().value, segment.y().value);
+      if let (PdfPathSegmentType::LineTo, Some((x, y))) = (segment.segment_type(), last_point) {
+        if (point.1 - y).abs() <= RULING_TOLERANCE && (point.0 - x).abs() > MAX_RULING_WIDTH {
+          rulings.push(Ruling::Horizontal(y, x.min(point.0), x.max(point.0)));
+        } else if (point.0 - x).abs() <= RULING_TOLERANCE && (point.1 - y).abs() > MAX_RULING_WIDTH
+        {
+          rulings.push(Ruling::Vertical(x, y.min(point.1), y.max(point.1)));
+        }
+      }
+      last_point = Some(point);
+    }
+  }
+
+  rulings
+}
+
+/// Crossing or touching perpendicular rulings and overlapping rulings on the same line
+fn are_connected(a: Ruling, b: Ruling) -> bool {
+  let within =
+    |v: f32, from: f32, to: f32| v >= from - RULING_TOLERANCE && v <= to + RULING_TOLERANCE;
+  match (a, b) {
+    (Ruling::Horizontal(y, x1, x2), Ruling::Vertical(x, y1, y2))
+    | (Ruling::Vertical(x, y1, y2), Ruling::Horizontal(y, x1, x2)) => {
+      within(x, x1, x2) && within(y, y1, y2)
+    }
+    (Ruling::Horizontal(pos, from, to), Ruling::Horizontal(other_pos, other_from, other_to))
+    | (Ruling::Vertical(pos, from, to), Ruling::Vertical(other_pos, other_from, other_to)) => {
+      (pos - other_pos).abs() <= RULING_TOLERANCE
+        && (within(other_from, from, to) || within(from, other_from, other_to))
+    }
+  }
+}
+
+/// Group rulings touching each other, each group is a table candidate
+fn connected_rulings(rulings: Vec<Ruling>) -> Vec<Vec<Ruling>> {
+  // group index of each ruling
+  let mut groups: Vec<usize> = (0..rulings.len()).collect();
+  fn root(groups: &mut [usize], mut i: usize) -> usize {
+    while groups[i] != i {
+      groups[i] = groups[groups[i]];
+      i = groups[i];
+    }
+    i
+  }
+
+  for i in 0..rulings.len() {
+    for j in i + 1..rulings.len() {
+      if are_connected(rulings[i], rulings[j]) {
+        let (a, b) = (root(&mut groups, i), root(&mut groups, j));
+        groups[a] = b;
+      }
+    }
+  }
+
+  let mut result: Vec<(usize, Vec<Ruling>)> = vec![];
+  for (i, ruling) in rulings.into_iter().enumerate() {
+    let group = root(&mut groups, i);
+    match result.iter_mut().find(|(g, _)| *g == group) {
+      Some((_, group_rulings)) => group_rulings.push(ruling),
+      None => result.push((group, vec![ruling])),
+    }
+  }
+
+  result
+    .into_iter()
+    .map(|(_, group_rulings)| group_rulings)
+    .collect()
+}
+
+/// Distinct sorted positions, positions closer than tolerance are merged
+fn distinct_positions(mut positions: Vec<f32>) -> Vec<f32> {
+  positions.sort_by(|a, b| a.total_cmp(b));
+  positions.dedup_by(|b, a| *b - *a <= RULING_TOLERANCE);
+  positions
+}
+
+fn build_table(
+  rulings: &[Ruling],
+  words: &[ExtractedWord],
+  page_index: u32,
+) -> Option<ExtractedTable> {
+  let xs = distinct_positions(
+    rulings
+      .iter()
+      .filter_map(|r| match r {
+        Ruling::Vertical(x, _, _) => Some(*x),
+        _ => None,
+      })
+      .collect(),
+  );
+  // rows from top to bottom
+  let mut ys = distinct_positions(
+    rulings
+      .iter()
+      .filter_map(|r| match r {
+        Ruling::Horizontal(y, _, _) => Some(*y),
+        _ => None,
+      })
+      .collect(),
+  );
+  ys.reverse();
+  if xs.len() < 2 || ys.len() < 2 {
+    return None;
+  }
+
+  let rows = ys
+    .windows(2)
+    .map(|row| {
+      let (top, bottom) = (row[0], row[1]);
+      let middle = (top + bottom) / 2.0;
+
+      // inner column borders drawn across this row, missing borders merge cells
+      let mut borders = vec![xs[0]];
+      borders.extend(xs[1..xs.len() - 1].iter().copied().filter(|x| {
+        rulings.iter().any(|r| match r {
+          Ruling::Vertical(rx, y1, y2) => {
+            (rx - x).abs() <= RULING_TOLERANCE && *y1 <= middle && *y2 >= middle
+          }
+          _ => false,
+        })
+      }));
+      borders.push(xs[xs.len() - 1]);
+
+      borders
+        .windows(2)
+        .map(|column| cell(words, (column[0], bottom, column[1], top)))
+        .collect()
+    })
+    .collect();
+
+  Some(ExtractedTable {
+    page_index,
+    left: xs[0] as f64,
+    top: ys[0] as f64,
+    right: xs[xs.len() - 1] as f64,
+    bottom: ys[ys.len() - 1] as f64,
+    rows,
+  })
+}
+
+/// Rotate table and cells bounds, rows and cells order is kept as in page space
+fn rotate_table(table: &mut ExtractedTable, rotation: PageRotation) {
+  (table.left, table.top, table.right, table.bottom) =
+    rotation.apply((table.left, table.top, table.right, table.bottom));
+  for cell in table.rows.iter_mut().flatten() {
+    (cell.left, cell.top, cell.right, cell.bottom) =
+      rotation.apply((cell.left, cell.top, cell.right, cell.bottom));
+  }
+}
+
+fn cell(words: &[ExtractedWord], bounds: Bounds) -> ExtractedTableCell {
+  let (left, bottom, right, top) = (
+    bounds.0 as f64,
+    bounds.1 as f64,
+    bounds.2 as f64,
+    bounds.3 as f64,
+  );
+
+  let mut cell_words: Vec<&ExtractedWord> = words
+    .iter()
+    .filter(|w| {
+      let (x, y) = ((w.left + w.right) / 2.0, (w.top + w.bottom) / 2.0);
+      x >= left && x <= right && y >= bottom && y <= top
+    })
+    .collect();
+  // from top to bottom, then words on the same line as the line first word from left to right
+  cell_words.sort_by(|a, b| b.top.total_cmp(&a.top));
+  let mut lines: Vec<Vec<&ExtractedWord>> = vec![];
+  for word in cell_words {
+    match lines.last_mut() {
+      Some(line) if (line[0].top - word.top).abs() < SAME_LINE_RANGE_DIFF => line.push(word),
+      _ => lines.push(vec![word]),
+    }
+  }
+
+  let text = lines
+    .into_iter()
+    .map(|mut line| {
+      line.sort_by(|a, b| a.left.total_cmp(&b.left));
+      line
+        .iter()
+        .map(|word| word.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+    })
+    .collect::<Vec<_>>()
+    .join("\n");
+
+  ExtractedTableCell {
+    text,
+    left,
+    top,
+    right,
+    bottom,
+  }
+}
@@ -0,0 +1,492 @@
+use pdfium_render::prelude::*;
+use unicode_normalization::UnicodeNormalization as _;
+
+use crate::errors::{ErrorCode, PdfError};
+use crate::layout::{overlap_ratio, rect_bounds, Bounds, PageRotation, DUPLICATE_OVERLAP_RATIO};
+use crate::options::{ExtractOptions, SearchOptions, UnicodeNormalization};
+use crate::{
+  check_aborted, check_page_limit, get_pdfium, load_document, report_progress, run_blocking,
+  PageRect,
+};
+
+#[napi(object)]
+/// Word with its bounding rectangle in page space points, origin at the bottom left page corner
+pub struct ExtractedWord {
+  pub text: String,
+  pub page_index: u32,
+  pub left: f64,
+  pub top: f64,
+  pub right: f64,
+  pub bottom: f64,
+}
+
+#[napi(object)]
+/// Page character with its bounding rectangle in page space points
+pub struct ExtractedChar {
+  /// Unicode character, may be whitespace generated by pdfium between words and lines
+  pub text: String,
+  pub left: f64,
+  pub top: f64,
+  pub right: f64,
+  pub bottom: f64,
+  /// Effective font size in points
+  pub font_size: f64,
+  /// Counter clockwise rotation in degrees
+  pub rotation: f64,
+}
+
+/// Font style of text object
+#[derive(Clone, Default)]
+pub struct TextStyle {
+  pub font_name: String,
+  pub font_size: f64,
+  pub font_weight: u32,
+  pub is_italic: bool,
+  pub fill_color: String,
+}
+
+#[napi(catch_unwind)]
+/// Extract words with their positions on the page
+pub async fn extract_words(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+  options: Option<ExtractOptions>,
+) -> napi::Result<Vec<ExtractedWord>> {
+  run_blocking(move || {
+    let options = options.unwrap_or_default();
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let document = load_document(pdfium, &pdf_path)?;
+    check_page_limit(&document, &options)?;
+
+    let mut result: Vec<ExtractedWord> = vec![];
+    let total_pages = document.pages().len() as u32;
+
+    for (page_index, page) in document.pages().iter().enumerate() {
+      check_aborted(&options)?;
+
+      let text_page: PdfPageText<'_> = page.text().map_err(|err| {
+        PdfError::page(page_index, "Failed to read pdf document page").caused_by(err)
+      })?;
+
+      let mut words = page_words(&text_page, page_index as u32);
+      let rotation = PageRotation::of(&page, &options);
+      for w in words.iter_mut() {
+        if let Some(rotation) = rotation {
+          (w.left, w.top, w.right, w.bottom) = rotation.apply((w.left, w.top, w.right, w.bottom));
+        }
+        w.text = normalize_text(std::mem::take(&mut w.text), &options);
+      }
+      result.append(&mut words);
+
+      report_progress(&options, page_index as u32, total_pages, 0);
+    }
+
+    Ok(result)
+  })
+  .await
+}
+
+#[napi(object)]
+/// Text search match with its bounding rectangle in page space points, origin at the bottom
+/// left page corner
+pub struct TextSearchMatch {
+  /// Matched text as written on the page
+  pub text: String,
+  pub page_index: u32,
+  pub left: f64,
+  pub top: f64,
+  pub right: f64,
+  pub bottom: f64,
+  /// Rectangle of each matched line, matches can wrap onto the next line
+  pub rects: Vec<PageRect>,
+}
+
+#[napi(catch_unwind)]
+/// Find text occurrences with their positions using pdfium text search
+pub async fn search_text(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+  query: String,
+  search_options: Option<SearchOptions>,
+  options: Option<ExtractOptions>,
+) -> napi::Result<Vec<TextSearchMatch>> {
+  run_blocking(move || {
+    let search_options = search_options.unwrap_or_default();
+    let options = options.unwrap_or_default();
+    if query.is_empty() {
+      return Err(PdfError::new(ErrorCode::InvalidArgument, "Search query is empty").into());
+    }
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let document = load_document(pdfium, &pdf_path)?;
+    check_page_limit(&document, &options)?;
+
+    let search_options = PdfSearchOptions::new()
+      .match_case(search_options.match_case.unwrap_or(false))
+      .match_whole_word(search_options.match_whole_word.unwrap_or(false));
+
+    let mut result: Vec<TextSearchMatch> = vec![];
+    let total_pages = document.pages().len() as u32;
+
+    for (page_index, page) in document.pages().iter().enumerate() {
+      check_aborted(&options)?;
+      let rotation = PageRotation::of(&page, &options);
+
+      let text_page: PdfPageText<'_> = page.text().map_err(|err| {
+        PdfError::page(page_index, "Failed to read pdf document page").caused_by(err)
+      })?;
+
+      for segments in text_page
+        .search(&query, &search_options)
+        .iter(PdfSearchDirection::SearchForward)
+      {
+        let rects: Vec<PageRect> = segments
+          .iter()
+          .map(|segment| {
+            let (left, top, right, bottom) = rect_bounds(&segment.bounds(), rotation);
+            PageRect {
+              left,
+              top,
+              right,
+              bottom,
+            }
+          })
+          .collect();
+        if rects.is_empty() {
+          continue;
+        }
+
+        result.push(TextSearchMatch {
+          // segment text is read from its rectangle and can include adjacent spaces
+          text: segments
+            .iter()
+            .map(|segment| match segment.chars() {
+              Ok(chars) => chars
+                .iter()
+                .filter_map(|char| char.unicode_string())
+                .collect::<String>(),
+              Err(_) => segment.text(),
+            })
+            .collect(),
+          page_index: page_index as u32,
+          left: rects.iter().map(|r| r.left).fold(f64::INFINITY, f64::min),
+          top: rects
+            .iter()
+            .map(|r| r.top)
+            .fold(f64::NEG_INFINITY, f64::max),
+          right: rects
+            .iter()
+            .map(|r| r.right)
+            .fold(f64::NEG_INFINITY, f64::max),
+          bottom: rects.iter().map(|r| r.bottom).fold(f64::INFINITY, f64::min),
+          rects,
+        });
+      }
+
+      report_progress(&options, page_index as u32, total_pages, 0);
+    }
+
+    Ok(result)
+  })
+  .await
+}
+
+#[napi(catch_unwind)]
+/// Extract page characters with their positions, font size and rotation
+pub async fn extract_chars(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+  page_index: u32,
+  options: Option<ExtractOptions>,
+) -> napi::Result<Vec<ExtractedChar>> {
+  run_blocking(move || {
+    let options = options.unwrap_or_default();
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let document = load_document(pdfium, &pdf_path)?;
+    check_page_limit(&document, &options)?;
+
+    let page = document
+      .pages()
+      .get(page_index as PdfPageIndex)
+      .map_err(|err| {
+        PdfError::page(page_index as usize, "Failed to read pdf document page").caused_by(err)
+      })?;
+    let text_page: PdfPageText<'_> = page.text().map_err(|err| {
+      PdfError::page(page_index as usize, "Failed to read pdf document page").caused_by(err)
+    })?;
+
+    let rotation = PageRotation::of(&page, &options);
+    let chars = text_page
+      .chars()
+      .iter()
+      .filter_map(|char| {
+        let text = char.unicode_string()?;
+        // pdfium has no bounds for some generated characters
+        let bounds = char.loose_bounds().ok()?;
+        let bounds = (
+          bounds.left().value as f64,
+          bounds.top().value as f64,
+          bounds.right().value as f64,
+          bounds.bottom().value as f64,
+        );
+        let angle = char.angle_degrees().unwrap_or(0.0) as f64;
+        let ((left, top, right, bottom), angle) = match rotation {
+          Some(rotation) => (rotation.apply(bounds), rotation.apply_angle(angle)),
+          None => (bounds, angle),
+        };
+
+        Some(ExtractedChar {
+          text,
+          left,
+          top,
+          right,
+          bottom,
+          font_size: char.scaled_font_size().value as f64,
+          rotation: angle,
+        })
+      })
+      .collect();
+
+    Ok(chars)
+  })
+  .await
+}
+
+#[napi(object)]
+/// Word and character counts of the document and each page
+pub struct TextStats {
+  pub word_count: u32,
+  /// Characters other than whitespace
+  pub char_count: u32,
+  pub pages: Vec<PageTextStats>,
+}
+
+#[napi(object)]
+pub struct PageTextStats {
+  pub page_index: u32,
+  pub word_count: u32,
+  /// Characters other than whitespace, pages without any usually need OCR
+  pub char_count: u32,
+}
+
+#[napi(catch_unwind)]
+/// Count words and characters of document text without extracting it
+pub async fn get_text_stats(pdfium_dir: String, pdf_path: String) -> napi::Result<TextStats> {
+  run_blocking(move || {
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    let document = load_document(pdfium, &pdf_path)?;
+
+    let mut pages = vec![];
+    for page_index in 0..document.pages().len() {
+      let page = document.pages().get(page_index).map_err(|err| {
+        PdfError::page(page_index as usize, "Failed to read pdf document page").caused_by(err)
+      })?;
+      let text_page = page.text().map_err(|err| {
+        PdfError::page(page_index as usize, "Failed to read pdf document page").caused_by(err)
+      })?;
+
+      let (mut word_count, mut char_count) = (0, 0);
+      let mut in_word = false;
+      for char in text_page.chars().iter() {
+        match char.unicode_char() {
+          Some(c) if !c.is_whitespace() && !c.is_control() => {
+            char_count += 1;
+            if !in_word {
+              word_count += 1;
+            }
+            in_word = true;
+          }
+          // pdfium generates spaces and line breaks between words and lines
+          _ => in_word = false,
+        }
+      }
+      pages.push(PageTextStats {
+        page_index: page_index as u32,
+        word_count,
+        char_count,
+      });
+    }
+
+    Ok(TextStats {
+      word_count: pages.iter().map(|page| page.word_count).sum(),
+      char_count: pages.iter().map(|page| page.char_count).sum(),
+      pages,
+    })
+  })
+  .await
+}
+
+/// Group page characters into words, split on whitespace and line changes
+pub fn page_words(text_page: &PdfPageText, page_index: u32) -> Vec<ExtractedWord> {
+  let mut words: Vec<ExtractedWord> = vec![];
+  let mut word: Option<ExtractedWord> = None;
+
+  for char in text_page.chars().iter() {
+    let (c, bounds) = match (char.unicode_char(), char.loose_bounds()) {
+      (Some(c), Ok(bounds)) if !c.is_whitespace() && !c.is_control() => (c, bounds),
+      // whitespace, including spaces and line breaks generated by pdfium, ends the word
+      _ => {
+        words.extend(word.take());
+        continue;
+      }
+    };
+    let (left, top, right, bottom) = (
+      bounds.left().value as f64,
+      bounds.top().value as f64,
+      bounds.right().value as f64,
+      bounds.bottom().value as f64,
+    );
+
+    // character vertical middle out of the word means it continues on another line
+    let middle = (top + bottom) / 2.0;
+    if let Some(w) = &word {
+      if middle > w.top || middle < w.bottom {
+        words.extend(word.take());
+      }
+    }
+
+    match &mut word {
+      Some(w) => {
+        w.text.push(c);
+        w.left = w.left.min(left);
+        w.top = w.top.max(top);
+        w.right = w.right.max(right);
+        w.bottom = w.bottom.min(bottom);
+      }
+      None => {
+        word = Some(ExtractedWord {
+          text: c.to_string(),
+          page_index,
+          left,
+          top,
+          right,
+          bottom,
+        })
+      }
+    }
+  }
+  words.extend(word);
+
+  // text drawn twice over itself, like fake bold or drop shadow, is read once
+  let mut unique: Vec<ExtractedWord> = vec![];
+  for word in words {
+    let bounds = word_bounds(&word);
+    if !unique.iter().any(|u| {
+      u.text == word.text && overlap_ratio(bounds, word_bounds(u)) > DUPLICATE_OVERLAP_RATIO
+    }) {
+      unique.push(word);
+    }
+  }
+
+  unique
+}
+
+fn word_bounds(word: &ExtractedWord) -> Bounds {
+  (
+    word.left as f32,
+    word.bottom as f32,
+    word.right as f32,
+    word.top as f32,
+  )
+}
+
+/// Apply unicode normalization and ligatures expansion requested in options
+pub fn normalize_text(text: String, options: &ExtractOptions) -> String {
+  let text = if options.expand_ligatures.unwrap_or(false) {
+    expand_ligatures(&text)
+  } else {
+    text
+  };
+  match options.unicode_normalization {
+    Some(UnicodeNormalization::Nfc) => text.nfc().collect(),
+    Some(UnicodeNormalization::Nfkc) => text.nfkc().collect(),
+    None => text,
+  }
+}
+
+fn expand_ligatures(text: &str) -> String {
+  let mut result = String::with_capacity(text.len());
+  for c in text.chars() {
+    match c {
+      'ﬀ' => result.push_str("ff"),
+      'ﬁ' => result.push_str("fi"),
+      'ﬂ' => result.push_str("fl"),
+      'ﬃ' => result.push_str("ffi"),
+      'ﬄ' => result.push_str("ffl"),
+      'ﬅ' | 'ﬆ' => result.push_str("st"),
+      _ => result.push(c),
+    }
+  }
+  result
+}
+
+/// ISO 639-3 code of text language, unreliable detection of short or mixed text is skipped
+pub fn detect_language(text: &str) -> Option<String> {
+  whatlang::detect(text)
+    .filter(|info| info.is_reliable())
+    .map(|info| info.lang().code().to_owned())
+}
+
+pub fn text_object_style(text_page: &PdfPageText, text: &PdfPageTextObject) -> TextStyle {
+  let font = text.font();
+  // base font name keeps style suffixes like "-BoldItalic" which font family drops
+  let chars = text_page.chars();
+  let font_name = text
+    .bounds()
+    .ok()
+    .and_then(|b| {
+      // first object character
+      chars.get_char_near_point(
+        b.left(),
+        b.width() / 2.0,
+        b.bottom() + b.height() / 2.0,
+        b.height() / 2.0,
+      )
+    })
+    .map(|char| char.font_name())
+    .filter(|name| !name.is_empty())
+    .unwrap_or_else(|| font.family());
+  // drop subset prefix like "ABCDEF+"
+  let font_name = match font_name.split_once('+') {
+    Some((prefix, name)) if prefix.len() == 6 => name.to_owned(),
+    _ => font_name,
+  };
+
+  // font descriptor weight is often missing or normal for bold fonts, fall back to font name
+  let font_weight = match font.weight().map(font_weight_value) {
+    _ if font.is_bold_reenforced() || font_name.contains("Bold") => 700,
+    Ok(weight) => weight,
+    Err(_) => 400,
+  };
+  let is_italic = font.is_italic()
+    || font.italic_angle().is_ok_and(|angle| angle != 0)
+    || font_name.contains("Italic")
+    || font_name.contains("Oblique");
+
+  let fill_color = text.fill_color().unwrap_or(PdfColor::BLACK);
+
+  TextStyle {
+    font_size: text.scaled_font_size().value as f64,
+    font_name,
+    font_weight,
+    is_italic,
+    fill_color: format!("#{}", fill_color.to_hex()),
+  }
+}
+
+fn font_weight_value(weight: PdfFontWeight) -> u32 {
+  match weight {
+    PdfFontWeight::Weight100 => 100,
+    PdfFontWeight::Weight200 => 200,
+    PdfFontWeight::Weight300 => 300,
+    PdfFontWeight::Weight400Normal => 400,
+    PdfFontWeight::Weight500 => 500,
+    PdfFontWeight::Weight600 => 600,
+    PdfFontWeight::Weight700Bold => 700,
+    PdfFontWeight::Weight800 => 800,
+    PdfFontWeight::Weight900 => 900,
+    PdfFontWeight::Custom(weight) => weight,
+  }
+}
@@ -0,0 +1,152 @@
+use crate::errors::{ErrorCode, PdfError};
+use crate::objects::{decrypted_data, find, inflate, reference_value, PdfObjects};
+use crate::{get_pdfium, load_document, run_blocking, RawDocument};
+
+static PACKET_START: &[u8] = b"<?xpacket begin=";
+static PACKET_END: &[u8] = b"<?xpacket end=";
+
+#[napi(object)]
+/// XMP metadata packet with its Dublin Core fields
+pub struct XmpMetadata {
+  /// Raw XMP packet
+  pub packet: String,
+  pub title: Option<String>,
+  pub creators: Vec<String>,
+  pub description: Option<String>,
+  /// Keywords
+  pub subjects: Vec<String>,
+  pub rights: Option<String>,
+}
+
+#[napi(catch_unwind)]
+/// Read XMP metadata packet of the document catalog
+pub async fn get_xmp_metadata(
+  // Path to pdfium library bindings
+  pdfium_dir: String,
+  pdf_path: String,
+) -> napi::Result<Option<XmpMetadata>> {
+  run_blocking(move || {
+    let pdfium = &get_pdfium(&pdfium_dir)?;
+    // fail with the reason reported by pdfium-render before reading the document directly
+    drop(load_document(pdfium, &pdf_path)?);
+
+    // document saved through pdfium has its metadata stream decrypted and the catalog written
+    // outside of compressed object streams
+    let raw_document = RawDocument::new(pdfium.bindings(), &pdf_path);
+    let data = decrypted_data(&raw_document).ok_or_else(|| {
+      napi::Error::from(PdfError::new(
+        ErrorCode::LoadFailed,
+        "Failed to read pdf document",
+      ))
+    })?;
+    let stream = PdfObjects::parse(&data).and_then(|objects| {
+      let metadata = reference_value(&objects.catalog()?, "/Metadata")?;
+      let stream = objects.decoded_stream(metadata)?;
+      // pdfium writes metadata streams without their filter but keeps compressed data
+      Some(inflate(&stream).unwrap_or(stream))
+    });
+    let stream = match stream {
+      Some(stream) => stream,
+      None => return Ok(None),
+    };
+
+    // packet wrapper is optional and may be followed by padding
+    let start = find(&stream, PACKET_START).unwrap_or(0);
+    let end = find(&stream[start..], PACKET_END)
+      .and_then(|end| find(&stream[start + end..], b"?>").map(|close| start + end + close + 2))
+      .unwrap_or(stream.len());
+    let packet = String::from_utf8_lossy(&stream[start..end])
+      .trim()
+      .to_owned();
+
+    Ok(Some(XmpMetadata {
+      title: property_values(&packet, "dc:title").into_iter().next(),
+      creators: property_values(&packet, "dc:creator"),
+      description: property_values(&packet, "dc:description")
+        .into_iter()
+        .next(),
+      subjects: property_values(&packet, "dc:subject"),
+      rights: property_values(&packet, "dc:rights").into_iter().next(),
+      packet,
+    }))
+  })
+  .await
+}
+
+/// Property array items or simple value written as element or attribute
+fn property_values(packet: &str, name: &str) -> Vec<String> {
+  let open = format!("<{}", name);
+  let close = format!("</{}>", name);
+
+  let mut search_from = 0;
+  while let Some(start) = packet[search_from..].find(&open).map(|i| search_from + i) {
+    search_from = start + open.len();
+    let rest = &packet[search_from..];
+    // skip longer names sharing the prefix
+    if !rest.starts_with(['>', ' ', '\t', '\r', '\n', '/']) {
+      continue;
+    }
+    let tag_end = match rest.find('>') {
+      Some(tag_end) => tag_end,
+      None => break,
+    };
+    if rest[..tag_end].ends_with('/') {
+      continue;
+    }
+    let content = match rest[tag_end + 1..].find(&close) {
+      Some(end) => &rest[tag_end + 1..tag_end + 1 + end],
+      None => break,
+    };
+
+    let items = element_texts(content, "rdf:li");
+    if !items.is_empty() {
+      return items;
+    }
+    let text = unescape(content.trim());
+    if !text.is_empty() {
+      return vec![text];
+    }
+  }
+
+  // simple values can be written as description attributes
+  let attribute = format!(" {}=\"", name);
+  packet
+    .find(&attribute)
+    .and_then(|start| {
+      let value = &packet[start + attribute.len()..];
+      value.find('"').map(|end| unescape(&value[..end]))
+    })
+    .into_iter()
+    .collect()
+}
+
+fn element_texts(content: &str, name: &str) -> Vec<String> {
+  let open = format!("<{}", name);
+  let close = format!("</{}>", name);
+
+  let mut result: Vec<String> = vec![];
+  let mut rest = content;
+  while let Some(start) = rest.find(&open) {
+    rest = &rest[start + open.len()..];
+    let tag_end = match rest.find('>') {
+      Some(tag_end) => tag_end,
+      None => break,
+    };
+    let end = match rest.find(&close) {
+      Some(end) if end > tag_end => end,
+      _ => break,
+    };
+    result.push(unescape(rest[tag_end + 1..end].trim()));
+    rest = &rest[end + close.len()..];
+  }
+  result
+}
+
+fn unescape(text: &str) -> String {
+  text
+    .replace("&lt;", "<")
+    .replace("&gt;", ">")
+    .replace("&quot;", "\"")
+    .replace("&apos;", "'")
+    .replace("&amp;", "&")
+}